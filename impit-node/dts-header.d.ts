@@ -24,6 +24,8 @@ export declare class ProxyTunnelError extends ProxyError {
 }
 export declare class ProxyAuthRequired extends ProxyError {}
 export declare class UnsupportedProtocol extends TransportError {}
+/** Thrown without sending the request while the circuit breaker for the host is open. */
+export declare class CircuitOpen extends TransportError {}
 export declare class DecodingError extends RequestError {}
 export declare class TooManyRedirects extends RequestError {}
 export declare class HTTPStatusError extends HTTPError {}
@@ -25,6 +25,7 @@ class ProxyTunnelError extends ProxyError {
 }
 class ProxyAuthRequired extends ProxyError {}
 class UnsupportedProtocol extends TransportError {}
+class CircuitOpen extends TransportError {}
 class DecodingError extends RequestError {}
 class TooManyRedirects extends RequestError {}
 class HTTPStatusError extends HTTPError {}
@@ -57,6 +58,7 @@ const errorClassMap = {
     ProxyTunnelError,
     ProxyAuthRequired,
     UnsupportedProtocol,
+    CircuitOpen,
     DecodingError,
     TooManyRedirects,
     HTTPStatusError,
@@ -67,6 +69,8 @@ const errorClassMap = {
     ResponseNotRead,
     RequestNotRead,
     StreamClosed,
+    // The rejected redirects (`redirect: 'error'`) fail with a `TypeError`, like in the Fetch API.
+    TypeError,
 };
 
 function rethrowNativeError(err) {
@@ -104,6 +108,7 @@ module.exports = {
     ProxyTunnelError,
     ProxyAuthRequired,
     UnsupportedProtocol,
+    CircuitOpen,
     DecodingError,
     TooManyRedirects,
     HTTPStatusError,
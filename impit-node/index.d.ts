@@ -24,6 +24,8 @@ export declare class ProxyTunnelError extends ProxyError {
 }
 export declare class ProxyAuthRequired extends ProxyError {}
 export declare class UnsupportedProtocol extends TransportError {}
+/** Thrown without sending the request while the circuit breaker for the host is open. */
+export declare class CircuitOpen extends TransportError {}
 export declare class DecodingError extends RequestError {}
 export declare class TooManyRedirects extends RequestError {}
 export declare class HTTPStatusError extends HTTPError {}
@@ -100,6 +102,21 @@ export declare class Impit {
    * ```
    */
   fetch(resource: string | URL | Request, init?: RequestInit): Promise<ImpitResponse>
+  /**
+   * Returns the state of the circuit breaker for the given host.
+   *
+   * The circuits are always `'closed'` unless {@link ImpitOptions.circuitBreaker} is set.
+   *
+   * @example
+   * ```ts
+   * const impit = new Impit({ circuitBreaker: { failureThreshold: 3 } });
+   *
+   * if (impit.circuitState('example.com') === 'open') {
+   *     // Skip the host for now
+   * }
+   * ```
+   */
+  circuitState(host: string): CircuitState
 }
 export type ImpitWrapper = Impit
 
@@ -143,6 +160,20 @@ export declare class ImpitResponse {
    * In case of redirects, this will be the final URL after all redirects have been followed.
    */
   url: string
+  /**
+   * The local IP address the request was sent from.
+   *
+   * Only available if {@link ImpitOptions.localAddress} or {@link ImpitOptions.localAddressPool} is set.
+   */
+  sourceAddress?: string
+  /** `true` if the response is the result of following one or more redirects. */
+  redirected: boolean
+  /**
+   * The redirect responses followed before this response, oldest first.
+   *
+   * Empty if no redirects were followed.
+   */
+  redirectChain: Array<RedirectHop>
   /** @ignore */
   decodeBuffer(buffer: Buffer): string
   /**
@@ -246,6 +277,20 @@ export declare class ImpitResponse {
   abort(): void
 }
 
+/** A redirect response followed before the final response. */
+export interface RedirectHop {
+  /** The method of the request that was redirected. */
+  method: string
+  /** The URL of the request that was redirected. */
+  url: string
+  /** HTTP status code of the redirect response. */
+  status: number
+  /** HTTP headers of the redirect response, including the `Location` and `Set-Cookie` headers. */
+  headers: [string, string][]
+  /** Time between sending the request and receiving the redirect response headers, in milliseconds. */
+  elapsed: number
+}
+
 /**
  * Supported browsers for emulation.
  *
@@ -275,6 +320,93 @@ export type Browser =  'chrome'|
 'okhttp5'|
 'ios18';
 
+/** Which IP versions are used for the connections, and in which order they are tried. */
+export type IpFamily =  /** Both IPv6 and IPv4 are used, IPv6 addresses are tried first (like in the browsers). */
+'prefer-ipv6'|
+/** Both IPv4 and IPv6 are used, IPv4 addresses are tried first. */
+'prefer-ipv4'|
+/** Only IPv4 addresses are used. */
+'ipv4-only'|
+/** Only IPv6 addresses are used. */
+'ipv6-only';
+
+/** How often a new local address is picked from {@link ImpitOptions.localAddressPool}. */
+export type LocalAddressSelection =  /** Every request is sent from a new address. */
+'request'|
+/** Every host gets its own address, used for all the requests to this host. */
+'host'|
+/** One address is picked for the whole {@link Impit} instance. */
+'session';
+
+/**
+ * Options for the circuit breaker.
+ *
+ * After {@link CircuitBreakerOptions.failureThreshold} consecutive failed requests to a host (connection errors,
+ * timeouts or responses with one of the {@link CircuitBreakerOptions.statusCodes}), the requests to the host fail
+ * right away with a `CircuitOpen` error. After the {@link CircuitBreakerOptions.cooldown}, a few probe requests are
+ * let through - if they succeed, the requests are sent normally again.
+ */
+export interface CircuitBreakerOptions {
+  /**
+   * Number of consecutive failures that opens the circuit.
+   *
+   * @default `5`
+   */
+  failureThreshold?: number
+  /**
+   * How long (in milliseconds) the circuit stays open before the probe requests are sent.
+   *
+   * @default `30000`
+   */
+  cooldown?: number
+  /**
+   * Number of probe requests let through (and required to succeed) after the cooldown.
+   *
+   * @default `1`
+   */
+  halfOpenProbes?: number
+  /**
+   * Response status codes that count as failures.
+   *
+   * @default `[500, 502, 503, 504]`
+   */
+  statusCodes?: Array<number>
+}
+
+/** The state of the circuit breaker for a host, see {@link Impit.circuitState}. */
+export type CircuitState =  /** The requests are sent normally. */
+'closed'|
+/** The requests fail right away until the cooldown passes. */
+'open'|
+/** A few probe requests are let through to find out whether the host has recovered. */
+'half-open';
+
+/** What the requests are grouped by for {@link ImpitOptions.rateLimit} and {@link ImpitOptions.maxConcurrency}. */
+export type LimitKey =  /** Every hostname has its own limit. */
+'host'|
+/** The hosts under the same registrable domain (e.g. `www.example.co.uk` and `api.example.co.uk`) share a limit. */
+'domain'|
+/** All the requests share a limit. */
+'proxy';
+
+/** How much of the previous page's URL is sent in the `Referer` header, see {@link ImpitOptions.referrerPolicy}. */
+export type ReferrerPolicy =  /** Never send the `Referer` header. */
+'no-referrer'|
+/** Send the full URL, unless the request goes from `https` to `http`. */
+'no-referrer-when-downgrade'|
+/** Send the full URL to the same origin, nothing to other origins. */
+'same-origin'|
+/** Send only the origin. */
+'origin'|
+/** Send only the origin, unless the request goes from `https` to `http`. */
+'strict-origin'|
+/** Send the full URL to the same origin, only the origin to other origins. */
+'origin-when-cross-origin'|
+/** Send the full URL to the same origin, only the origin to other origins, nothing from `https` to `http`. */
+'strict-origin-when-cross-origin'|
+/** Always send the full URL. */
+'unsafe-url';
+
 export type HttpMethod =  'GET'|
 'POST'|
 'PUT'|
@@ -284,6 +416,20 @@ export type HttpMethod =  'GET'|
 'OPTIONS'|
 'TRACE';
 
+/** What a request is fetching, see {@link RequestInit.destination}. */
+export type RequestDestination =  /** A top-level navigation, e.g. typing the URL into the address bar or clicking a link. */
+'document'|
+/** A navigation inside an `<iframe>`. */
+'iframe'|
+/** A `fetch()` call or an `XMLHttpRequest`. */
+'fetch'|
+/** A `<script>` element. */
+'script'|
+/** A stylesheet, e.g. a `<link rel="stylesheet">` element. */
+'style'|
+/** An `<img>` element or a CSS background image. */
+'image';
+
 /**
  * Options for configuring an {@link Impit} instance.
  *
@@ -318,7 +464,8 @@ export interface ImpitOptions {
    *
    * Supports HTTP, HTTPS, SOCKS4 and SOCKS5 proxies.
    *
-   * **Warning:** Not supported when HTTP/3 is enabled.
+   * With {@link ImpitOptions.http3} enabled, HTTP/3 traffic is only relayed through SOCKS5 proxies supporting `UDP ASSOCIATE`.
+   * Other proxies (and SOCKS5 proxies refusing UDP) make the requests fall back to TCP, always through the proxy.
    *
    * @default `undefined` (no proxy)
    */
@@ -328,7 +475,7 @@ export interface ImpitOptions {
   /**
    * Enable HTTP/3 support.
    *
-   * **Warning:** Proxies are not supported when HTTP/3 is enabled.
+   * When a proxy is set, HTTP/3 is only used with SOCKS5 proxies supporting `UDP ASSOCIATE`. See {@link ImpitOptions.proxyUrl}.
    *
    * @default `false`
    */
@@ -342,15 +489,18 @@ export interface ImpitOptions {
   /**
    * Maximum number of redirects to follow.
    *
-   * If this number is exceeded, the request will be rejected with an error.
+   * If this number is exceeded, the request will be rejected with a `TooManyRedirects` error.
    *
-   * @default `10`
+   * @default `20`
    */
   maxRedirects?: number
   /**
    * Pass a {@link https://github.com/salesforce/tough-cookie | `ToughCookie`} instance to Impit.
    *
-   * This `impit` instance will use the provided cookie jar for both storing and retrieving cookies.
+   * This `impit` instance will use the provided cookie jar for both storing and retrieving cookies,
+   * including the cookies set by the redirect responses.
+   *
+   * An error thrown by the jar's `getCookieString` fails the request.
    *
    * @default `undefined` (no cookie jar, i.e., cookies are not stored or sent across requests)
    */
@@ -365,11 +515,22 @@ cookieJar?: { setCookie: (cookie: string, url: string, cb?: any) => Promise<void
  * Header matching is **case-insensitive** — for example, setting `user-agent` here will override
  * the impersonation `User-Agent` header.
  *
- * To remove an impersonated header, pass an empty string as the value.
+ * To remove an impersonated header, pass `null` as the value. An empty string sends the header with an empty value,
+ * an array of values or a repeated name in an array of tuples sends the header several times.
+ * **Breaking change:** an empty string used to remove the header, pass `null` for that instead.
  *
  * @default `undefined` (no additional headers)
  */
-headers?: Headers | Record<string, string> | [string, string][]
+headers?: Headers | Record<string, string | string[] | null> | [string, string | null][]
+/**
+ * Where the custom headers that aren't part of the impersonated browser's headers are sent:
+ * `'start'`, `'end'`, `'before:<header>'` or `'after:<header>'`.
+ *
+ * The custom headers overriding the browser's headers always keep the browser's order.
+ *
+ * @default `'end'`
+ */
+headerPosition?: string
 /**
  * Local address to bind the client to. Useful for testing purposes or when you want to bind the client to a specific network interface.
  *
@@ -378,6 +539,201 @@ headers?: Headers | Record<string, string> | [string, string][]
  * @default `undefined` (the OS will choose the local address)
  */
 localAddress?: string
+/**
+ * A pool of local addresses to send the requests from.
+ *
+ * Every entry is either an IP address or a CIDR prefix (e.g. `2001:db8::/48`). The entries are used in a round-robin fashion,
+ * with a random address picked inside each prefix. How often a new address is picked is set by {@link ImpitOptions.localAddressSelection}.
+ *
+ * The address a request was sent from is available as {@link ImpitResponse.sourceAddress}.
+ * Takes precedence over {@link ImpitOptions.localAddress}.
+ *
+ * @default `undefined` (no pool)
+ */
+localAddressPool?: Array<string>
+/**
+ * How often a new address is picked from {@link ImpitOptions.localAddressPool}.
+ *
+ * @default `'session'`
+ */
+localAddressSelection?: LocalAddressSelection
+/**
+ * Name of the network interface to send the requests through (e.g. `eth1` or `wg0`).
+ *
+ * Applies to both the TCP connections and the QUIC sockets used for HTTP/3. Only supported on Linux.
+ *
+ * @default `undefined` (the OS will choose the interface)
+ */
+interface?: string
+/**
+ * Static DNS overrides in the curl's `--resolve` format, i.e. `host:port:address[,address...]` (e.g. `example.com:443:127.0.0.1`).
+ *
+ * Like in curl, an override only applies to the connections to its port (or to any port with `*`).
+ *
+ * @default `undefined` (no overrides)
+ */
+resolve?: Array<string>
+/**
+ * Nameservers to use instead of the system ones.
+ *
+ * Supports plain DNS (`8.8.8.8`), DNS-over-TLS (`tls://1.1.1.1`) and DNS-over-HTTPS (`https://1.1.1.1/dns-query`).
+ * The configured resolver is used both for opening connections and for the HTTP/3 discovery.
+ *
+ * @default `undefined` (the system resolver is used)
+ */
+nameservers?: Array<string>
+/**
+ * Which IP versions are used for the connections, and in which order they are tried.
+ *
+ * @default `'prefer-ipv6'`
+ */
+ipFamily?: IpFamily
+/**
+ * How long (in milliseconds) to wait for a connection attempt before racing it with the next address (Happy Eyeballs).
+ *
+ * Applies to the HTTP/3 connections, the TCP connections always use the delay of 300 ms.
+ *
+ * @default `300`
+ */
+connectionAttemptDelay?: number
+/**
+ * Rules to open the connections for some hosts to a different host or port, in the curl's `--connect-to` format,
+ * i.e. `host:port:connect_host:connect_port` (e.g. `example.com:443:origin.example.net:8443`).
+ *
+ * Empty fields match any host (or port) on the left side and keep the original host (or port) on the right side.
+ * The requests keep the `Host` header, the TLS server name and the cookies of the URL host.
+ *
+ * @default `undefined` (no rules)
+ */
+connectTo?: Array<string>
+/**
+ * Server name to send in the TLS handshake (SNI) instead of the URL hostname.
+ *
+ * The server certificate is verified against this name. Over TCP, these requests are only sent over HTTP/1.1,
+ * with the URL hostname in the `Host` header.
+ *
+ * @default `undefined` (the URL hostname is sent)
+ */
+serverName?: string
+/**
+ * Set to `false` to send the TLS handshakes without the SNI extension.
+ *
+ * @default `true`
+ */
+sni?: boolean
+/**
+ * How long (in milliseconds) the QUIC connection is waited for before the request is sent over TCP instead.
+ *
+ * Only applies to the origins discovered to support HTTP/3 with {@link ImpitOptions.http3} enabled. After a QUIC failure,
+ * the requests to the origin are sent over TCP for a while.
+ *
+ * @default `300`
+ */
+quicHeadStart?: number
+/**
+ * Which failed requests are retried, and how long to wait between the attempts.
+ *
+ * @default `undefined` (the requests are not retried)
+ */
+retry?: RetryOptions
+/**
+ * Maximum number of requests started per second, can be a fraction (e.g. `0.5` for one request every two seconds).
+ *
+ * The requests over the limit wait in a queue, see {@link ImpitOptions.queueTimeout}.
+ *
+ * @default `undefined` (no limit)
+ */
+rateLimit?: number
+/**
+ * Maximum number of requests in flight at the same time.
+ *
+ * A request holds its slot until its response body is read. The requests over the limit wait in a queue,
+ * see {@link ImpitOptions.queueTimeout}.
+ *
+ * @default `undefined` (no limit)
+ */
+maxConcurrency?: number
+/**
+ * What the requests are grouped by for {@link ImpitOptions.rateLimit} and {@link ImpitOptions.maxConcurrency}.
+ *
+ * @default `'host'`
+ */
+limitBy?: LimitKey
+/**
+ * How long (in milliseconds) a request can wait in the queue for the rate and concurrency limits before failing.
+ *
+ * @default `undefined` (the requests wait for as long as needed)
+ */
+queueTimeout?: number
+/**
+ * Stops sending requests to the hosts that keep failing, see {@link CircuitBreakerOptions}.
+ *
+ * @default `undefined` (no circuit breaker)
+ */
+circuitBreaker?: CircuitBreakerOptions
+/**
+ * Sends every request as a navigation from the previously visited page, like a browser tab.
+ *
+ * The requests then carry the `Referer` header and a matching `Sec-Fetch-Site` header (`same-origin`, `same-site`
+ * or `cross-site`, computed with the Public Suffix List) instead of looking like URLs typed into the address bar.
+ *
+ * @default `false`
+ */
+trackNavigation?: boolean
+/**
+ * Referrer policy of the visited pages without a `Referrer-Policy` header, used with {@link ImpitOptions.trackNavigation}.
+ *
+ * @default `'strict-origin-when-cross-origin'`
+ */
+referrerPolicy?: ReferrerPolicy
+}
+
+/**
+ * Options for retrying the failed requests.
+ *
+ * The requests failing to connect (or refused by an HTTP/2 server before processing) are retried for any method,
+ * the requests dropped by an HTTP/2 server shutting down (`GOAWAY`) only for the idempotent ones.
+ * The responses with one of the {@link RetryOptions.statusCodes} are only retried for the {@link RetryOptions.methods}.
+ * Streamed request bodies are never sent twice.
+ */
+export interface RetryOptions {
+  /**
+   * Maximum number of retries after the first attempt.
+   *
+   * @default `2`
+   */
+  maxRetries?: number
+  /**
+   * Response status codes to retry.
+   *
+   * @default `[429, 503]`
+   */
+  statusCodes?: Array<number>
+  /**
+   * Methods whose requests are retried after a response with one of the {@link RetryOptions.statusCodes}.
+   *
+   * @default `['GET', 'HEAD', 'OPTIONS', 'TRACE', 'PUT', 'DELETE']`
+   */
+  methods?: Array<HttpMethod>
+  /**
+   * Delay before the first retry in milliseconds, doubled for every further retry (with a random jitter).
+   *
+   * @default `500`
+   */
+  backoff?: number
+  /**
+   * Longest delay between two attempts in milliseconds.
+   *
+   * @default `30000`
+   */
+  maxBackoff?: number
+  /**
+   * Whether to wait as long as the `Retry-After` response header asks for. Responses asking for a longer delay
+   * than {@link RetryOptions.maxBackoff} are not retried.
+   *
+   * @default `true`
+   */
+  respectRetryAfter?: boolean
 }
 
 /**
@@ -393,9 +749,11 @@ export interface RequestInit {
   /**
    * HTTP method to use for the request. Default is `GET`.
    *
-   * Can be one of: `GET`, `POST`, `PUT`, `DELETE`, `PATCH`, `HEAD`, `OPTIONS`.
+   * Besides the standard methods, any valid HTTP token (e.g. `PROPFIND`, `PURGE` or `QUERY`) can be used.
+   * The standard methods are matched case-insensitively, the other methods are sent exactly as they are passed.
+   * Like in the browsers, `CONNECT`, `TRACE` and `TRACK` are rejected.
    */
-  method?: HttpMethod
+  method?: HttpMethod | (string & {})
   /**
    * Additional headers to include in the request.
    *
@@ -406,15 +764,35 @@ export interface RequestInit {
    * Header matching is **case-insensitive** — for example, setting `user-agent` here will override
    * the impersonation `User-Agent` header.
    *
-   * To remove an impersonated header, pass an empty string as the value.
+   * To remove an impersonated header, pass `null` as the value. An empty string sends the header with an empty value,
+   * an array of values or a repeated name in an array of tuples sends the header several times.
+   * **Breaking change:** an empty string used to remove the header, pass `null` for that instead.
    */
-  headers?: Headers | Record<string, string> | [string, string][]
+  headers?: Headers | Record<string, string | string[] | null> | [string, string | null][]
   /** Request body. Can be a string, Buffer, ArrayBuffer, TypedArray, DataView, Blob, File, URLSearchParams, FormData or ReadableStream. */
   body?: string | ArrayBuffer | Uint8Array | DataView | Blob | File | URLSearchParams | FormData | ReadableStream
   /** Request timeout in milliseconds. Overrides the Impit-wide timeout option from {@link ImpitOptions.timeout}. */
   timeout?: number
   /** Force the request to use HTTP/3. If the server doesn't expect HTTP/3 or the Impit instance doesn't have HTTP/3 enabled (via the {@link ImpitOptions.http3} option), the request will fail. */
   forceHttp3?: boolean
+  /**
+   * Rules to open the connection for this request to a different host or port, in the curl's `--connect-to` format.
+   *
+   * These are checked before the rules from {@link ImpitOptions.connectTo}.
+   */
+  connectTo?: Array<string>
+  /** Server name to send in the TLS handshake (SNI) for this request. Overrides {@link ImpitOptions.serverName}. */
+  serverName?: string
+  /** Set to `false` to send this request without the SNI extension. Overrides {@link ImpitOptions.sni}. */
+  sni?: boolean
+  /** Retry options for this request. Overrides {@link ImpitOptions.retry}. */
+  retry?: RetryOptions
+  /**
+   * What the request is fetching. Adjusts the impersonated `Accept`, `Sec-Fetch-*`, `Upgrade-Insecure-Requests` and `Priority` headers.
+   *
+   * Use `'fetch'` for API calls, so they don't look like page navigations. Default is `'document'`.
+   */
+  destination?: RequestDestination
   /** Abort signal to cancel the request. */
   signal?: AbortSignal
   /**
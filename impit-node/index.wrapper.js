@@ -29,18 +29,43 @@ class ResponsePatches {
 }
 
 function canonicalizeHeaders(headers) {
+    let entries = [];
     if (headers instanceof Headers) {
-        return [...headers.entries()];
+        entries = [...headers.entries()];
     } else if (Array.isArray(headers)) {
-        return headers;
+        entries = headers;
     } else if (typeof headers === 'object') {
-        return Object.entries(headers || {});
+        entries = Object.entries(headers || {});
     }
-    return [];
+
+    // An array of values sends the header several times, `null` (or an empty array) removes it.
+    return entries.flatMap(([name, value]) => {
+        if (Array.isArray(value)) {
+            return value.length > 0 ? value.map((item) => [name, item]) : [[name, null]];
+        }
+        return [[name, value ?? null]];
+    });
 }
 
-function isRedirectStatus(status) {
-    return [301, 302, 303, 307, 308].includes(status);
+/**
+ * Adapts the cookie jar to the callbacks the native client calls for every request and response,
+ * including the redirect hops.
+ * @param {{ setCookie: Function, getCookieString: Function }} cookieJar
+ */
+function nativeCookieJar(cookieJar) {
+    return {
+        // The errors thrown by the jar fail the request.
+        getCookieString: async (url) => (await cookieJar.getCookieString?.(url)) ?? '',
+        setCookies: async ([url, ...cookies]) => {
+            for (const cookie of cookies) {
+                try {
+                    await cookieJar.setCookie?.(cookie, url);
+                } catch {
+                    // Ignore cookie parsing errors
+                }
+            }
+        },
+    };
 }
 
 // Stream chunks may be strings (e.g. a ReadableStream or Node stream that wasn't
@@ -60,64 +85,26 @@ function concatUint8Arrays(chunks) {
     return result;
 }
 
-function shouldRewriteRedirectToGet(httpStatus, method) {
-    // See https://github.com/mozilla-firefox/firefox/blob/911b3eec6c5e58a9a49e23aa105e49aa76e00f9c/netwerk/protocol/http/HttpBaseChannel.cpp#L4801
-    if ([301, 302].includes(httpStatus)) {
-        return method === 'POST';
-    }
-
-    if (httpStatus === 303) return method !== 'HEAD';
+const STANDARD_METHODS = ['GET', 'POST', 'PUT', 'DELETE', 'PATCH', 'HEAD', 'OPTIONS', 'TRACE', 'CONNECT'];
 
-    return false;
+/**
+ * Uppercases the standard HTTP methods, the other methods (e.g. `PROPFIND`) are kept as they are.
+ * @param {string | undefined} method
+ */
+function normalizeMethod(method) {
+    if (typeof method !== 'string') return method;
+    const upper = method.toUpperCase();
+    return STANDARD_METHODS.includes(upper) ? upper : method;
 }
 
 class Impit extends native.Impit {
-    #cookieJar;
-    #followRedirects;
-    #maxRedirects;
-
     constructor(options) {
-        // Pass options to native. When cookieJar is provided, pass a truthy value
-        // to signal that JS handles cookies (actual cookie ops happen in JS).
-        // Redirects are always handled in JS layer.
+        // The redirects (and the cookies of every hop) are handled by the native client.
         super({
             ...options,
             headers: canonicalizeHeaders(options?.headers),
+            cookieJar: options?.cookieJar ? nativeCookieJar(options.cookieJar) : undefined,
         });
-
-        this.#cookieJar = options?.cookieJar;
-        this.#followRedirects = options?.followRedirects ?? true;
-        this.#maxRedirects = options?.maxRedirects ?? 20;
-    }
-
-    /**
-     * Get cookies from the cookie jar for a URL
-     * @param {string} url
-     * @returns {Promise<string>}
-     */
-    async #getCookies(url) {
-        try {
-            return (await this.#cookieJar?.getCookieString?.(url)) ?? '';
-        } catch {
-            return '';
-        }
-    }
-
-    /**
-     * Given response headers, set cookies in the cookie jar
-     * @param {Headers} headers
-     * @param {string} url
-     */
-    async #setCookies(headers, url) {
-        if (!this.#cookieJar) return;
-
-        for (const cookie of (headers.getSetCookie?.() ?? [])) {
-            try {
-                await this.#cookieJar.setCookie?.(cookie, url);
-            } catch {
-                // Ignore cookie parsing errors
-            }
-        }
     }
 
     // Taken from https://github.com/nodejs/undici/blob/14e62db0d0cff4bea27357aa5bd14881459b27c7/lib/web/fetch/body.js#L120
@@ -249,18 +236,27 @@ class Impit extends native.Impit {
 
         return {
             url: url,
-            method: options.method,
+            method: normalizeMethod(options.method),
             headers: options.headers,
             body: options.body,
             timeout: options.timeout,
             forceHttp3: options.forceHttp3,
+            connectTo: options.connectTo,
+            serverName: options.serverName,
+            sni: options.sni,
+            retry: options.retry,
+            destination: options.destination,
             signal: options.signal,
             redirect: options.redirect,
         };
     }
 
     async fetch(resource, init) {
-        const { url: initialUrl, signal, redirect, ...options } = await this.#parseFetchOptions(resource, init);
+        const { url, signal, ...options } = await this.#parseFetchOptions(resource, init);
+        // The body of a `GET` request is dropped, rather than rejected by the native client.
+        if ((options.method ?? 'GET') === 'GET') {
+            delete options.body;
+        }
 
         // Check immediately if already aborted (before creating any promises)
         signal?.throwIfAborted();
@@ -272,7 +268,11 @@ class Impit extends native.Impit {
         });
 
         try {
-            return await this.#fetchWithRedirectHandling(initialUrl, options, signal, waitForAbort, redirect);
+            const response = await Promise.race([
+                super.fetch(url, options),
+                waitForAbort,
+            ]);
+            return this.#wrapResponse(response, signal);
         } catch (err) {
             rethrowNativeError(err);
         } finally {
@@ -280,83 +280,6 @@ class Impit extends native.Impit {
         }
     }
 
-    /**
-     * Fetch with manual redirect handling
-     * @param {string} initialUrl
-     * @param {object} options
-     * @param {AbortSignal} signal
-     * @param {Promise} waitForAbort
-     * @param {'follow' | 'manual' | 'error'} [redirect] Per-request redirect mode override
-     */
-    async #fetchWithRedirectHandling(initialUrl, options, signal, waitForAbort, redirect) {
-        let url = initialUrl;
-        let method = options.method || 'GET';
-        let redirectCount = 0;
-        const maxRedirects = this.#maxRedirects;
-        const followRedirects = redirect
-            ? redirect === 'follow'
-            : this.#followRedirects;
-        const errorOnRedirect = redirect === 'error';
-
-        while (true) {
-            signal?.throwIfAborted();
-
-            const headers = [...(options.headers || [])];
-            const hasUserCookie = headers.some(([k]) => k.toLowerCase() === 'cookie');
-
-            if (this.#cookieJar && !hasUserCookie) {
-                const cookieHeader = await this.#getCookies(url);
-                if (cookieHeader) {
-                    headers.push(['Cookie', cookieHeader]);
-                }
-            }
-
-            const response = super.fetch(url, {
-                ...options,
-                method,
-                headers,
-                body: method === 'GET' ? undefined : options.body,
-            });
-
-            const originalResponse = await Promise.race([
-                response,
-                waitForAbort
-            ]);
-
-            const responseHeaders = new Headers(originalResponse.headers);
-
-            if (this.#cookieJar) {
-                await this.#setCookies(responseHeaders, url);
-            }
-
-            if (isRedirectStatus(originalResponse.status)) {
-                if (errorOnRedirect) {
-                    throw new TypeError(`URI requested responds with a redirect, redirect mode is set to 'error': ${url}`);
-                }
-
-                if (followRedirects) {
-                    const location = responseHeaders.get('location');
-
-                    if (!location) {
-                        return this.#wrapResponse(originalResponse, signal);
-                    }
-
-                    redirectCount++;
-                    if (redirectCount > maxRedirects) {
-                        throw new Error(`Maximum redirect limit (${maxRedirects}) exceeded`);
-                    }
-
-                    url = new URL(location, url).toString();
-                    method = shouldRewriteRedirectToGet(originalResponse.status, method) ? 'GET' : method;
-
-                    continue;
-                }
-            }
-
-            return this.#wrapResponse(originalResponse, signal);
-        }
-    }
-
     /**
      * Wrap a native response with JS enhancements
      * @param {object} originalResponse
@@ -1,23 +1,162 @@
-use reqwest::Url;
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex},
+};
 
+use async_trait::async_trait;
+use impit::{errors::ImpitError, middleware::Middleware, request::ImpitRequest};
+use napi::{
+  bindgen_prelude::{Function, Object, Promise},
+  threadsafe_function::ThreadsafeFunction,
+  Status,
+};
 use reqwest::cookie::CookieStore;
 use reqwest::header::HeaderValue;
+use reqwest::{Response, Url};
 
-/// A no-op cookie store implementation.
+type GetCookieString = ThreadsafeFunction<String, Promise<String>, String, Status, false, true>;
+type SetCookies = ThreadsafeFunction<Vec<String>, Promise<()>, Vec<String>, Status, false, true>;
+
+/// Maximum number of URLs whose cookies are kept for the requests in flight.
+const MAX_PREFETCHED_URLS: usize = 1024;
+
+/// A cookie store backed by the cookie jar passed to the `Impit` constructor.
 ///
-/// This struct is only used to silence compiler errors when building `impit` Node bindings,
-/// as cookies are handled in JavaScript when using `impit` from Node.js.
-pub struct NodeCookieJar;
+/// The JS wrapper passes the jar as two async callbacks, `getCookieString(url)` and `setCookies([url, ...cookies])`.
+/// As the [`CookieStore`] methods are synchronous, the jar is also registered as a [`Middleware`]: before every
+/// attempt (including the redirect hops followed by `impit`), it passes the cookies set so far to the jar and fetches
+/// the cookies for the URL of the attempt. The errors thrown by the callbacks fail the request.
+#[derive(Clone)]
+pub struct NodeCookieJar(Arc<Jar>);
+
+struct Jar {
+  get_cookie_string: GetCookieString,
+  set_cookies: SetCookies,
+  /// The cookies fetched for the latest attempts, the newest last.
+  prefetched: Mutex<VecDeque<(Url, Option<HeaderValue>)>>,
+  /// The `setCookies` arguments for the responses whose cookies the jar doesn't have yet.
+  pending: Mutex<VecDeque<Vec<String>>>,
+  /// Keeps the callbacks of the concurrent requests in order.
+  calls: tokio::sync::Mutex<()>,
+}
+
+impl NodeCookieJar {
+  pub fn new(jar: &Object) -> Result<Self, napi::Error> {
+    Ok(NodeCookieJar(Arc::new(Jar {
+      get_cookie_string: jar
+        .get_named_property::<Function<String, Promise<String>>>("getCookieString")?
+        .build_threadsafe_function()
+        .callee_handled::<false>()
+        .weak::<true>()
+        .build()?,
+      set_cookies: jar
+        .get_named_property::<Function<Vec<String>, Promise<()>>>("setCookies")?
+        .build_threadsafe_function()
+        .callee_handled::<false>()
+        .weak::<true>()
+        .build()?,
+      prefetched: Mutex::new(VecDeque::new()),
+      pending: Mutex::new(VecDeque::new()),
+      calls: tokio::sync::Mutex::new(()),
+    })))
+  }
+
+  /// Passes the cookies set by the responses so far to the jar.
+  ///
+  /// The cookies stay pending until the jar accepts them, so a failed call is retried with the next request.
+  async fn store_pending(&self) -> Result<(), ImpitError> {
+    loop {
+      let Some(args) = self.0.pending.lock().unwrap().front().cloned() else {
+        return Ok(());
+      };
+      self
+        .0
+        .set_cookies
+        .call_async(args)
+        .await
+        .map_err(jar_error)?
+        .await
+        .map_err(jar_error)?;
+      self.0.pending.lock().unwrap().pop_front();
+    }
+  }
+
+  /// Fetches the cookies for `url` from the jar, for the [`CookieStore::cookies`] calls of the attempt.
+  async fn prefetch(&self, url: &Url) -> Result<(), ImpitError> {
+    let cookies = self
+      .0
+      .get_cookie_string
+      .call_async(url.to_string())
+      .await
+      .map_err(jar_error)?
+      .await
+      .map_err(jar_error)?;
+    let cookies = match cookies.as_str() {
+      "" => None,
+      value => Some(
+        HeaderValue::from_str(value)
+          .map_err(|_| ImpitError::InvalidHeaderValue(value.to_string()))?,
+      ),
+    };
+
+    let mut prefetched = self.0.prefetched.lock().unwrap();
+    if prefetched.len() >= MAX_PREFETCHED_URLS {
+      prefetched.pop_front();
+    }
+    prefetched.push_back((url.clone(), cookies));
+    Ok(())
+  }
+}
+
+fn jar_error(err: napi::Error) -> ImpitError {
+  ImpitError::BindingPassthroughError(format!("The cookie jar failed: {}", err.reason))
+}
 
 impl CookieStore for NodeCookieJar {
-  fn set_cookies(
-    &self,
-    _cookie_headers: &mut dyn Iterator<Item = &reqwest::header::HeaderValue>,
-    _url: &Url,
-  ) {
+  fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+    let cookies: Vec<String> = cookie_headers
+      .filter_map(|header| header.to_str().ok())
+      .map(str::to_string)
+      .collect();
+    if cookies.is_empty() {
+      return;
+    }
+
+    let mut args = vec![url.to_string()];
+    args.extend(cookies);
+    self.0.pending.lock().unwrap().push_back(args);
+  }
+
+  fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+    self
+      .0
+      .prefetched
+      .lock()
+      .unwrap()
+      .iter()
+      .rev()
+      .find(|(prefetched, _)| prefetched == url)
+      .and_then(|(_, cookies)| cookies.clone())
+  }
+}
+
+#[async_trait]
+impl Middleware for NodeCookieJar {
+  async fn on_request(&self, request: &mut ImpitRequest) -> Result<(), ImpitError> {
+    let _calls = self.0.calls.lock().await;
+    self.store_pending().await?;
+    self.prefetch(&request.url).await
+  }
+
+  async fn on_response(&self, _response: &Response) -> Result<(), ImpitError> {
+    let _calls = self.0.calls.lock().await;
+    self.store_pending().await
   }
 
-  fn cookies(&self, _url: &Url) -> Option<HeaderValue> {
-    None
+  async fn on_error(&self, _url: &Url, _error: &ImpitError) {
+    let _calls = self.0.calls.lock().await;
+    // The request fails with `_error` either way. If the jar fails too, the cookies stay pending
+    // and the next request reports it.
+    let _ = self.store_pending().await;
   }
 }
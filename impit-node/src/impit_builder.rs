@@ -1,14 +1,23 @@
 use std::time::Duration;
 
 use impit::{
-  fingerprint::BrowserFingerprint,
+  circuit_breaker::CircuitBreaker,
+  dns::{DnsConfig, HappyEyeballs, Nameserver},
+  fingerprint::{BrowserFingerprint, HeaderPosition},
   impit::{ImpitBuilder, RedirectBehavior},
+  limits::{ConcurrencyLimit, RateLimit},
+  local_address::LocalAddressPool,
+  request::HeaderOp,
+  retry::RetryPolicy,
+  routing::{ConnectTo, ServerName},
 };
 
 use napi::bindgen_prelude::Object;
 use napi_derive::napi;
 
-use crate::cookies::NodeCookieJar;
+use crate::{cookies::NodeCookieJar, request::HttpMethod};
+
+const DEFAULT_MAX_REDIRECTS: usize = 20;
 
 /// Supported browsers for emulation.
 ///
@@ -40,6 +49,123 @@ pub enum Browser {
   Ios18,
 }
 
+/// How often a new local address is picked from {@link ImpitOptions.localAddressPool}.
+#[napi(string_enum = "lowercase")]
+pub enum LocalAddressSelection {
+  /// Every request is sent from a new address.
+  Request,
+  /// Every host gets its own address, used for all the requests to this host.
+  Host,
+  /// One address is picked for the whole {@link Impit} instance.
+  Session,
+}
+
+impl From<LocalAddressSelection> for impit::local_address::LocalAddressSelection {
+  fn from(val: LocalAddressSelection) -> Self {
+    match val {
+      LocalAddressSelection::Request => Self::PerRequest,
+      LocalAddressSelection::Host => Self::PerHost,
+      LocalAddressSelection::Session => Self::PerSession,
+    }
+  }
+}
+
+/// Which IP versions are used for the connections, and in which order they are tried.
+#[napi(string_enum)]
+pub enum IpFamily {
+  /// Both IPv6 and IPv4 are used, IPv6 addresses are tried first (like in the browsers).
+  #[napi(value = "prefer-ipv6")]
+  PreferIpv6,
+  /// Both IPv4 and IPv6 are used, IPv4 addresses are tried first.
+  #[napi(value = "prefer-ipv4")]
+  PreferIpv4,
+  /// Only IPv4 addresses are used.
+  #[napi(value = "ipv4-only")]
+  Ipv4Only,
+  /// Only IPv6 addresses are used.
+  #[napi(value = "ipv6-only")]
+  Ipv6Only,
+}
+
+impl From<IpFamily> for impit::dns::IpFamily {
+  fn from(val: IpFamily) -> Self {
+    match val {
+      IpFamily::PreferIpv6 => Self::PreferIpv6,
+      IpFamily::PreferIpv4 => Self::PreferIpv4,
+      IpFamily::Ipv4Only => Self::Ipv4Only,
+      IpFamily::Ipv6Only => Self::Ipv6Only,
+    }
+  }
+}
+
+/// What the requests are grouped by for {@link ImpitOptions.rateLimit} and {@link ImpitOptions.maxConcurrency}.
+#[napi(string_enum)]
+pub enum LimitKey {
+  /// Every hostname has its own limit.
+  #[napi(value = "host")]
+  Host,
+  /// The hosts under the same registrable domain (e.g. `www.example.co.uk` and `api.example.co.uk`) share a limit.
+  #[napi(value = "domain")]
+  Domain,
+  /// All the requests share a limit.
+  #[napi(value = "proxy")]
+  Proxy,
+}
+
+impl From<LimitKey> for impit::limits::LimitKey {
+  fn from(val: LimitKey) -> Self {
+    match val {
+      LimitKey::Host => Self::Host,
+      LimitKey::Domain => Self::RegistrableDomain,
+      LimitKey::Proxy => Self::Proxy,
+    }
+  }
+}
+
+/// How much of the previous page's URL is sent in the `Referer` header, see {@link ImpitOptions.referrerPolicy}.
+#[napi(string_enum)]
+pub enum ReferrerPolicy {
+  /// Never send the `Referer` header.
+  #[napi(value = "no-referrer")]
+  NoReferrer,
+  /// Send the full URL, unless the request goes from `https` to `http`.
+  #[napi(value = "no-referrer-when-downgrade")]
+  NoReferrerWhenDowngrade,
+  /// Send the full URL to the same origin, nothing to other origins.
+  #[napi(value = "same-origin")]
+  SameOrigin,
+  /// Send only the origin.
+  #[napi(value = "origin")]
+  Origin,
+  /// Send only the origin, unless the request goes from `https` to `http`.
+  #[napi(value = "strict-origin")]
+  StrictOrigin,
+  /// Send the full URL to the same origin, only the origin to other origins.
+  #[napi(value = "origin-when-cross-origin")]
+  OriginWhenCrossOrigin,
+  /// Send the full URL to the same origin, only the origin to other origins, nothing from `https` to `http`.
+  #[napi(value = "strict-origin-when-cross-origin")]
+  StrictOriginWhenCrossOrigin,
+  /// Always send the full URL.
+  #[napi(value = "unsafe-url")]
+  UnsafeUrl,
+}
+
+impl From<ReferrerPolicy> for impit::navigation::ReferrerPolicy {
+  fn from(val: ReferrerPolicy) -> Self {
+    match val {
+      ReferrerPolicy::NoReferrer => Self::NoReferrer,
+      ReferrerPolicy::NoReferrerWhenDowngrade => Self::NoReferrerWhenDowngrade,
+      ReferrerPolicy::SameOrigin => Self::SameOrigin,
+      ReferrerPolicy::Origin => Self::Origin,
+      ReferrerPolicy::StrictOrigin => Self::StrictOrigin,
+      ReferrerPolicy::OriginWhenCrossOrigin => Self::OriginWhenCrossOrigin,
+      ReferrerPolicy::StrictOriginWhenCrossOrigin => Self::StrictOriginWhenCrossOrigin,
+      ReferrerPolicy::UnsafeUrl => Self::UnsafeUrl,
+    }
+  }
+}
+
 /// Options for configuring an {@link Impit} instance.
 ///
 /// These options allow you to customize the behavior of the Impit instance, including browser emulation, TLS settings, proxy configuration, timeouts, and more.
@@ -67,7 +193,8 @@ pub struct ImpitOptions<'a> {
   ///
   /// Supports HTTP, HTTPS, SOCKS4 and SOCKS5 proxies.
   ///
-  /// **Warning:** Not supported when HTTP/3 is enabled.
+  /// With {@link ImpitOptions.http3} enabled, HTTP/3 traffic is only relayed through SOCKS5 proxies supporting `UDP ASSOCIATE`.
+  /// Other proxies (and SOCKS5 proxies refusing UDP) make the requests fall back to TCP, always through the proxy.
   ///
   /// @default `undefined` (no proxy)
   pub proxy_url: Option<String>,
@@ -75,7 +202,7 @@ pub struct ImpitOptions<'a> {
   pub timeout: Option<u32>,
   /// Enable HTTP/3 support.
   ///
-  /// **Warning:** Proxies are not supported when HTTP/3 is enabled.
+  /// When a proxy is set, HTTP/3 is only used with SOCKS5 proxies supporting `UDP ASSOCIATE`. See {@link ImpitOptions.proxyUrl}.
   ///
   /// @default `false`
   pub http3: Option<bool>,
@@ -85,13 +212,16 @@ pub struct ImpitOptions<'a> {
   pub follow_redirects: Option<bool>,
   /// Maximum number of redirects to follow.
   ///
-  /// If this number is exceeded, the request will be rejected with an error.
+  /// If this number is exceeded, the request will be rejected with a `TooManyRedirects` error.
   ///
-  /// @default `10`
+  /// @default `20`
   pub max_redirects: Option<u32>,
   /// Pass a {@link https://github.com/salesforce/tough-cookie | `ToughCookie`} instance to Impit.
   ///
-  /// This `impit` instance will use the provided cookie jar for both storing and retrieving cookies.
+  /// This `impit` instance will use the provided cookie jar for both storing and retrieving cookies,
+  /// including the cookies set by the redirect responses.
+  ///
+  /// An error thrown by the jar's `getCookieString` fails the request.
   ///
   /// @default `undefined` (no cookie jar, i.e., cookies are not stored or sent across requests)
   #[napi(
@@ -107,17 +237,224 @@ pub struct ImpitOptions<'a> {
   /// Header matching is **case-insensitive** — for example, setting `user-agent` here will override
   /// the impersonation `User-Agent` header.
   ///
-  /// To remove an impersonated header, pass an empty string as the value.
+  /// To remove an impersonated header, pass `null` as the value. An empty string sends the header with an empty value,
+  /// an array of values or a repeated name in an array of tuples sends the header several times.
+  /// **Breaking change:** an empty string used to remove the header, pass `null` for that instead.
   ///
   /// @default `undefined` (no additional headers)
-  #[napi(ts_type = "Headers | Record<string, string> | [string, string][]")]
-  pub headers: Option<Vec<(String, String)>>,
+  #[napi(
+    ts_type = "Headers | Record<string, string | string[] | null> | [string, string | null][]"
+  )]
+  pub headers: Option<Vec<(String, Option<String>)>>,
+  /// Where the custom headers that aren't part of the impersonated browser's headers are sent:
+  /// `'start'`, `'end'`, `'before:<header>'` or `'after:<header>'`.
+  ///
+  /// The custom headers overriding the browser's headers always keep the browser's order.
+  ///
+  /// @default `'end'`
+  pub header_position: Option<String>,
   /// Local address to bind the client to. Useful for testing purposes or when you want to bind the client to a specific network interface.
   ///
   /// Can be an IP address in the format `xxx.xxx.xxx.xxx` (for IPv4) or `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff` (for IPv6).
   ///
   /// @default `undefined` (the OS will choose the local address)
   pub local_address: Option<String>,
+  /// A pool of local addresses to send the requests from.
+  ///
+  /// Every entry is either an IP address or a CIDR prefix (e.g. `2001:db8::/48`). The entries are used in a round-robin fashion,
+  /// with a random address picked inside each prefix. How often a new address is picked is set by {@link ImpitOptions.localAddressSelection}.
+  ///
+  /// The address a request was sent from is available as {@link ImpitResponse.sourceAddress}.
+  /// Takes precedence over {@link ImpitOptions.localAddress}.
+  ///
+  /// @default `undefined` (no pool)
+  pub local_address_pool: Option<Vec<String>>,
+  /// How often a new address is picked from {@link ImpitOptions.localAddressPool}.
+  ///
+  /// @default `'session'`
+  pub local_address_selection: Option<LocalAddressSelection>,
+  /// Name of the network interface to send the requests through (e.g. `eth1` or `wg0`).
+  ///
+  /// Applies to both the TCP connections and the QUIC sockets used for HTTP/3. Only supported on Linux.
+  ///
+  /// @default `undefined` (the OS will choose the interface)
+  pub interface: Option<String>,
+  /// Static DNS overrides in the curl's `--resolve` format, i.e. `host:port:address[,address...]` (e.g. `example.com:443:127.0.0.1`).
+  ///
+  /// Like in curl, an override only applies to the connections to its port (or to any port with `*`).
+  ///
+  /// @default `undefined` (no overrides)
+  pub resolve: Option<Vec<String>>,
+  /// Nameservers to use instead of the system ones.
+  ///
+  /// Supports plain DNS (`8.8.8.8`), DNS-over-TLS (`tls://1.1.1.1`) and DNS-over-HTTPS (`https://1.1.1.1/dns-query`).
+  /// The configured resolver is used both for opening connections and for the HTTP/3 discovery.
+  ///
+  /// @default `undefined` (the system resolver is used)
+  pub nameservers: Option<Vec<String>>,
+  /// Which IP versions are used for the connections, and in which order they are tried.
+  ///
+  /// @default `'prefer-ipv6'`
+  pub ip_family: Option<IpFamily>,
+  /// How long (in milliseconds) to wait for a connection attempt before racing it with the next address (Happy Eyeballs).
+  ///
+  /// Applies to the HTTP/3 connections, the TCP connections always use the delay of 300 ms.
+  ///
+  /// @default `300`
+  pub connection_attempt_delay: Option<u32>,
+  /// Rules to open the connections for some hosts to a different host or port, in the curl's `--connect-to` format,
+  /// i.e. `host:port:connect_host:connect_port` (e.g. `example.com:443:origin.example.net:8443`).
+  ///
+  /// Empty fields match any host (or port) on the left side and keep the original host (or port) on the right side.
+  /// The requests keep the `Host` header, the TLS server name and the cookies of the URL host.
+  ///
+  /// @default `undefined` (no rules)
+  pub connect_to: Option<Vec<String>>,
+  /// Server name to send in the TLS handshake (SNI) instead of the URL hostname.
+  ///
+  /// The server certificate is verified against this name. Over TCP, these requests are only sent over HTTP/1.1,
+  /// with the URL hostname in the `Host` header.
+  ///
+  /// @default `undefined` (the URL hostname is sent)
+  pub server_name: Option<String>,
+  /// Set to `false` to send the TLS handshakes without the SNI extension.
+  ///
+  /// @default `true`
+  pub sni: Option<bool>,
+  /// How long (in milliseconds) the QUIC connection is waited for before the request is sent over TCP instead.
+  ///
+  /// Only applies to the origins discovered to support HTTP/3 with {@link ImpitOptions.http3} enabled. After a QUIC failure,
+  /// the requests to the origin are sent over TCP for a while.
+  ///
+  /// @default `300`
+  pub quic_head_start: Option<u32>,
+  /// Which failed requests are retried, and how long to wait between the attempts.
+  ///
+  /// @default `undefined` (the requests are not retried)
+  pub retry: Option<RetryOptions>,
+  /// Maximum number of requests started per second, can be a fraction (e.g. `0.5` for one request every two seconds).
+  ///
+  /// The requests over the limit wait in a queue, see {@link ImpitOptions.queueTimeout}.
+  ///
+  /// @default `undefined` (no limit)
+  pub rate_limit: Option<f64>,
+  /// Maximum number of requests in flight at the same time.
+  ///
+  /// A request holds its slot until its response body is read. The requests over the limit wait in a queue,
+  /// see {@link ImpitOptions.queueTimeout}.
+  ///
+  /// @default `undefined` (no limit)
+  pub max_concurrency: Option<u32>,
+  /// What the requests are grouped by for {@link ImpitOptions.rateLimit} and {@link ImpitOptions.maxConcurrency}.
+  ///
+  /// @default `'host'`
+  pub limit_by: Option<LimitKey>,
+  /// How long (in milliseconds) a request can wait in the queue for the rate and concurrency limits before failing.
+  ///
+  /// @default `undefined` (the requests wait for as long as needed)
+  pub queue_timeout: Option<u32>,
+  /// Stops sending requests to the hosts that keep failing, see {@link CircuitBreakerOptions}.
+  ///
+  /// @default `undefined` (no circuit breaker)
+  pub circuit_breaker: Option<CircuitBreakerOptions>,
+  /// Sends every request as a navigation from the previously visited page, like a browser tab.
+  ///
+  /// The requests then carry the `Referer` header and a matching `Sec-Fetch-Site` header (`same-origin`, `same-site`
+  /// or `cross-site`, computed with the Public Suffix List) instead of looking like URLs typed into the address bar.
+  ///
+  /// @default `false`
+  pub track_navigation: Option<bool>,
+  /// Referrer policy of the visited pages without a `Referrer-Policy` header, used with {@link ImpitOptions.trackNavigation}.
+  ///
+  /// @default `'strict-origin-when-cross-origin'`
+  pub referrer_policy: Option<ReferrerPolicy>,
+}
+
+/// Options for retrying the failed requests.
+///
+/// The requests failing to connect (or refused by an HTTP/2 server before processing) are retried for any method,
+/// the requests dropped by an HTTP/2 server shutting down (`GOAWAY`) only for the idempotent ones.
+/// The responses with one of the {@link RetryOptions.statusCodes} are only retried for the {@link RetryOptions.methods}.
+/// Streamed request bodies are never sent twice.
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct RetryOptions {
+  /// Maximum number of retries after the first attempt.
+  ///
+  /// @default `2`
+  pub max_retries: Option<u32>,
+  /// Response status codes to retry.
+  ///
+  /// @default `[429, 503]`
+  pub status_codes: Option<Vec<u32>>,
+  /// Methods whose requests are retried after a response with one of the {@link RetryOptions.statusCodes}.
+  ///
+  /// @default `['GET', 'HEAD', 'OPTIONS', 'TRACE', 'PUT', 'DELETE']`
+  pub methods: Option<Vec<HttpMethod>>,
+  /// Delay before the first retry in milliseconds, doubled for every further retry (with a random jitter).
+  ///
+  /// @default `500`
+  pub backoff: Option<u32>,
+  /// Longest delay between two attempts in milliseconds.
+  ///
+  /// @default `30000`
+  pub max_backoff: Option<u32>,
+  /// Whether to wait as long as the `Retry-After` response header asks for. Responses asking for a longer delay
+  /// than {@link RetryOptions.maxBackoff} are not retried.
+  ///
+  /// @default `true`
+  pub respect_retry_after: Option<bool>,
+}
+
+/// Options for the circuit breaker.
+///
+/// After {@link CircuitBreakerOptions.failureThreshold} consecutive failed requests to a host (connection errors,
+/// timeouts or responses with one of the {@link CircuitBreakerOptions.statusCodes}), the requests to the host fail
+/// right away with a `CircuitOpen` error. After the {@link CircuitBreakerOptions.cooldown}, a few probe requests are
+/// let through - if they succeed, the requests are sent normally again.
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct CircuitBreakerOptions {
+  /// Number of consecutive failures that opens the circuit.
+  ///
+  /// @default `5`
+  pub failure_threshold: Option<u32>,
+  /// How long (in milliseconds) the circuit stays open before the probe requests are sent.
+  ///
+  /// @default `30000`
+  pub cooldown: Option<u32>,
+  /// Number of probe requests let through (and required to succeed) after the cooldown.
+  ///
+  /// @default `1`
+  pub half_open_probes: Option<u32>,
+  /// Response status codes that count as failures.
+  ///
+  /// @default `[500, 502, 503, 504]`
+  pub status_codes: Option<Vec<u32>>,
+}
+
+/// The state of the circuit breaker for a host, see {@link Impit.circuitState}.
+#[napi(string_enum)]
+pub enum CircuitState {
+  /// The requests are sent normally.
+  #[napi(value = "closed")]
+  Closed,
+  /// The requests fail right away until the cooldown passes.
+  #[napi(value = "open")]
+  Open,
+  /// A few probe requests are let through to find out whether the host has recovered.
+  #[napi(value = "half-open")]
+  HalfOpen,
+}
+
+impl From<impit::circuit_breaker::CircuitState> for CircuitState {
+  fn from(val: impit::circuit_breaker::CircuitState) -> Self {
+    match val {
+      impit::circuit_breaker::CircuitState::Closed => Self::Closed,
+      impit::circuit_breaker::CircuitState::Open(_) => Self::Open,
+      impit::circuit_breaker::CircuitState::HalfOpen => Self::HalfOpen,
+    }
+  }
 }
 
 impl From<Browser> for BrowserFingerprint {
@@ -152,6 +489,13 @@ impl From<Browser> for BrowserFingerprint {
 }
 
 impl ImpitOptions<'_> {
+  /// The maximum number of redirects to follow, see `max_redirects`.
+  pub fn max_redirects(&self) -> usize {
+    self
+      .max_redirects
+      .map_or(DEFAULT_MAX_REDIRECTS, |max| max as usize)
+  }
+
   pub fn into_builder(self) -> Result<ImpitBuilder<NodeCookieJar>, napi::Error> {
     let mut config: ImpitBuilder<NodeCookieJar> = ImpitBuilder::default();
 
@@ -176,11 +520,29 @@ impl ImpitOptions<'_> {
       }
     }
     if let Some(headers) = self.headers {
-      config = config.with_headers(headers);
+      config = config.with_headers(HeaderOp::from_entries(headers));
     }
+    if let Some(position) = self.header_position {
+      config = config.with_header_position(
+        position
+          .parse::<HeaderPosition>()
+          .map_err(|e| napi::Error::from_reason(e.to_string()))?,
+      );
+    }
+
+    config = config.with_redirect(if self.follow_redirects.unwrap_or(true) {
+      RedirectBehavior::FollowRedirect(self.max_redirects())
+    } else {
+      RedirectBehavior::ManualRedirect
+    });
 
-    // Always use ManualRedirect - redirects are handled in the JS layer
-    config = config.with_redirect(RedirectBehavior::ManualRedirect);
+    if let Some(cookie_jar) = &self.cookie_jar {
+      // The jar's callbacks are async, so the middleware fetches the cookies before every attempt.
+      let cookie_jar = NodeCookieJar::new(cookie_jar)?;
+      config = config
+        .with_cookie_store(cookie_jar.clone())
+        .with_middleware(cookie_jar);
+    }
 
     if let Some(local_address) = self.local_address {
       config = config
@@ -188,6 +550,164 @@ impl ImpitOptions<'_> {
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
     }
 
+    if let Some(local_address_pool) = self.local_address_pool {
+      let pool = LocalAddressPool::new(local_address_pool)
+        .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+      let selection = self
+        .local_address_selection
+        .map(Into::into)
+        .unwrap_or_default();
+
+      config = config.with_local_address_pool(pool, selection);
+    }
+
+    if let Some(interface) = self.interface {
+      config = config.with_interface(interface);
+    }
+
+    if self.resolve.is_some() || self.nameservers.is_some() {
+      let mut dns = DnsConfig::default();
+      for entry in self.resolve.unwrap_or_default() {
+        dns = dns
+          .with_resolve(&entry)
+          .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+      }
+      for nameserver in self.nameservers.unwrap_or_default() {
+        dns = dns.with_nameserver(
+          nameserver
+            .parse::<Nameserver>()
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?,
+        );
+      }
+
+      config = config.with_dns(dns);
+    }
+
+    if self.ip_family.is_some() || self.connection_attempt_delay.is_some() {
+      let defaults = HappyEyeballs::default();
+      config = config.with_happy_eyeballs(HappyEyeballs {
+        ip_family: self.ip_family.map(Into::into).unwrap_or(defaults.ip_family),
+        connection_attempt_delay: self
+          .connection_attempt_delay
+          .map(|delay| Duration::from_millis(delay.into()))
+          .unwrap_or(defaults.connection_attempt_delay),
+      });
+    }
+
+    for rule in connect_to_rules(self.connect_to)? {
+      config = config.with_connect_to(rule);
+    }
+
+    if let Some(server_name) = server_name(self.server_name, self.sni) {
+      config = config.with_server_name(server_name);
+    }
+
+    if let Some(quic_head_start) = self.quic_head_start {
+      config = config.with_quic_head_start(Duration::from_millis(quic_head_start.into()));
+    }
+
+    if let Some(retry) = self.retry {
+      config = config.with_retry(retry_policy(retry));
+    }
+
+    let limit_key: impit::limits::LimitKey = self.limit_by.map(Into::into).unwrap_or_default();
+    if let Some(rate_limit) = self.rate_limit {
+      config = config.with_rate_limit(RateLimit::per_second(limit_key, rate_limit));
+    }
+    if let Some(max_concurrency) = self.max_concurrency {
+      config = config.with_concurrency_limit(ConcurrencyLimit {
+        key: limit_key,
+        max_concurrent: max_concurrency as usize,
+      });
+    }
+    if let Some(queue_timeout) = self.queue_timeout {
+      config = config.with_queue_timeout(Duration::from_millis(queue_timeout.into()));
+    }
+
+    if let Some(options) = self.circuit_breaker {
+      config = config.with_circuit_breaker(circuit_breaker(options));
+    }
+
+    if self.track_navigation == Some(true) {
+      config = config.with_navigation(self.referrer_policy.map(Into::into).unwrap_or_default());
+    }
+
     Ok(config)
   }
 }
+
+/// Parses the `connectTo` rules from the client or request options.
+pub(crate) fn connect_to_rules(rules: Option<Vec<String>>) -> Result<Vec<ConnectTo>, napi::Error> {
+  rules
+    .unwrap_or_default()
+    .iter()
+    .map(|rule| {
+      rule
+        .parse::<ConnectTo>()
+        .map_err(|e| napi::Error::from_reason(e.to_string()))
+    })
+    .collect()
+}
+
+/// Builds the TLS server name from the `serverName` and `sni` options.
+pub(crate) fn server_name(server_name: Option<String>, sni: Option<bool>) -> Option<ServerName> {
+  match (server_name, sni) {
+    (_, Some(false)) => Some(ServerName::Disabled),
+    (Some(server_name), _) => Some(ServerName::Custom(server_name)),
+    (None, _) => None,
+  }
+}
+
+/// Builds the circuit breaker from the `circuitBreaker` client option.
+pub(crate) fn circuit_breaker(options: CircuitBreakerOptions) -> CircuitBreaker {
+  let defaults = CircuitBreaker::default();
+
+  CircuitBreaker {
+    failure_threshold: options
+      .failure_threshold
+      .map_or(defaults.failure_threshold, |threshold| threshold as usize),
+    cooldown: options.cooldown.map_or(defaults.cooldown, |cooldown| {
+      Duration::from_millis(cooldown.into())
+    }),
+    half_open_probes: options
+      .half_open_probes
+      .map_or(defaults.half_open_probes, |probes| probes as usize),
+    statuses: options.status_codes.map_or(defaults.statuses, |statuses| {
+      statuses
+        .into_iter()
+        .filter_map(|status| u16::try_from(status).ok())
+        .collect()
+    }),
+    ..defaults
+  }
+}
+
+/// Builds the retry policy from the `retry` client or request option.
+pub(crate) fn retry_policy(options: RetryOptions) -> RetryPolicy {
+  let defaults = RetryPolicy::default();
+
+  RetryPolicy {
+    max_attempts: options
+      .max_retries
+      .map_or(defaults.max_attempts, |retries| retries as usize + 1),
+    statuses: options.status_codes.map_or(defaults.statuses, |statuses| {
+      statuses
+        .into_iter()
+        .filter_map(|status| u16::try_from(status).ok())
+        .collect()
+    }),
+    methods: options.methods.map_or(defaults.methods, |methods| {
+      methods.into_iter().map(Into::into).collect()
+    }),
+    initial_backoff: options.backoff.map_or(defaults.initial_backoff, |backoff| {
+      Duration::from_millis(backoff.into())
+    }),
+    max_backoff: options.max_backoff.map_or(defaults.max_backoff, |backoff| {
+      Duration::from_millis(backoff.into())
+    }),
+    respect_retry_after: options
+      .respect_retry_after
+      .unwrap_or(defaults.respect_retry_after),
+    ..defaults
+  }
+}
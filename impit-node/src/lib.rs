@@ -1,6 +1,11 @@
 use std::time::Duration;
 
-use impit::{errors::ImpitError, impit::Impit, request::RequestOptions};
+use impit::{
+  errors::ImpitError,
+  impit::{Impit, RedirectBehavior},
+  redirect::{RedirectAction, RedirectPolicy},
+  request::{HeaderOp, RequestOptions},
+};
 use napi::{bindgen_prelude::ObjectFinalize, Env};
 use napi_derive::napi;
 
@@ -12,8 +17,8 @@ mod response;
 
 use self::response::ImpitResponse;
 use cookies::NodeCookieJar;
-use impit_builder::ImpitOptions;
-use request::{HttpMethod, RequestInit};
+use impit_builder::{CircuitState, ImpitOptions};
+use request::RequestInit;
 
 /// The main class of the `impit` package
 ///
@@ -36,6 +41,8 @@ use request::{HttpMethod, RequestInit};
 #[napi(js_name = "Impit", custom_finalize)]
 pub struct ImpitWrapper {
   inner: Impit<NodeCookieJar>,
+  /// The redirect limit for the requests with `redirect: 'follow'`.
+  max_redirects: usize,
 }
 
 impl ObjectFinalize for ImpitWrapper {
@@ -66,7 +73,9 @@ impl ImpitWrapper {
   /// ```
   #[napi(constructor)]
   pub fn new(env: &Env, options: Option<ImpitOptions>) -> Result<Self, napi::Error> {
-    let config = options.unwrap_or_default().into_builder()?;
+    let options = options.unwrap_or_default();
+    let max_redirects = options.max_redirects();
+    let config = options.into_builder()?;
 
     let _ = env.adjust_external_memory(500 * 1024);
 
@@ -81,10 +90,28 @@ impl ImpitWrapper {
             format!("Failed to build Impit instance: {e}"),
           )
         })?,
+        max_redirects,
       })
     })
   }
 
+  /// Returns the state of the circuit breaker for the given host.
+  ///
+  /// The circuits are always `'closed'` unless {@link ImpitOptions.circuitBreaker} is set.
+  ///
+  /// @example
+  /// ```ts
+  /// const impit = new Impit({ circuitBreaker: { failureThreshold: 3 } });
+  ///
+  /// if (impit.circuitState('example.com') === 'open') {
+  ///     // Skip the host for now
+  /// }
+  /// ```
+  #[napi]
+  pub fn circuit_state(&self, host: String) -> CircuitState {
+    self.inner.circuit_state(&host).into()
+  }
+
   #[napi(js_name = "getMultipartBoundary", skip_typescript)]
   pub fn get_multipart_boundary(&self) -> String {
     self.inner.generate_multipart_boundary()
@@ -119,8 +146,8 @@ impl ImpitWrapper {
     let request_options = Some(RequestOptions {
       headers: request_init
         .as_ref()
-        .and_then(|init| init.headers.as_ref())
-        .cloned()
+        .and_then(|init| init.headers.clone())
+        .map(HeaderOp::from_entries)
         .unwrap_or_default(),
       timeout: request_init
         .as_ref()
@@ -130,32 +157,52 @@ impl ImpitWrapper {
         .as_ref()
         .and_then(|init| init.force_http3)
         .unwrap_or_default(),
+      connect_to: impit_builder::connect_to_rules(
+        request_init
+          .as_ref()
+          .and_then(|init| init.connect_to.clone()),
+      )?,
+      server_name: impit_builder::server_name(
+        request_init
+          .as_ref()
+          .and_then(|init| init.server_name.clone()),
+        request_init.as_ref().and_then(|init| init.sni),
+      ),
+      retry: request_init
+        .as_ref()
+        .and_then(|init| init.retry.clone())
+        .map(impit_builder::retry_policy),
+      redirect: request_init
+        .as_ref()
+        .and_then(|init| init.redirect.as_deref())
+        .map(|mode| redirect_behavior(mode, self.max_redirects))
+        .transpose()?,
+      destination: request_init
+        .as_ref()
+        .and_then(|init| init.destination)
+        .map(Into::into)
+        .unwrap_or_default(),
     });
 
     let method = request_init
       .as_ref()
       .and_then(|init| init.method.to_owned())
-      .unwrap_or_default();
+      .unwrap_or_else(|| "GET".to_string());
     let body = request_init
       .and_then(|init| init.body)
       .map(|array| array.to_vec().into());
 
-    let response = if matches!(method, HttpMethod::Get | HttpMethod::Head) && body.is_some() {
+    let response = if (method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD"))
+      && body.is_some()
+    {
       Err(ImpitError::BindingPassthroughError(
         "GET/HEAD methods don't support passing a request body".to_string(),
       ))
     } else {
-      // Match the HTTP method and execute the corresponding request
-      match method {
-        HttpMethod::Get => self.inner.get(url, body, request_options).await,
-        HttpMethod::Head => self.inner.head(url, body, request_options).await,
-        HttpMethod::Post => self.inner.post(url, body, request_options).await,
-        HttpMethod::Put => self.inner.put(url, body, request_options).await,
-        HttpMethod::Delete => self.inner.delete(url, body, request_options).await,
-        HttpMethod::Patch => self.inner.patch(url, body, request_options).await,
-        HttpMethod::Options => self.inner.options(url, body, request_options).await,
-        HttpMethod::Trace => self.inner.trace(url, body, request_options).await,
-      }
+      self
+        .inner
+        .request(&method, url, body, request_options)
+        .await
     };
 
     match response {
@@ -177,7 +224,8 @@ impl ImpitWrapper {
           ImpitError::ConnectTimeout => "ConnectTimeout",
           ImpitError::ReadTimeout => "ReadTimeout",
           ImpitError::WriteTimeout => "WriteTimeout",
-          ImpitError::PoolTimeout => "PoolTimeout",
+          ImpitError::PoolTimeout | ImpitError::QueueTimeout(_) => "PoolTimeout",
+          ImpitError::CircuitOpen(_) => "CircuitOpen",
           ImpitError::NetworkError => "NetworkError",
           ImpitError::ConnectError(_) => "ConnectError",
           ImpitError::ReadError => "ReadError",
@@ -185,13 +233,18 @@ impl ImpitWrapper {
           ImpitError::CloseError => "CloseError",
           ImpitError::ProtocolError => "ProtocolError",
           ImpitError::LocalProtocolError => "LocalProtocolError",
-          ImpitError::RemoteProtocolError => "RemoteProtocolError",
+          ImpitError::RemoteProtocolError
+          | ImpitError::RefusedStream(_)
+          | ImpitError::GoAway(_) => "RemoteProtocolError",
           ImpitError::ProxyError(_) => "ProxyError",
           ImpitError::ProxyTunnelError(_) => "ProxyTunnelError",
           ImpitError::ProxyAuthRequired => "ProxyAuthRequired",
+          ImpitError::Http3ProxyUnsupported(_) => "ProxyError",
           ImpitError::UnsupportedProtocol => "UnsupportedProtocol",
           ImpitError::DecodingError => "DecodingError",
           ImpitError::TooManyRedirects(_) => "TooManyRedirects",
+          // Only the `redirect: 'error'` mode rejects redirects, which fails with a `TypeError` in the Fetch API.
+          ImpitError::RedirectRejected(_) => "TypeError",
           ImpitError::HTTPStatusError(_) => "HTTPStatusError",
           ImpitError::InvalidURL
           | ImpitError::UrlParsingError(_)
@@ -216,3 +269,21 @@ impl ImpitWrapper {
     }
   }
 }
+
+/// Maps the Fetch API `redirect` mode of a request to the redirect behavior.
+fn redirect_behavior(mode: &str, max_redirects: usize) -> Result<RedirectBehavior, napi::Error> {
+  match mode {
+    "follow" => Ok(RedirectBehavior::FollowRedirect(max_redirects)),
+    "manual" => Ok(RedirectBehavior::ManualRedirect),
+    "error" => Ok(RedirectBehavior::Custom(RedirectPolicy::new(|attempt| {
+      RedirectAction::Error(format!(
+        "URI requested responds with a redirect, redirect mode is set to 'error': {}",
+        attempt.request.url
+      ))
+    }))),
+    _ => Err(napi::Error::new(
+      napi::Status::InvalidArg,
+      format!("Invalid redirect mode: {mode}"),
+    )),
+  }
+}
@@ -2,6 +2,8 @@ use napi::bindgen_prelude::Uint8Array;
 
 use napi_derive::napi;
 
+use crate::impit_builder::RetryOptions;
+
 #[derive(Default, Clone)]
 #[napi(string_enum = "UPPERCASE")]
 pub enum HttpMethod {
@@ -16,6 +18,59 @@ pub enum HttpMethod {
   Trace,
 }
 
+impl From<HttpMethod> for reqwest::Method {
+  fn from(method: HttpMethod) -> Self {
+    match method {
+      HttpMethod::Get => reqwest::Method::GET,
+      HttpMethod::Post => reqwest::Method::POST,
+      HttpMethod::Put => reqwest::Method::PUT,
+      HttpMethod::Delete => reqwest::Method::DELETE,
+      HttpMethod::Patch => reqwest::Method::PATCH,
+      HttpMethod::Head => reqwest::Method::HEAD,
+      HttpMethod::Options => reqwest::Method::OPTIONS,
+      HttpMethod::Trace => reqwest::Method::TRACE,
+    }
+  }
+}
+
+/// What a request is fetching, see {@link RequestInit.destination}.
+#[derive(Default, Clone, Copy)]
+#[napi(string_enum)]
+pub enum RequestDestination {
+  /// A top-level navigation, e.g. typing the URL into the address bar or clicking a link.
+  #[default]
+  #[napi(value = "document")]
+  Document,
+  /// A navigation inside an `<iframe>`.
+  #[napi(value = "iframe")]
+  Iframe,
+  /// A `fetch()` call or an `XMLHttpRequest`.
+  #[napi(value = "fetch")]
+  Fetch,
+  /// A `<script>` element.
+  #[napi(value = "script")]
+  Script,
+  /// A stylesheet, e.g. a `<link rel="stylesheet">` element.
+  #[napi(value = "style")]
+  Style,
+  /// An `<img>` element or a CSS background image.
+  #[napi(value = "image")]
+  Image,
+}
+
+impl From<RequestDestination> for impit::fingerprint::RequestDestination {
+  fn from(val: RequestDestination) -> Self {
+    match val {
+      RequestDestination::Document => Self::Document,
+      RequestDestination::Iframe => Self::Iframe,
+      RequestDestination::Fetch => Self::Fetch,
+      RequestDestination::Script => Self::Script,
+      RequestDestination::Style => Self::Style,
+      RequestDestination::Image => Self::Image,
+    }
+  }
+}
+
 /// Options for configuring an individual HTTP request.
 ///
 /// These options allow you to customize the behavior of a specific request, including the HTTP method, headers, body, timeout, and whether to force HTTP/3.
@@ -28,8 +83,11 @@ pub enum HttpMethod {
 pub struct RequestInit {
   /// HTTP method to use for the request. Default is `GET`.
   ///
-  /// Can be one of: `GET`, `POST`, `PUT`, `DELETE`, `PATCH`, `HEAD`, `OPTIONS`.
-  pub method: Option<HttpMethod>,
+  /// Besides the standard methods, any valid HTTP token (e.g. `PROPFIND`, `PURGE` or `QUERY`) can be used.
+  /// The standard methods are matched case-insensitively, the other methods are sent exactly as they are passed.
+  /// Like in the browsers, `CONNECT`, `TRACE` and `TRACK` are rejected.
+  #[napi(ts_type = "HttpMethod | (string & {})")]
+  pub method: Option<String>,
   /// Additional headers to include in the request.
   ///
   /// Can be an object, a Map, or an array of tuples or an instance of the {@link https://developer.mozilla.org/en-US/docs/Web/API/Headers | Headers} class.
@@ -39,9 +97,13 @@ pub struct RequestInit {
   /// Header matching is **case-insensitive** — for example, setting `user-agent` here will override
   /// the impersonation `User-Agent` header.
   ///
-  /// To remove an impersonated header, pass an empty string as the value.
-  #[napi(ts_type = "Headers | Record<string, string> | [string, string][]")]
-  pub headers: Option<Vec<(String, String)>>,
+  /// To remove an impersonated header, pass `null` as the value. An empty string sends the header with an empty value,
+  /// an array of values or a repeated name in an array of tuples sends the header several times.
+  /// **Breaking change:** an empty string used to remove the header, pass `null` for that instead.
+  #[napi(
+    ts_type = "Headers | Record<string, string | string[] | null> | [string, string | null][]"
+  )]
+  pub headers: Option<Vec<(String, Option<String>)>>,
   #[napi(
     ts_type = "string | ArrayBuffer | Uint8Array | DataView | Blob | File | URLSearchParams | FormData | ReadableStream"
   )]
@@ -51,6 +113,20 @@ pub struct RequestInit {
   pub timeout: Option<u32>,
   /// Force the request to use HTTP/3. If the server doesn't expect HTTP/3 or the Impit instance doesn't have HTTP/3 enabled (via the {@link ImpitOptions.http3} option), the request will fail.
   pub force_http3: Option<bool>,
+  /// Rules to open the connection for this request to a different host or port, in the curl's `--connect-to` format.
+  ///
+  /// These are checked before the rules from {@link ImpitOptions.connectTo}.
+  pub connect_to: Option<Vec<String>>,
+  /// Server name to send in the TLS handshake (SNI) for this request. Overrides {@link ImpitOptions.serverName}.
+  pub server_name: Option<String>,
+  /// Set to `false` to send this request without the SNI extension. Overrides {@link ImpitOptions.sni}.
+  pub sni: Option<bool>,
+  /// Retry options for this request. Overrides {@link ImpitOptions.retry}.
+  pub retry: Option<RetryOptions>,
+  /// What the request is fetching. Adjusts the impersonated `Accept`, `Sec-Fetch-*`, `Upgrade-Insecure-Requests` and `Priority` headers.
+  ///
+  /// Use `'fetch'` for API calls, so they don't look like page navigations. Default is `'document'`.
+  pub destination: Option<RequestDestination>,
   /// Abort signal to cancel the request.
   #[napi(ts_type = "AbortSignal")]
   pub signal: Option<()>, // This value is consumed in the JS wrapper and is not passed through to the Rust layer.
@@ -64,5 +140,5 @@ pub struct RequestInit {
   ///
   /// @see {@link https://developer.mozilla.org/en-US/docs/Web/API/RequestInit#redirect | Fetch API `redirect` option}
   #[napi(ts_type = "'follow' | 'manual' | 'error'")]
-  pub redirect: Option<()>, // This value is consumed in the JS wrapper and is not passed through to the Rust layer.
+  pub redirect: Option<String>,
 }
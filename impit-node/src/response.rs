@@ -1,5 +1,7 @@
 #![allow(clippy::await_holding_refcell_ref, deprecated)]
 use crate::abortable_stream::AbortableStream;
+use impit::local_address::SourceAddress;
+use impit::redirect::{RedirectHistory, RedirectHop as ImpitRedirectHop};
 use impit::utils::{decode, ContentType};
 use napi::bindgen_prelude::JsObjectValue;
 use napi::{
@@ -9,7 +11,7 @@ use napi::{
   sys, Env, JsValue, Unknown,
 };
 use napi_derive::napi;
-use reqwest::Response;
+use reqwest::{header::HeaderMap, Response};
 use std::cell::RefCell;
 use std::sync::Arc;
 use tokio_stream::StreamExt;
@@ -28,6 +30,50 @@ impl Headers {
   }
 }
 
+/// JS Fetch semantics: header values are decoded as ISO-8859-1 (each byte 0x00..=0xFF maps to
+/// the code point U+0000..=U+00FF). Since that mapping is a bijection, the string stays
+/// byte-recoverable via `Buffer.from(value, 'latin1')`.
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+  headers
+    .iter()
+    .map(|(k, v)| {
+      (
+        k.as_str().to_string(),
+        v.as_bytes().iter().map(|&b| b as char).collect(),
+      )
+    })
+    .collect()
+}
+
+/// A redirect response followed before the final response.
+#[napi(object)]
+#[derive(Clone)]
+pub struct RedirectHop {
+  /// The method of the request that was redirected.
+  pub method: String,
+  /// The URL of the request that was redirected.
+  pub url: String,
+  /// HTTP status code of the redirect response.
+  pub status: u16,
+  /// HTTP headers of the redirect response, including the `Location` and `Set-Cookie` headers.
+  #[napi(ts_type = "[string, string][]")]
+  pub headers: Vec<(String, String)>,
+  /// Time between sending the request and receiving the redirect response headers, in milliseconds.
+  pub elapsed: f64,
+}
+
+impl From<&ImpitRedirectHop> for RedirectHop {
+  fn from(hop: &ImpitRedirectHop) -> Self {
+    RedirectHop {
+      method: hop.method.clone(),
+      url: hop.url.to_string(),
+      status: hop.status.as_u16(),
+      headers: header_pairs(&hop.headers),
+      elapsed: hop.elapsed.as_secs_f64() * 1000.0,
+    }
+  }
+}
+
 /// Represents an HTTP response.
 ///
 /// The `ImpitResponse` class provides access to the response status, headers, and body.
@@ -61,6 +107,16 @@ pub struct ImpitResponse {
   ///
   /// In case of redirects, this will be the final URL after all redirects have been followed.
   pub url: String,
+  /// The local IP address the request was sent from.
+  ///
+  /// Only available if {@link ImpitOptions.localAddress} or {@link ImpitOptions.localAddressPool} is set.
+  pub source_address: Option<String>,
+  /// `true` if the response is the result of following one or more redirects.
+  pub redirected: bool,
+  /// The redirect responses followed before this response, oldest first.
+  ///
+  /// Empty if no redirects were followed.
+  pub redirect_chain: Vec<RedirectHop>,
   // Shared sender used to immediately signal abort to the JS ReadableStream without polling.
   abort_receiver: Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::Receiver<()>>>>,
   abort_sender: Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::Sender<()>>>>,
@@ -89,19 +145,18 @@ impl<'env> ImpitResponse {
       .canonical_reason()
       .unwrap_or("")
       .to_string();
-    // JS Fetch semantics: header values are decoded as ISO-8859-1 (each byte 0x00..=0xFF maps to
-    // the code point U+0000..=U+00FF). Since that mapping is a bijection, the string stays
-    // byte-recoverable via `Buffer.from(value, 'latin1')`.
-    let mut headers_vec: Vec<(String, String)> = Vec::new();
-    for (k, v) in response.headers().iter() {
-      headers_vec.push((
-        k.as_str().to_string(),
-        v.as_bytes().iter().map(|&b| b as char).collect(),
-      ));
-    }
-    let headers = Headers(headers_vec);
+    let headers = Headers(header_pairs(response.headers()));
     let ok = response.status().is_success();
     let url = response.url().to_string();
+    let source_address = response
+      .extensions()
+      .get::<SourceAddress>()
+      .map(|address| address.0.to_string());
+    let redirect_chain: Vec<RedirectHop> = response
+      .extensions()
+      .get::<RedirectHistory>()
+      .map(|history| history.iter().map(RedirectHop::from).collect())
+      .unwrap_or_default();
 
     Ok(Self {
       inner: RefCell::new(Some(response)),
@@ -110,6 +165,9 @@ impl<'env> ImpitResponse {
       headers,
       ok,
       url,
+      source_address,
+      redirected: !redirect_chain.is_empty(),
+      redirect_chain,
       abort_receiver: Arc::new(tokio::sync::Mutex::new(None)),
       abort_sender: Arc::new(tokio::sync::Mutex::new(None)),
     })
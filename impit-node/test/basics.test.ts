@@ -309,6 +309,91 @@ describe.each([
             t.expect(json.headers?.['Sec-Fetch-User']).toBeUndefined();
         });
 
+        test('fetch destination sends the API call headers', async (t) => {
+            if (!browser) return t.skip();
+            const response = await impit.fetch(getHttpBinUrl('/headers'), { destination: 'fetch' });
+            const json = await response.json();
+
+            t.expect(json.headers?.['Accept']).toBe('*/*');
+            t.expect(json.headers?.['Sec-Fetch-Dest']).toBe('empty');
+            t.expect(json.headers?.['Sec-Fetch-Mode']).toBe('cors');
+            t.expect(json.headers?.['Sec-Fetch-User']).toBeUndefined();
+        });
+
+        test('headers keep the browser order', async (t) => {
+            if (!browser) return t.skip();
+
+            const server = http.createServer((req, res) => {
+                res.end(JSON.stringify(req.rawHeaders.filter((_, i) => i % 2 === 0).map((name) => name.toLowerCase())));
+            });
+            await new Promise((r) => server.listen(0, '127.0.0.1', r));
+            const url = `http://127.0.0.1:${(server.address() as AddressInfo).port}/`;
+
+            const impit = new Impit({ browser, headerPosition: 'after:user-agent' });
+            const baseline: string[] = await impit.fetch(url).then((r) => r.json());
+            const names: string[] = await impit.fetch(url, {
+                headers: [['Accept-Language', 'de'], ['X-B', '2'], ['X-A', '1']],
+            }).then((r) => r.json());
+            server.close();
+
+            t.expect(names[0]).toBe('host');
+            t.expect(names.filter((name) => !name.startsWith('x-'))).toEqual(baseline);
+            t.expect(names.indexOf('x-b')).toBe(names.indexOf('user-agent') + 1);
+            t.expect(names.indexOf('x-a')).toBe(names.indexOf('x-b') + 1);
+        });
+
+        test('header values can be repeated, emptied and removed', async (t) => {
+            const server = http.createServer((req, res) => {
+                res.end(JSON.stringify(req.rawHeaders));
+            });
+            await new Promise((r) => server.listen(0, '127.0.0.1', r));
+            const url = `http://127.0.0.1:${(server.address() as AddressInfo).port}/`;
+
+            const impit = new Impit({ browser, headers: { 'X-Client': 'client', 'X-Other': 'other' } });
+            const rawHeaders: string[] = await impit.fetch(url, {
+                headers: {
+                    'X-Forwarded-For': ['1.1.1.1', '2.2.2.2'],
+                    'X-Empty': '',
+                    'X-Client': null,
+                },
+            }).then((r) => r.json());
+            server.close();
+
+            const values = (name: string) => rawHeaders
+                .filter((_, i) => i % 2 === 1 && rawHeaders[i - 1].toLowerCase() === name);
+
+            t.expect(values('x-forwarded-for')).toEqual(['1.1.1.1', '2.2.2.2']);
+            t.expect(values('x-empty')).toEqual(['']);
+            t.expect(values('x-client')).toEqual([]);
+            t.expect(values('x-other')).toEqual(['other']);
+        });
+
+        test('client hints are sent to the origins asking for them', async (t) => {
+            if (browser !== Browser.Chrome) return t.skip();
+
+            let requests = 0;
+            const server = http.createServer((req, res) => {
+                requests += 1;
+                if (!req.headers['sec-ch-ua-arch']) {
+                    res.setHeader('Accept-CH', 'Sec-CH-UA-Arch, Sec-CH-UA-Full-Version-List');
+                    res.setHeader('Critical-CH', 'Sec-CH-UA-Arch');
+                }
+                res.end(JSON.stringify(req.headers));
+            });
+            await new Promise((r) => server.listen(0, '127.0.0.1', r));
+            const url = `http://127.0.0.1:${(server.address() as AddressInfo).port}/`;
+
+            const impit = new Impit({ browser });
+            const headers = await impit.fetch(url).then((r) => r.json());
+            server.close();
+
+            // The first response lists a critical hint, so the request is sent again with the hints.
+            t.expect(requests).toBe(2);
+            t.expect(headers['sec-ch-ua-arch']).toMatch(/^"(x86|arm)"$/);
+            t.expect(headers['sec-ch-ua-full-version-list']).toContain('Google Chrome');
+            t.expect(headers['sec-ch-ua-model']).toBeUndefined();
+        });
+
         test('client-scoped headers work', async (t) => {
             const headers = new Headers();
             headers.set('User-Agent', 'client-scoped user agent');
@@ -399,6 +484,21 @@ describe.each([
 
             expect(json.ip).toBe(remoteAddress);
         });
+
+        test('trackNavigation sends the previous page as Referer', async () => {
+            const impit = new Impit({
+                browser,
+                trackNavigation: true,
+            });
+
+            const first = await impit.fetch(getHttpBinUrl('/headers'));
+            expect((await first.json()).headers?.['Referer']).toBeUndefined();
+
+            const second = await impit.fetch(getHttpBinUrl('/headers'));
+            const json = await second.json();
+            expect(json.headers?.['Referer']).toBe(getHttpBinUrl('/headers'));
+            expect(json.headers?.['Sec-Fetch-Site']).toBe('same-origin');
+        });
     });
 
     describe('Parameter types', () => {
@@ -828,6 +928,23 @@ describe.each([
             expect(response.url).toBe('http://localhost:3001/get');
         });
 
+        test('records the redirect chain', async () => {
+            const response = await impit.fetch('http://localhost:3001/redirect/1');
+
+            expect(response.redirected).toBe(true);
+            expect(response.redirectChain).toHaveLength(1);
+
+            const [hop] = response.redirectChain;
+            expect(hop.method).toBe('GET');
+            expect(hop.url).toBe('http://localhost:3001/redirect/1');
+            expect(hop.status).toBe(302);
+            expect(new Headers(hop.headers).get('location')).toBe('/get');
+
+            const direct = await impit.fetch('http://localhost:3001/get');
+            expect(direct.redirected).toBe(false);
+            expect(direct.redirectChain).toEqual([]);
+        });
+
         test('instance-level followRedirects: false disables redirects', async () => {
             const noRedirect = new Impit({ browser, followRedirects: false });
             const response = await noRedirect.fetch('http://localhost:3001/redirect/1');
@@ -841,7 +958,7 @@ describe.each([
 
             await expect(
                 limited.fetch('http://localhost:3001/redirect/2'),
-            ).rejects.toThrow('Maximum redirect limit (1) exceeded');
+            ).rejects.toThrow('Maximum allowed: 1.');
         });
 
         test('per-request redirect: "manual" returns 3xx response', async () => {
@@ -915,7 +1032,7 @@ describe.each([
 
             await expect(
                 limited.fetch('http://localhost:3001/redirect/2', { redirect: 'follow' }),
-            ).rejects.toThrow('Maximum redirect limit (1) exceeded');
+            ).rejects.toThrow('Maximum allowed: 1.');
         });
 
         test('redirect: "manual" with 301 status code', async () => {
@@ -937,5 +1054,20 @@ describe.each([
             expect(response.status).toBe(307);
             expect(response.headers.get('location')).toBe('/get');
         });
+
+        test('credentials are dropped on cross-origin redirects', async () => {
+            const target = encodeURIComponent('http://127.0.0.1:3001/post');
+            const response = await impit.fetch(`http://localhost:3001/redirect-to?url=${target}`, {
+                headers: { Authorization: 'Bearer token' },
+            });
+            const { headers } = await response.json();
+
+            expect(headers.authorization).toBeUndefined();
+
+            const sameOrigin = await impit.fetch('http://localhost:3001/redirect-to?url=/post', {
+                headers: { Authorization: 'Bearer token' },
+            });
+            expect((await sameOrigin.json()).headers.authorization).toBe('Bearer token');
+        });
     })
 });
@@ -165,6 +165,19 @@ describe('Cookie handling', () => {
             // The request header should take precedence
             expect(json.cookies.override).toBe('replaced');
         });
+
+        test('cookie jar errors fail the request', async () => {
+            const cookieJar = {
+                getCookieString: async () => {
+                    throw new Error('jar is unavailable');
+                },
+                setCookie: async () => {},
+            };
+
+            const impit = new Impit({ cookieJar });
+
+            await expect(impit.fetch(`${baseUrl}/cookies`)).rejects.toThrow('jar is unavailable');
+        });
     });
 
     describe('without cookie jar', () => {
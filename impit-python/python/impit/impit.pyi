@@ -1,15 +1,22 @@
 from __future__ import annotations
+from datetime import timedelta
 from http.cookiejar import CookieJar
 from .cookies import Cookies
 from .headers import Headers
 
 from . import Browser
 
-from typing import Any
-from collections.abc import AsyncIterable, AsyncIterator, Iterable, Iterator
+from typing import Any, Literal
+from collections.abc import AsyncIterable, AsyncIterator, Iterable, Iterator, Mapping, Sequence
 from contextlib import AbstractAsyncContextManager, AbstractContextManager
 
 
+HeaderTypes = Mapping[str, str | list[str] | None] | Sequence[tuple[str, str | None]]
+"""The request headers. ``None`` removes a header, an empty string sends it empty and a list of values sends it several times.
+
+Breaking change: an empty string used to remove the header, pass ``None`` for that instead.
+"""
+
 USE_CLIENT_DEFAULT: str
 """Sentinel that, when passed as a per-request ``timeout``, causes the client-level default timeout to be used.
 
@@ -89,6 +96,10 @@ class UnsupportedProtocol(TransportError):
     """Represents an unsupported protocol error."""
 
 
+class CircuitOpen(TransportError):
+    """Raised without sending the request while the circuit breaker for the host is open."""
+
+
 class DecodingError(RequestError):
     """Represents an error during response decoding."""
 
@@ -226,6 +237,41 @@ class Response:
         print(response.url) # 'https://crawlee.dev'
     """
 
+    history: list[Response]
+    """The redirect responses followed before this response, oldest first. Empty if no redirects were followed.
+
+    The redirect responses have no body, but keep their status code, URL and headers (e.g. `Location` and `Set-Cookie`),
+    along with the :attr:`method` and :attr:`elapsed` time of the redirected request.
+
+    .. code-block:: python
+
+        response = await client.get("http://crawlee.dev")
+        print(response.history) # [<Response [301 Moved Permanently]>]
+        print(response.history[0].headers["location"]) # 'https://crawlee.dev/'
+    """
+
+    method: str | None
+    """The method of the redirected request, e.g. `'POST'` for a redirect that switched the next request to `GET`.
+
+    Only set for the responses in :attr:`history`, `None` otherwise.
+
+    .. code-block:: python
+
+        response = await client.post("https://httpbin.org/redirect-to?url=/get&status_code=303")
+        print(response.history[0].method) # 'POST'
+    """
+
+    elapsed: timedelta | None
+    """The time between sending the redirected request and receiving the redirect response headers (including the retries).
+
+    Only set for the responses in :attr:`history`, `None` otherwise.
+
+    .. code-block:: python
+
+        response = await client.get("http://crawlee.dev")
+        print(response.history[0].elapsed) # datetime.timedelta(microseconds=85312)
+    """
+
     content: bytes
     """Contains the response body as bytes. If the response was created with `stream=True`, this will be empty until the content is read using :meth:`read` or :meth:`iter_bytes`.
 
@@ -267,6 +313,18 @@ class Response:
         # read the content of the response using response.content
     """
 
+    source_address: str | None
+    """The local IP address the request was sent from.
+
+    Only available if the client was created with ``local_address`` or ``local_address_pool``, `None` otherwise.
+
+    .. code-block:: python
+
+        client = Client(local_address_pool=["2001:db8::/48"], local_address_selection="request")
+        response = client.get("https://crawlee.dev")
+        print(response.source_address) # '2001:db8:0:1a2b:3c4d:5e6f:7a8b:9c0d'
+    """
+
     def __init__(
         self,
         status_code: int,
@@ -411,8 +469,8 @@ class Client:
                 .. note::
                     The HTTP/3 support is experimental and may not work with all servers. The impersonation capabilities are limited when using HTTP/3.
 
-                .. warning::
-                    Proxies are not supported when HTTP/3 is enabled.
+                .. note::
+                    With a proxy, HTTP/3 is only used if the proxy is a SOCKS5 proxy supporting ``UDP ASSOCIATE``.
 
             proxy:
 
@@ -420,8 +478,9 @@ class Client:
 
                 This can be an HTTP, HTTPS, or SOCKS proxy.
 
-                .. warning::
-                    Not supported when HTTP/3 is enabled.
+                .. note::
+                    With HTTP/3 enabled, QUIC traffic is only relayed through SOCKS5 proxies supporting ``UDP ASSOCIATE``.
+                    Other proxies make the requests fall back to TCP, always through the proxy.
             timeout:
                 Default request timeout in seconds. Pass ``None`` to disable the timeout entirely.
 
@@ -464,6 +523,12 @@ class Client:
                 Header matching is case-insensitive — e.g., setting ``user-agent`` here will
                 override the impersonation ``User-Agent`` header.
 
+                A ``None`` value removes the header (e.g. an impersonated one), an empty string sends the header
+                with an empty value and a list of values sends the header several times, e.g.
+                ``{'X-Forwarded-For': ['1.1.1.1', '2.2.2.2']}``. A sequence of ``(name, value)`` pairs can be passed
+                instead of a mapping, in which case a repeated name sends the header several times as well.
+                Breaking change: an empty string used to remove the header, pass ``None`` for that instead.
+
                 Default is an empty dictionary.
             local_address:
 
@@ -471,6 +536,117 @@ class Client:
 
                 Useful for testing purposes or when you want to bind the client to a specific network interface.
                 Can be an IP address in the format `xxx.xxx.xxx.xxx` (for IPv4) or `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff` (for IPv6).
+            local_address_pool:
+
+                A pool of local addresses to send the requests from.
+
+                Every entry is either an IP address or a CIDR prefix (e.g. ``2001:db8::/48``).
+                The entries are used in a round-robin fashion, with a random address picked inside each prefix.
+                The address a request was sent from is available as :attr:`Response.source_address`.
+
+                Takes precedence over ``local_address``.
+            local_address_selection:
+
+                How often a new address is picked from ``local_address_pool``.
+
+                Use ``"request"`` for a new address for every request, ``"host"`` for one address per host,
+                or ``"session"`` (default) for one address for the whole client.
+            interface:
+
+                Name of the network interface to send the requests through (e.g. ``eth1`` or ``wg0``).
+
+                Applies to both the TCP connections and the QUIC sockets used for HTTP/3. Only supported on Linux.
+            resolve:
+
+                Static DNS overrides in the curl's ``--resolve`` format, i.e. ``host:port:address[,address...]``
+                (e.g. ``example.com:443:127.0.0.1``). Like in curl, an override only applies to its port (or to any port with ``*``).
+            nameservers:
+
+                Nameservers to use instead of the system ones. Supports plain DNS (``8.8.8.8``),
+                DNS-over-TLS (``tls://1.1.1.1``) and DNS-over-HTTPS (``https://1.1.1.1/dns-query``).
+
+                The configured resolver is used both for opening connections and for the HTTP/3 discovery.
+            ip_family:
+
+                Which IP address family to connect over: ``"prefer-ipv6"`` (default), ``"prefer-ipv4"``,
+                ``"ipv4-only"`` or ``"ipv6-only"``.
+            connection_attempt_delay:
+
+                Delay (in seconds) before the next address is tried while the previous connection attempt
+                is still pending (Happy Eyeballs, default: 0.3). Applies to the HTTP/3 connections only,
+                the TCP connections always use 300 ms.
+            connect_to:
+
+                Rules to open the connections for some hosts to a different host or port, in the curl's
+                ``--connect-to`` format, i.e. ``host:port:connect_host:connect_port``
+                (e.g. ``example.com:443:origin.example.net:8443``). Empty fields match any host (or port)
+                on the left side and keep the original host (or port) on the right side.
+
+                The requests keep the ``Host`` header, the TLS server name and the cookies of the URL host.
+            server_name:
+
+                Server name to send in the TLS handshake (SNI) instead of the URL hostname. The server certificate
+                is verified against this name. Over TCP, these requests are only sent over HTTP/1.1, with the URL
+                hostname in the ``Host`` header.
+            sni:
+
+                Set to ``False`` to send the TLS handshakes without the SNI extension.
+            quic_head_start:
+
+                Seconds the QUIC connection is waited for before the request is sent over TCP instead
+                (default: 0.3). After a QUIC failure, the requests to the origin are sent over TCP for a while.
+            max_retries:
+
+                Maximum number of retries of the failed requests. Connection errors are retried for any method,
+                responses with the ``retry_status_codes`` only for the idempotent methods. Streamed request
+                bodies are never sent twice. The requests are not retried unless one of the retry arguments is set.
+            retry_status_codes:
+
+                Response status codes to retry (default: ``[429, 503]``). The ``Retry-After`` header is respected.
+            retry_backoff:
+
+                Seconds to wait before the first retry, doubled for every further retry (default: 0.5).
+            rate_limit:
+
+                Maximum number of requests started per second, can be a fraction (e.g. ``0.5`` for one request
+                every two seconds). The requests over the limit wait in a queue.
+            max_concurrency:
+
+                Maximum number of requests in flight at the same time. A request holds its slot until its
+                response body is read.
+            limit_by:
+
+                What the requests are grouped by for ``rate_limit`` and ``max_concurrency``: ``'host'`` (default),
+                ``'domain'`` (the registrable domain, e.g. ``example.co.uk``) or ``'proxy'``.
+            queue_timeout:
+
+                Seconds a request can wait for the rate and concurrency limits before failing with
+                :class:`PoolTimeout` (default: no timeout).
+            circuit_breaker_threshold:
+
+                Number of consecutive failed requests to a host (connection errors, timeouts or 5xx responses)
+                after which the requests to it raise :class:`CircuitOpen` without being sent. After the cooldown,
+                a probe request is let through - if it succeeds, the requests are sent normally again
+                (default: 5 if any circuit breaker argument is set, otherwise no circuit breaker).
+            circuit_breaker_cooldown:
+
+                Seconds the circuit stays open before the probe request is sent (default: 30).
+            track_navigation:
+
+                Send every request as a navigation from the previously visited page, like a browser tab.
+                The requests then carry the ``Referer`` header and a matching ``Sec-Fetch-Site`` header
+                (``same-origin``, ``same-site`` or ``cross-site``, computed with the Public Suffix List)
+                instead of looking like URLs typed into the address bar (default: False).
+            referrer_policy:
+
+                Referrer policy of the visited pages without a ``Referrer-Policy`` header, used with
+                ``track_navigation`` (default: ``'strict-origin-when-cross-origin'``).
+
+            header_position:
+
+                Where the custom headers that aren't part of the impersonated browser's headers are sent:
+                ``'start'``, ``'end'`` (default), ``'before:<header>'`` or ``'after:<header>'``. The custom headers
+                overriding the browser's headers always keep the browser's order.
         """
 
     def __enter__(self) -> Client:
@@ -492,8 +668,40 @@ class Client:
         max_redirects: int | None = None,
         cookie_jar: CookieJar | None = None,
         cookies: Cookies | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         local_address: str | None = None,
+        local_address_pool: list[str] | None = None,
+        local_address_selection: Literal['request', 'host', 'session'] | None = None,
+        interface: str | None = None,
+        resolve: list[str] | None = None,
+        nameservers: list[str] | None = None,
+        ip_family: Literal['prefer-ipv6', 'prefer-ipv4', 'ipv4-only', 'ipv6-only'] | None = None,
+        connection_attempt_delay: float | None = None,
+        connect_to: list[str] | None = None,
+        server_name: str | None = None,
+        sni: bool | None = None,
+        quic_head_start: float | None = None,
+        max_retries: int | None = None,
+        retry_status_codes: list[int] | None = None,
+        retry_backoff: float | None = None,
+        rate_limit: float | None = None,
+        max_concurrency: int | None = None,
+        limit_by: Literal['host', 'domain', 'proxy'] | None = None,
+        queue_timeout: float | None = None,
+        circuit_breaker_threshold: int | None = None,
+        circuit_breaker_cooldown: float | None = None,
+        track_navigation: bool | None = None,
+        referrer_policy: Literal[
+            'no-referrer',
+            'no-referrer-when-downgrade',
+            'same-origin',
+            'origin',
+            'strict-origin',
+            'origin-when-cross-origin',
+            'strict-origin-when-cross-origin',
+            'unsafe-url',
+        ] | None = None,
+        header_position: str | None = None,
     ) -> None:
         """Initialize a synchronous HTTP client.
 
@@ -510,9 +718,42 @@ class Client:
             cookie_jar: Cookie jar to store cookies in.
             cookies: httpx-compatible cookies object.
             headers: Default HTTP headers to include in requests. These override browser impersonation
-                headers and are overridden by per-request headers. Matching is case-insensitive. To remove an impersonated header, pass an empty string as the value.
+                headers and are overridden by per-request headers. Matching is case-insensitive. To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times. Breaking change: an empty string used to remove the header, pass ``None`` for that instead.
             local_address: Local address to bind the client to. Useful for testing purposes or when you want to bind the client to a specific network interface.
                 Can be an IP address in the format "xxx.xxx.xxx.xxx" (for IPv4) or "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff" (for IPv6).
+            local_address_pool: Local IP addresses or CIDR prefixes (e.g. "2001:db8::/48") to send the requests from. Takes precedence over `local_address`.
+            local_address_selection: How often a new address is picked from `local_address_pool` ("request", "host" or "session", default: "session").
+            interface: Name of the network interface to send the requests through (e.g. "eth1" or "wg0"). Only supported on Linux.
+            resolve: Static DNS overrides in the curl's `--resolve` format (e.g. "example.com:443:127.0.0.1").
+            nameservers: Nameservers to use instead of the system ones (e.g. "8.8.8.8", "tls://1.1.1.1" or "https://1.1.1.1/dns-query").
+            ip_family: Which IP address family to connect over ("prefer-ipv6", "prefer-ipv4", "ipv4-only" or "ipv6-only", default: "prefer-ipv6").
+            connection_attempt_delay: Delay in seconds between the Happy Eyeballs connection attempts for HTTP/3 (default: 0.3).
+            connect_to: Rules to connect to a different host or port, in the curl's `--connect-to` format (e.g. "example.com:443:origin.example.net:8443").
+            server_name: Server name to send in the TLS handshake instead of the URL hostname. Over TCP, the requests then use HTTP/1.1 only.
+            sni: Set to False to send the TLS handshakes without the SNI extension (default: True).
+            quic_head_start: Seconds the QUIC connection is waited for before the request goes over TCP (default: 0.3).
+            max_retries: Maximum number of retries of the failed requests (default: 2 if any retry argument is set, otherwise 0).
+            retry_status_codes: Response status codes to retry (default: [429, 503]).
+            retry_backoff: Seconds to wait before the first retry, doubled for every further retry (default: 0.5).
+            rate_limit: Maximum number of requests started per second, can be a fraction (default: no limit).
+            max_concurrency: Maximum number of requests in flight at the same time (default: no limit).
+            limit_by: What the limits are counted by - 'host', 'domain' (registrable domain) or 'proxy' (default: 'host').
+            queue_timeout: Seconds a request can wait for the limits before raising PoolTimeout (default: no timeout).
+            circuit_breaker_threshold: Consecutive failures after which the requests to a host raise CircuitOpen (default: 5 if any circuit breaker argument is set).
+            circuit_breaker_cooldown: Seconds before a probe request is let through to an open circuit (default: 30).
+            track_navigation: Send the Referer and Sec-Fetch-Site headers from the previously visited page (default: False).
+            referrer_policy: Default referrer policy of the visited pages (default: 'strict-origin-when-cross-origin').
+            header_position: Position of the extra custom headers: 'start', 'end' (default), 'before:<header>' or 'after:<header>'.
+        """
+
+    def circuit_state(self, host: str) -> Literal['closed', 'open', 'half-open']:
+        """Return the state of the circuit breaker for the given host.
+
+        The circuits are always ``'closed'`` unless ``circuit_breaker_threshold`` or ``circuit_breaker_cooldown``
+        is set.
+
+        Args:
+            host: Hostname of the server, e.g. ``"example.com"``.
         """
 
     def get(
@@ -520,9 +761,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a GET request.
 
@@ -530,9 +773,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def post(
@@ -540,9 +785,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a POST request.
 
@@ -550,9 +797,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
 
         """
 
@@ -561,9 +810,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a PUT request.
 
@@ -571,9 +822,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def patch(
@@ -581,9 +834,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a PATCH request.
 
@@ -591,9 +846,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def delete(
@@ -601,9 +858,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a DELETE request.
 
@@ -611,9 +870,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def head(
@@ -621,9 +882,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a HEAD request.
 
@@ -631,9 +894,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def options(
@@ -641,9 +906,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an OPTIONS request.
 
@@ -651,9 +918,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def trace(
@@ -661,9 +930,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make a TRACE request.
 
@@ -671,9 +942,11 @@ class Client:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def request(
@@ -682,22 +955,26 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
         stream: bool = False,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an HTTP request with the specified method.
 
         Args:
-            method: HTTP method (e.g., "get", "post")
+            method: HTTP method (e.g., "get", "post" or "propfind"), sent in uppercase. ``CONNECT`` and ``TRACK`` are rejected
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
             stream: Whether to return a streaming response (default: False)
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def stream(
@@ -706,9 +983,11 @@ class Client:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> AbstractContextManager[Response]:
         """Make a streaming request with the specified method.
 
@@ -724,13 +1003,15 @@ class Client:
                         process(chunk)  # Process each chunk as it is received
 
         Args:
-            method: HTTP method (e.g., "get", "post")
+            method: HTTP method (e.g., "get", "post" or "propfind"), sent in uppercase. ``CONNECT`` and ``TRACK`` are rejected
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
 
@@ -753,8 +1034,8 @@ class AsyncClient:
                 .. note::
                     The HTTP/3 support is experimental and may not work with all servers. The impersonation capabilities are limited when using HTTP/3.
 
-                .. warning::
-                    Proxies are not supported when HTTP/3 is enabled.
+                .. note::
+                    With a proxy, HTTP/3 is only used if the proxy is a SOCKS5 proxy supporting ``UDP ASSOCIATE``.
 
             proxy:
 
@@ -762,8 +1043,9 @@ class AsyncClient:
 
                 This can be an HTTP, HTTPS, or SOCKS proxy.
 
-                .. warning::
-                    Not supported when HTTP/3 is enabled.
+                .. note::
+                    With HTTP/3 enabled, QUIC traffic is only relayed through SOCKS5 proxies supporting ``UDP ASSOCIATE``.
+                    Other proxies make the requests fall back to TCP, always through the proxy.
             timeout:
                 Default request timeout in seconds. Pass ``None`` to disable the timeout entirely.
 
@@ -806,6 +1088,12 @@ class AsyncClient:
                 Header matching is case-insensitive — e.g., setting ``user-agent`` here will
                 override the impersonation ``User-Agent`` header.
 
+                A ``None`` value removes the header (e.g. an impersonated one), an empty string sends the header
+                with an empty value and a list of values sends the header several times, e.g.
+                ``{'X-Forwarded-For': ['1.1.1.1', '2.2.2.2']}``. A sequence of ``(name, value)`` pairs can be passed
+                instead of a mapping, in which case a repeated name sends the header several times as well.
+                Breaking change: an empty string used to remove the header, pass ``None`` for that instead.
+
                 Default is an empty dictionary.
             local_address:
 
@@ -813,6 +1101,117 @@ class AsyncClient:
 
                 Useful for testing purposes or when you want to bind the client to a specific network interface.
                 Can be an IP address in the format `xxx.xxx.xxx.xxx` (for IPv4) or `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff` (for IPv6).
+            local_address_pool:
+
+                A pool of local addresses to send the requests from.
+
+                Every entry is either an IP address or a CIDR prefix (e.g. ``2001:db8::/48``).
+                The entries are used in a round-robin fashion, with a random address picked inside each prefix.
+                The address a request was sent from is available as :attr:`Response.source_address`.
+
+                Takes precedence over ``local_address``.
+            local_address_selection:
+
+                How often a new address is picked from ``local_address_pool``.
+
+                Use ``"request"`` for a new address for every request, ``"host"`` for one address per host,
+                or ``"session"`` (default) for one address for the whole client.
+            interface:
+
+                Name of the network interface to send the requests through (e.g. ``eth1`` or ``wg0``).
+
+                Applies to both the TCP connections and the QUIC sockets used for HTTP/3. Only supported on Linux.
+            resolve:
+
+                Static DNS overrides in the curl's ``--resolve`` format, i.e. ``host:port:address[,address...]``
+                (e.g. ``example.com:443:127.0.0.1``). Like in curl, an override only applies to its port (or to any port with ``*``).
+            nameservers:
+
+                Nameservers to use instead of the system ones. Supports plain DNS (``8.8.8.8``),
+                DNS-over-TLS (``tls://1.1.1.1``) and DNS-over-HTTPS (``https://1.1.1.1/dns-query``).
+
+                The configured resolver is used both for opening connections and for the HTTP/3 discovery.
+            ip_family:
+
+                Which IP address family to connect over: ``"prefer-ipv6"`` (default), ``"prefer-ipv4"``,
+                ``"ipv4-only"`` or ``"ipv6-only"``.
+            connection_attempt_delay:
+
+                Delay (in seconds) before the next address is tried while the previous connection attempt
+                is still pending (Happy Eyeballs, default: 0.3). Applies to the HTTP/3 connections only,
+                the TCP connections always use 300 ms.
+            connect_to:
+
+                Rules to open the connections for some hosts to a different host or port, in the curl's
+                ``--connect-to`` format, i.e. ``host:port:connect_host:connect_port``
+                (e.g. ``example.com:443:origin.example.net:8443``). Empty fields match any host (or port)
+                on the left side and keep the original host (or port) on the right side.
+
+                The requests keep the ``Host`` header, the TLS server name and the cookies of the URL host.
+            server_name:
+
+                Server name to send in the TLS handshake (SNI) instead of the URL hostname. The server certificate
+                is verified against this name. Over TCP, these requests are only sent over HTTP/1.1, with the URL
+                hostname in the ``Host`` header.
+            sni:
+
+                Set to ``False`` to send the TLS handshakes without the SNI extension.
+            quic_head_start:
+
+                Seconds the QUIC connection is waited for before the request is sent over TCP instead
+                (default: 0.3). After a QUIC failure, the requests to the origin are sent over TCP for a while.
+            max_retries:
+
+                Maximum number of retries of the failed requests. Connection errors are retried for any method,
+                responses with the ``retry_status_codes`` only for the idempotent methods. Streamed request
+                bodies are never sent twice. The requests are not retried unless one of the retry arguments is set.
+            retry_status_codes:
+
+                Response status codes to retry (default: ``[429, 503]``). The ``Retry-After`` header is respected.
+            retry_backoff:
+
+                Seconds to wait before the first retry, doubled for every further retry (default: 0.5).
+            rate_limit:
+
+                Maximum number of requests started per second, can be a fraction (e.g. ``0.5`` for one request
+                every two seconds). The requests over the limit wait in a queue.
+            max_concurrency:
+
+                Maximum number of requests in flight at the same time. A request holds its slot until its
+                response body is read.
+            limit_by:
+
+                What the requests are grouped by for ``rate_limit`` and ``max_concurrency``: ``'host'`` (default),
+                ``'domain'`` (the registrable domain, e.g. ``example.co.uk``) or ``'proxy'``.
+            queue_timeout:
+
+                Seconds a request can wait for the rate and concurrency limits before failing with
+                :class:`PoolTimeout` (default: no timeout).
+            circuit_breaker_threshold:
+
+                Number of consecutive failed requests to a host (connection errors, timeouts or 5xx responses)
+                after which the requests to it raise :class:`CircuitOpen` without being sent. After the cooldown,
+                a probe request is let through - if it succeeds, the requests are sent normally again
+                (default: 5 if any circuit breaker argument is set, otherwise no circuit breaker).
+            circuit_breaker_cooldown:
+
+                Seconds the circuit stays open before the probe request is sent (default: 30).
+            track_navigation:
+
+                Send every request as a navigation from the previously visited page, like a browser tab.
+                The requests then carry the ``Referer`` header and a matching ``Sec-Fetch-Site`` header
+                (``same-origin``, ``same-site`` or ``cross-site``, computed with the Public Suffix List)
+                instead of looking like URLs typed into the address bar (default: False).
+            referrer_policy:
+
+                Referrer policy of the visited pages without a ``Referrer-Policy`` header, used with
+                ``track_navigation`` (default: ``'strict-origin-when-cross-origin'``).
+
+            header_position:
+
+                Where the custom headers that aren't part of the impersonated browser's headers are sent:
+                ``'start'``, ``'end'`` (default), ``'before:<header>'`` or ``'after:<header>'``. The custom headers
+                overriding the browser's headers always keep the browser's order.
         """
 
     async def __aenter__(self) -> AsyncClient:
@@ -833,8 +1232,40 @@ class AsyncClient:
         max_redirects: int | None = None,
         cookie_jar: CookieJar | None = None,
         cookies: Cookies | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         local_address: str | None = None,
+        local_address_pool: list[str] | None = None,
+        local_address_selection: Literal['request', 'host', 'session'] | None = None,
+        interface: str | None = None,
+        resolve: list[str] | None = None,
+        nameservers: list[str] | None = None,
+        ip_family: Literal['prefer-ipv6', 'prefer-ipv4', 'ipv4-only', 'ipv6-only'] | None = None,
+        connection_attempt_delay: float | None = None,
+        connect_to: list[str] | None = None,
+        server_name: str | None = None,
+        sni: bool | None = None,
+        quic_head_start: float | None = None,
+        max_retries: int | None = None,
+        retry_status_codes: list[int] | None = None,
+        retry_backoff: float | None = None,
+        rate_limit: float | None = None,
+        max_concurrency: int | None = None,
+        limit_by: Literal['host', 'domain', 'proxy'] | None = None,
+        queue_timeout: float | None = None,
+        circuit_breaker_threshold: int | None = None,
+        circuit_breaker_cooldown: float | None = None,
+        track_navigation: bool | None = None,
+        referrer_policy: Literal[
+            'no-referrer',
+            'no-referrer-when-downgrade',
+            'same-origin',
+            'origin',
+            'strict-origin',
+            'origin-when-cross-origin',
+            'strict-origin-when-cross-origin',
+            'unsafe-url',
+        ] | None = None,
+        header_position: str | None = None,
     ) -> None:
         """Initialize an asynchronous HTTP client.
 
@@ -851,9 +1282,42 @@ class AsyncClient:
             cookie_jar: Cookie jar to store cookies in.
             cookies: httpx-compatible cookies object.
             headers: Default HTTP headers to include in requests. These override browser impersonation
-                headers and are overridden by per-request headers. Matching is case-insensitive. To remove an impersonated header, pass an empty string as the value.
+                headers and are overridden by per-request headers. Matching is case-insensitive. To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times. Breaking change: an empty string used to remove the header, pass ``None`` for that instead.
             local_address: Local address to bind the client to. Useful for testing purposes or when you want to bind the client to a specific network interface.
                 Can be an IP address in the format "xxx.xxx.xxx.xxx" (for IPv4) or "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff" (for IPv6).
+            local_address_pool: Local IP addresses or CIDR prefixes (e.g. "2001:db8::/48") to send the requests from. Takes precedence over `local_address`.
+            local_address_selection: How often a new address is picked from `local_address_pool` ("request", "host" or "session", default: "session").
+            interface: Name of the network interface to send the requests through (e.g. "eth1" or "wg0"). Only supported on Linux.
+            resolve: Static DNS overrides in the curl's `--resolve` format (e.g. "example.com:443:127.0.0.1").
+            nameservers: Nameservers to use instead of the system ones (e.g. "8.8.8.8", "tls://1.1.1.1" or "https://1.1.1.1/dns-query").
+            ip_family: Which IP address family to connect over ("prefer-ipv6", "prefer-ipv4", "ipv4-only" or "ipv6-only", default: "prefer-ipv6").
+            connection_attempt_delay: Delay in seconds between the Happy Eyeballs connection attempts for HTTP/3 (default: 0.3).
+            connect_to: Rules to connect to a different host or port, in the curl's `--connect-to` format (e.g. "example.com:443:origin.example.net:8443").
+            server_name: Server name to send in the TLS handshake instead of the URL hostname. Over TCP, the requests then use HTTP/1.1 only.
+            sni: Set to False to send the TLS handshakes without the SNI extension (default: True).
+            quic_head_start: Seconds the QUIC connection is waited for before the request goes over TCP (default: 0.3).
+            max_retries: Maximum number of retries of the failed requests (default: 2 if any retry argument is set, otherwise 0).
+            retry_status_codes: Response status codes to retry (default: [429, 503]).
+            retry_backoff: Seconds to wait before the first retry, doubled for every further retry (default: 0.5).
+            rate_limit: Maximum number of requests started per second, can be a fraction (default: no limit).
+            max_concurrency: Maximum number of requests in flight at the same time (default: no limit).
+            limit_by: What the limits are counted by - 'host', 'domain' (registrable domain) or 'proxy' (default: 'host').
+            queue_timeout: Seconds a request can wait for the limits before raising PoolTimeout (default: no timeout).
+            circuit_breaker_threshold: Consecutive failures after which the requests to a host raise CircuitOpen (default: 5 if any circuit breaker argument is set).
+            circuit_breaker_cooldown: Seconds before a probe request is let through to an open circuit (default: 30).
+            track_navigation: Send the Referer and Sec-Fetch-Site headers from the previously visited page (default: False).
+            referrer_policy: Default referrer policy of the visited pages (default: 'strict-origin-when-cross-origin').
+            header_position: Position of the extra custom headers: 'start', 'end' (default), 'before:<header>' or 'after:<header>'.
+        """
+
+    def circuit_state(self, host: str) -> Literal['closed', 'open', 'half-open']:
+        """Return the state of the circuit breaker for the given host.
+
+        The circuits are always ``'closed'`` unless ``circuit_breaker_threshold`` or ``circuit_breaker_cooldown``
+        is set.
+
+        Args:
+            host: Hostname of the server, e.g. ``"example.com"``.
         """
 
     async def get(
@@ -861,9 +1325,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous GET request.
 
@@ -871,9 +1337,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def post(
@@ -881,9 +1349,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous POST request.
 
@@ -891,9 +1361,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
 
         """
 
@@ -902,9 +1374,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous PUT request.
 
@@ -912,9 +1386,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def patch(
@@ -922,9 +1398,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous PATCH request.
 
@@ -932,9 +1410,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def delete(
@@ -942,9 +1422,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous DELETE request.
 
@@ -952,9 +1434,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def head(
@@ -962,9 +1446,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous HEAD request.
 
@@ -972,9 +1458,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def options(
@@ -982,9 +1470,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous OPTIONS request.
 
@@ -992,9 +1482,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def trace(
@@ -1002,9 +1494,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous TRACE request.
 
@@ -1012,9 +1506,11 @@ class AsyncClient:
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     async def request(
@@ -1023,22 +1519,26 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
         stream: bool = False,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> Response:
         """Make an asynchronous HTTP request with the specified method.
 
         Args:
-            method: HTTP method (e.g., "get", "post")
+            method: HTTP method (e.g., "get", "post" or "propfind"), sent in uppercase. ``CONNECT`` and ``TRACK`` are rejected
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
             stream: Whether to return a streaming response (default: False)
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
     def stream(
@@ -1047,9 +1547,11 @@ class AsyncClient:
         url: str,
         content: bytes | bytearray | str | list[int] | Iterable[bytes] | AsyncIterable[bytes] | None = None,
         data: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: HeaderTypes | None = None,
         timeout: float | str | None = USE_CLIENT_DEFAULT,
         force_http3: bool | None = None,
+        follow_redirects: bool | None = None,
+        destination: Literal['document', 'iframe', 'fetch', 'script', 'style', 'image'] | None = None,
     ) -> AbstractAsyncContextManager[Response]:
         """Make an asynchronous streaming request with the specified method.
 
@@ -1065,13 +1567,15 @@ class AsyncClient:
                         process(chunk)  # Process each chunk as it is received
 
         Args:
-            method: HTTP method (e.g., "get", "post")
+            method: HTTP method (e.g., "get", "post" or "propfind"), sent in uppercase. ``CONNECT`` and ``TRACK`` are rejected
             url: URL to request
             content: Raw content to send
             data: Form data to send in request body
-            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass an empty string as the value
+            headers: HTTP headers for this request. Override both client-level and impersonation headers (case-insensitive). To remove an impersonated header, pass ``None`` as the value. An empty string sends the header with an empty value, a list of values or a repeated name in a sequence of pairs sends the header several times
             timeout: Per-request timeout in seconds. Pass ``None`` to disable the timeout entirely. Defaults to ``USE_CLIENT_DEFAULT`` (inherits the client-level timeout).
             force_http3: Force HTTP/3 protocol
+            follow_redirects: Whether to follow the redirects for this request. Defaults to the client's ``follow_redirects``.
+            destination: What the request fetches. Adjusts the impersonated ``Accept``, ``Sec-Fetch-*``, ``Upgrade-Insecure-Requests`` and ``Priority`` headers, e.g. ``'fetch'`` for API calls. Defaults to ``'document'`` (a page navigation).
         """
 
 
@@ -1080,7 +1584,7 @@ def stream(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1092,7 +1596,7 @@ def stream(
     """Make a streaming request without creating a client instance.
 
     Args:
-        method: HTTP method (e.g., "get", "post")
+        method: HTTP method (e.g., "get", "post" or "propfind"), sent in uppercase. ``CONNECT`` and ``TRACK`` are rejected
         url: URL to request
         content: Raw content to send
         data: Form data to send in request body
@@ -1113,7 +1617,7 @@ def get(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1146,7 +1650,7 @@ def post(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1179,7 +1683,7 @@ def put(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1212,7 +1716,7 @@ def patch(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1245,7 +1749,7 @@ def delete(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1278,7 +1782,7 @@ def head(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1311,7 +1815,7 @@ def options(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
@@ -1341,7 +1845,7 @@ def trace(
     url: str,
     content: bytes | bytearray | str | list[int] | Iterable[bytes] | None = None,
     data: dict[str, str] | None = None,
-    headers: dict[str, str] | None = None,
+    headers: HeaderTypes | None = None,
     timeout: float | str | None = USE_CLIENT_DEFAULT,
     force_http3: bool | None = None,
     follow_redirects: bool | None = None,
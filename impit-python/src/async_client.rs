@@ -1,8 +1,8 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use either::{Either, Right};
 use impit::{
-    errors::ImpitError,
+    fingerprint::RequestDestination,
     impit::{Impit, ImpitBuilder},
     request::RequestOptions,
 };
@@ -11,7 +11,10 @@ use pyo3::{ffi::c_str, prelude::*};
 use crate::{
     cookies::PythonCookieJar,
     errors::ImpitPyError,
-    request::{parse_timeout, to_body, RequestBody, USE_CLIENT_DEFAULT_SENTINEL},
+    request::{
+        parse_timeout, redirect_behavior, to_body, Headers, RequestBody,
+        USE_CLIENT_DEFAULT_SENTINEL,
+    },
     response::ImpitPyResponse,
 };
 
@@ -19,6 +22,8 @@ use crate::{
 pub(crate) struct AsyncClient {
     impit: Arc<Impit<PythonCookieJar>>,
     default_encoding: Option<String>,
+    /// The redirect limit used by the requests overriding `follow_redirects`.
+    max_redirects: usize,
 }
 
 #[pymethods]
@@ -40,8 +45,13 @@ impl AsyncClient {
         pyo3_async_runtimes::tokio::future_into_py::<_, ()>(py, async { Ok(()) })
     }
 
+    /// Returns the state of the circuit breaker for `host` ("closed", "open" or "half-open").
+    pub fn circuit_state(&self, host: &str) -> &'static str {
+        crate::circuit_breaker::state_name(self.impit.circuit_state(host))
+    }
+
     #[new]
-    #[pyo3(signature = (browser=None, http3=None, proxy=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), verify=None, default_encoding=None, follow_redirects=None, max_redirects=Some(20), cookie_jar=None, cookies=None, headers=None, local_address=None))]
+    #[pyo3(signature = (browser=None, http3=None, proxy=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), verify=None, default_encoding=None, follow_redirects=None, max_redirects=Some(20), cookie_jar=None, cookies=None, headers=None, local_address=None, local_address_pool=None, local_address_selection=None, interface=None, resolve=None, nameservers=None, ip_family=None, connection_attempt_delay=None, connect_to=None, server_name=None, sni=None, quic_head_start=None, max_retries=None, retry_status_codes=None, retry_backoff=None, rate_limit=None, max_concurrency=None, limit_by=None, queue_timeout=None, circuit_breaker_threshold=None, circuit_breaker_cooldown=None, track_navigation=None, referrer_policy=None, header_position=None))]
     pub fn new(
         py: Python<'_>,
         browser: Option<String>,
@@ -54,8 +64,31 @@ impl AsyncClient {
         max_redirects: Option<u16>,
         cookie_jar: Option<crate::Bound<'_, crate::PyAny>>,
         cookies: Option<crate::Bound<'_, crate::PyAny>>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         local_address: Option<String>,
+        local_address_pool: Option<Vec<String>>,
+        local_address_selection: Option<String>,
+        interface: Option<String>,
+        resolve: Option<Vec<String>>,
+        nameservers: Option<Vec<String>>,
+        ip_family: Option<String>,
+        connection_attempt_delay: Option<f64>,
+        connect_to: Option<Vec<String>>,
+        server_name: Option<String>,
+        sni: Option<bool>,
+        quic_head_start: Option<f64>,
+        max_retries: Option<usize>,
+        retry_status_codes: Option<Vec<u16>>,
+        retry_backoff: Option<f64>,
+        rate_limit: Option<f64>,
+        max_concurrency: Option<usize>,
+        limit_by: Option<String>,
+        queue_timeout: Option<f64>,
+        circuit_breaker_threshold: Option<usize>,
+        circuit_breaker_cooldown: Option<f64>,
+        track_navigation: Option<bool>,
+        referrer_policy: Option<String>,
+        header_position: Option<String>,
     ) -> PyResult<Self> {
         let builder = ImpitBuilder::default();
 
@@ -87,12 +120,11 @@ impl AsyncClient {
             _ => builder,
         };
 
-        let builder = match follow_redirects {
-            Some(true) => builder.with_redirect(impit::impit::RedirectBehavior::FollowRedirect(
-                max_redirects.unwrap_or(20).into(),
-            )),
-            _ => builder.with_redirect(impit::impit::RedirectBehavior::ManualRedirect),
-        };
+        let max_redirects = max_redirects.unwrap_or(20).into();
+        let builder = builder.with_redirect(redirect_behavior(
+            follow_redirects.unwrap_or(false),
+            max_redirects,
+        ));
 
         let builder = match (cookie_jar, cookies) {
             (Some(_), Some(_)) => {
@@ -110,7 +142,7 @@ impl AsyncClient {
         };
 
         let builder = match headers {
-            Some(headers) => builder.with_headers(headers.into_iter().collect::<Vec<_>>()),
+            Some(headers) => builder.with_headers(headers.0),
             None => builder,
         };
 
@@ -121,25 +153,98 @@ impl AsyncClient {
             None => builder,
         };
 
+        let builder = match crate::local_address::local_address_pool(
+            local_address_pool,
+            local_address_selection,
+        )? {
+            Some((pool, selection)) => builder.with_local_address_pool(pool, selection),
+            None => builder,
+        };
+
+        let builder = match interface {
+            Some(interface) => builder.with_interface(interface),
+            None => builder,
+        };
+
+        let builder = match crate::dns::dns_config(resolve, nameservers)? {
+            Some(dns) => builder.with_dns(dns),
+            None => builder,
+        };
+
+        let builder = match crate::dns::happy_eyeballs(ip_family, connection_attempt_delay)? {
+            Some(happy_eyeballs) => builder.with_happy_eyeballs(happy_eyeballs),
+            None => builder,
+        };
+
+        let builder = crate::routing::connect_to_rules(connect_to)?
+            .into_iter()
+            .fold(builder, |builder, rule| builder.with_connect_to(rule));
+
+        let builder = match crate::routing::server_name(server_name, sni) {
+            Some(server_name) => builder.with_server_name(server_name),
+            None => builder,
+        };
+
+        let builder = match quic_head_start {
+            Some(head_start) => builder.with_quic_head_start(
+                Duration::try_from_secs_f64(head_start)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?,
+            ),
+            None => builder,
+        };
+
+        let builder =
+            match crate::retry::retry_policy(max_retries, retry_status_codes, retry_backoff)? {
+                Some(retry) => builder.with_retry(retry),
+                None => builder,
+            };
+
+        let builder = crate::limits::with_limits(
+            builder,
+            rate_limit,
+            max_concurrency,
+            limit_by,
+            queue_timeout,
+        )?;
+
+        let builder = match crate::circuit_breaker::circuit_breaker(
+            circuit_breaker_threshold,
+            circuit_breaker_cooldown,
+        )? {
+            Some(breaker) => builder.with_circuit_breaker(breaker),
+            None => builder,
+        };
+
+        let builder =
+            crate::navigation::with_navigation(builder, track_navigation, referrer_policy)?;
+
+        let builder = match header_position {
+            Some(position) => builder.with_header_position(position.parse().map_err(ImpitPyError)?),
+            None => builder,
+        };
+
         let impit = pyo3_async_runtimes::tokio::get_runtime()
             .block_on(async { builder.build().map_err(ImpitPyError) })?;
 
         Ok(Self {
             impit: Arc::new(impit),
             default_encoding,
+            max_redirects,
         })
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn get<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -151,19 +256,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn head<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -175,19 +284,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn post<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -199,19 +312,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn patch<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -223,19 +340,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn put<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -247,19 +368,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn delete<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -271,19 +396,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn options<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -295,19 +424,23 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn trace<'python>(
         &self,
         py: Python<'python>,
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         self.request(
             py,
@@ -319,10 +452,12 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(false),
+            follow_redirects,
+            destination,
         )
     }
 
-    #[pyo3(signature = (method, url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false))]
+    #[pyo3(signature = (method, url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, follow_redirects=None, destination=None))]
     pub fn stream<'python>(
         &self,
         py: Python<'python>,
@@ -330,9 +465,11 @@ impl AsyncClient {
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         let response = self.request(
             py,
@@ -344,6 +481,8 @@ impl AsyncClient {
             timeout,
             force_http3,
             Some(true),
+            follow_redirects,
+            destination,
         )?;
 
         let fun: Py<PyAny> = PyModule::from_code(
@@ -368,7 +507,7 @@ impl AsyncClient {
         Ok(wrapped_response.into_bound(py))
     }
 
-    #[pyo3(signature = (method, url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, stream=false))]
+    #[pyo3(signature = (method, url, content=None, data=None, headers=None, timeout=Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), force_http3=false, stream=false, follow_redirects=None, destination=None))]
     pub fn request<'python>(
         &self,
         py: Python<'python>,
@@ -376,10 +515,12 @@ impl AsyncClient {
         url: String,
         content: Option<RequestBody>,
         mut data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
         stream: Option<bool>,
+        follow_redirects: Option<bool>,
+        destination: Option<String>,
     ) -> Result<pyo3::Bound<'python, PyAny>, PyErr> {
         let mut headers = headers.clone();
 
@@ -391,14 +532,20 @@ impl AsyncClient {
 
         let timeout = parse_timeout(timeout)?;
 
+        let destination = destination
+            .map(|destination| destination.parse::<RequestDestination>())
+            .transpose()
+            .map_err(ImpitPyError)?
+            .unwrap_or_default();
+
         let options = RequestOptions {
-            headers: headers
-                .unwrap_or_default()
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect(),
+            headers: headers.unwrap_or_default().0,
             timeout,
             http3_prior_knowledge: force_http3.unwrap_or(false),
+            redirect: follow_redirects
+                .map(|follow_redirects| redirect_behavior(follow_redirects, self.max_redirects)),
+            destination,
+            ..Default::default()
         };
 
         let method_str = method.to_string();
@@ -407,16 +554,13 @@ impl AsyncClient {
         let impit = Arc::clone(&self.impit);
 
         pyo3_async_runtimes::tokio::future_into_py::<_, ImpitPyResponse>(py, async move {
-            let response = match method_str.to_lowercase().as_str() {
-                "get" => impit.get(url, Some(body), Some(options)).await,
-                "post" => impit.post(url, Some(body), Some(options)).await,
-                "patch" => impit.patch(url, Some(body), Some(options)).await,
-                "put" => impit.put(url, Some(body), Some(options)).await,
-                "options" => impit.options(url, Some(body), Some(options)).await,
-                "trace" => impit.trace(url, Some(body), Some(options)).await,
-                "head" => impit.head(url, Some(body), Some(options)).await,
-                "delete" => impit.delete(url, Some(body), Some(options)).await,
-                _ => Err(ImpitError::InvalidMethod(method_str.to_string())),
+            // Like httpx, the methods are always sent in uppercase.
+            let method = method_str.to_uppercase();
+            // `Impit::request` rejects `TRACE` like the browsers do, `trace()` is kept for httpx compatibility.
+            let response = if method == "TRACE" {
+                impit.trace(url, Some(body), Some(options)).await
+            } else {
+                impit.request(&method, url, Some(body), Some(options)).await
             };
 
             match response {
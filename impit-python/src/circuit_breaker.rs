@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use impit::circuit_breaker::{CircuitBreaker, CircuitState};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// Builds the circuit breaker from the `circuit_breaker_threshold` and `circuit_breaker_cooldown` client arguments.
+///
+/// The cooldown is given in seconds.
+pub(crate) fn circuit_breaker(
+    threshold: Option<usize>,
+    cooldown: Option<f64>,
+) -> PyResult<Option<CircuitBreaker>> {
+    if threshold.is_none() && cooldown.is_none() {
+        return Ok(None);
+    }
+
+    let mut breaker = CircuitBreaker::default();
+    if let Some(threshold) = threshold {
+        breaker.failure_threshold = threshold;
+    }
+    if let Some(cooldown) = cooldown {
+        breaker.cooldown = Duration::try_from_secs_f64(cooldown)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+
+    Ok(Some(breaker))
+}
+
+/// Returns the name of the circuit state returned by `circuit_state`.
+pub(crate) fn state_name(state: CircuitState) -> &'static str {
+    match state {
+        CircuitState::Closed => "closed",
+        CircuitState::Open(_) => "open",
+        CircuitState::HalfOpen => "half-open",
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use impit::dns::{DnsConfig, HappyEyeballs, IpFamily, Nameserver};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+use crate::errors::ImpitPyError;
+
+/// Builds the DNS configuration from the `resolve` and `nameservers` client arguments.
+pub(crate) fn dns_config(
+    resolve: Option<Vec<String>>,
+    nameservers: Option<Vec<String>>,
+) -> PyResult<Option<DnsConfig>> {
+    if resolve.is_none() && nameservers.is_none() {
+        return Ok(None);
+    }
+
+    let mut config = DnsConfig::default();
+    for entry in resolve.unwrap_or_default() {
+        config = config.with_resolve(&entry).map_err(ImpitPyError)?;
+    }
+    for nameserver in nameservers.unwrap_or_default() {
+        config = config.with_nameserver(nameserver.parse::<Nameserver>().map_err(ImpitPyError)?);
+    }
+
+    Ok(Some(config))
+}
+
+/// Builds the Happy Eyeballs settings from the `ip_family` and `connection_attempt_delay` client arguments.
+///
+/// The connection attempt delay is given in seconds.
+pub(crate) fn happy_eyeballs(
+    ip_family: Option<String>,
+    connection_attempt_delay: Option<f64>,
+) -> PyResult<Option<HappyEyeballs>> {
+    if ip_family.is_none() && connection_attempt_delay.is_none() {
+        return Ok(None);
+    }
+
+    let mut happy_eyeballs = HappyEyeballs::default();
+    if let Some(ip_family) = ip_family {
+        happy_eyeballs.ip_family = match ip_family.as_str() {
+            "prefer-ipv6" => IpFamily::PreferIpv6,
+            "prefer-ipv4" => IpFamily::PreferIpv4,
+            "ipv4-only" => IpFamily::Ipv4Only,
+            "ipv6-only" => IpFamily::Ipv6Only,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Invalid ip_family: {ip_family}. Expected one of 'prefer-ipv6', 'prefer-ipv4', 'ipv4-only', 'ipv6-only'."
+                )))
+            }
+        };
+    }
+    if let Some(delay) = connection_attempt_delay {
+        happy_eyeballs.connection_attempt_delay =
+            Duration::try_from_secs_f64(delay).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+
+    Ok(Some(happy_eyeballs))
+}
@@ -20,6 +20,7 @@ create_exception!(impit, LocalProtocolError, ProtocolError);
 create_exception!(impit, RemoteProtocolError, ProtocolError);
 create_exception!(impit, ProxyError, TransportError);
 create_exception!(impit, UnsupportedProtocol, TransportError);
+create_exception!(impit, CircuitOpen, TransportError);
 create_exception!(impit, DecodingError, RequestError);
 create_exception!(impit, TooManyRedirects, RequestError);
 create_exception!(impit, HTTPStatusError, HTTPError);
@@ -54,7 +55,9 @@ impl From<ImpitPyError> for pyo3::PyErr {
             }
             ImpitPyError(ImpitError::ReadTimeout) => ReadTimeout::new_err(format!("{}", err.0)),
             ImpitPyError(ImpitError::WriteTimeout) => WriteTimeout::new_err(format!("{}", err.0)),
-            ImpitPyError(ImpitError::PoolTimeout) => PoolTimeout::new_err(format!("{}", err.0)),
+            ImpitPyError(ImpitError::PoolTimeout) | ImpitPyError(ImpitError::QueueTimeout(_)) => {
+                PoolTimeout::new_err(format!("{}", err.0))
+            }
             ImpitPyError(ImpitError::NetworkError) => NetworkError::new_err(format!("{}", err.0)),
             ImpitPyError(ImpitError::ConnectError(_)) => {
                 ConnectError::new_err(format!("{}", err.0))
@@ -66,7 +69,9 @@ impl From<ImpitPyError> for pyo3::PyErr {
             ImpitPyError(ImpitError::LocalProtocolError) => {
                 LocalProtocolError::new_err(format!("{}", err.0))
             }
-            ImpitPyError(ImpitError::RemoteProtocolError) => {
+            ImpitPyError(ImpitError::RemoteProtocolError)
+            | ImpitPyError(ImpitError::RefusedStream(_))
+            | ImpitPyError(ImpitError::GoAway(_)) => {
                 RemoteProtocolError::new_err(format!("{}", err.0))
             }
             ImpitPyError(ImpitError::ProxyError(_)) => ProxyError::new_err(format!("{}", err.0)),
@@ -76,9 +81,13 @@ impl From<ImpitPyError> for pyo3::PyErr {
             ImpitPyError(ImpitError::ProxyAuthRequired) => {
                 ProxyError::new_err(format!("{}", err.0))
             }
+            ImpitPyError(ImpitError::Http3ProxyUnsupported(_)) => {
+                ProxyError::new_err(format!("{}", err.0))
+            }
             ImpitPyError(ImpitError::UnsupportedProtocol) => {
                 UnsupportedProtocol::new_err(format!("{}", err.0))
             }
+            ImpitPyError(ImpitError::CircuitOpen(_)) => CircuitOpen::new_err(format!("{}", err.0)),
             ImpitPyError(ImpitError::DecodingError) => DecodingError::new_err(format!("{}", err.0)),
             ImpitPyError(ImpitError::TooManyRedirects(_)) => {
                 TooManyRedirects::new_err(format!("{}", err.0))
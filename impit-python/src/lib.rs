@@ -2,17 +2,23 @@ use either::{Either, Right};
 use pyo3::prelude::*;
 
 mod async_client;
+mod circuit_breaker;
 mod client;
 mod cookies;
+mod dns;
 mod errors;
 mod fingerprint;
+mod limits;
+mod local_address;
+mod navigation;
 mod request;
 mod response;
+mod retry;
+mod routing;
 
 use async_client::AsyncClient;
 use client::Client;
-use request::{RequestBody, USE_CLIENT_DEFAULT_SENTINEL};
-use std::collections::HashMap;
+use request::{Headers, RequestBody, USE_CLIENT_DEFAULT_SENTINEL};
 
 #[pymodule]
 fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -56,6 +62,7 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
         "UnsupportedProtocol",
         m.py().get_type::<errors::UnsupportedProtocol>(),
     )?;
+    m.add("CircuitOpen", m.py().get_type::<errors::CircuitOpen>())?;
     m.add("DecodingError", m.py().get_type::<errors::DecodingError>())?;
     m.add(
         "TooManyRedirects",
@@ -95,7 +102,7 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
                 url: String,
                 content: Option<RequestBody>,
                 data: Option<RequestBody>,
-                headers: Option<HashMap<String, String>>,
+                headers: Option<Headers>,
                 timeout: Option<Either<f64, &str>>,
                 force_http3: Option<bool>,
                 cookie_jar: Option<pyo3::Bound<'_, pyo3::PyAny>>,
@@ -104,9 +111,9 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
                 max_redirects: Option<u16>,
                 proxy: Option<String>,
             ) -> PyResult<response::ImpitPyResponse> {
-                let client = Client::new(_py, None, None, proxy, Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), None, None, follow_redirects, max_redirects, cookie_jar, cookies, None, None);
+                let client = Client::new(_py, None, None, proxy, Some(Right(USE_CLIENT_DEFAULT_SENTINEL)), None, None, follow_redirects, max_redirects, cookie_jar, cookies, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None);
 
-                Ok(client?.$name(_py, url, content, data, headers, timeout, force_http3)?)
+                Ok(client?.$name(_py, url, content, data, headers, timeout, force_http3, None, None)?)
             }
 
             m.add_function(wrap_pyfunction!($name, m)?)?;
@@ -124,7 +131,7 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
         url: String,
         content: Option<RequestBody>,
         data: Option<RequestBody>,
-        headers: Option<HashMap<String, String>>,
+        headers: Option<Headers>,
         timeout: Option<Either<f64, &str>>,
         force_http3: Option<bool>,
         cookie_jar: Option<pyo3::Bound<'_, pyo3::PyAny>>,
@@ -147,6 +154,29 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
             cookies,
             None,
             None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         client?.stream(
@@ -158,6 +188,8 @@ fn impit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
             headers,
             timeout,
             force_http3,
+            None,
+            None,
         )
     }
 
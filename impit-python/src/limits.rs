@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use impit::{
+    impit::ImpitBuilder,
+    limits::{ConcurrencyLimit, LimitKey, RateLimit},
+};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+fn limit_key(limit_by: Option<String>) -> PyResult<LimitKey> {
+    match limit_by.as_deref() {
+        None | Some("host") => Ok(LimitKey::Host),
+        Some("domain") => Ok(LimitKey::RegistrableDomain),
+        Some("proxy") => Ok(LimitKey::Proxy),
+        Some(other) => Err(PyValueError::new_err(format!(
+            "Invalid limit_by value: {other}. Expected one of: host, domain, proxy"
+        ))),
+    }
+}
+
+/// Applies the `rate_limit`, `max_concurrency`, `limit_by` and `queue_timeout` client arguments to the builder.
+///
+/// The rate limit is given in requests per second, the queue timeout in seconds.
+pub(crate) fn with_limits(
+    builder: ImpitBuilder,
+    rate_limit: Option<f64>,
+    max_concurrency: Option<usize>,
+    limit_by: Option<String>,
+    queue_timeout: Option<f64>,
+) -> PyResult<ImpitBuilder> {
+    let key = limit_key(limit_by)?;
+    let mut builder = builder;
+
+    if let Some(rate_limit) = rate_limit {
+        if rate_limit.is_nan() || rate_limit <= 0.0 {
+            return Err(PyValueError::new_err(
+                "rate_limit must be a positive number",
+            ));
+        }
+        builder = builder.with_rate_limit(RateLimit::per_second(key, rate_limit));
+    }
+    if let Some(max_concurrent) = max_concurrency {
+        builder = builder.with_concurrency_limit(ConcurrencyLimit {
+            key,
+            max_concurrent,
+        });
+    }
+    if let Some(queue_timeout) = queue_timeout {
+        builder = builder.with_queue_timeout(
+            Duration::try_from_secs_f64(queue_timeout)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        );
+    }
+
+    Ok(builder)
+}
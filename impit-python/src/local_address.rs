@@ -0,0 +1,32 @@
+use impit::local_address::{LocalAddressPool, LocalAddressSelection};
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+use crate::errors::ImpitPyError;
+
+/// Builds the local address pool from the `local_address_pool` and `local_address_selection` client arguments.
+///
+/// Shared by the sync and async clients so the two bindings cannot drift apart.
+pub(crate) fn local_address_pool(
+    pool: Option<Vec<String>>,
+    selection: Option<String>,
+) -> PyResult<Option<(LocalAddressPool, LocalAddressSelection)>> {
+    let selection = match selection.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("session") => LocalAddressSelection::PerSession,
+        Some("request") => LocalAddressSelection::PerRequest,
+        Some("host") => LocalAddressSelection::PerHost,
+        Some(other) => {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported local address selection: {other}. Use 'request', 'host' or 'session'."
+            )))
+        }
+    };
+
+    match pool {
+        Some(pool) => Ok(Some((
+            LocalAddressPool::new(pool).map_err(ImpitPyError)?,
+            selection,
+        ))),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,21 @@
+use impit::{impit::ImpitBuilder, navigation::ReferrerPolicy};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// Applies the `track_navigation` and `referrer_policy` client arguments to the builder.
+pub(crate) fn with_navigation(
+    builder: ImpitBuilder,
+    track_navigation: Option<bool>,
+    referrer_policy: Option<String>,
+) -> PyResult<ImpitBuilder> {
+    let policy = match referrer_policy {
+        Some(policy) => policy
+            .parse::<ReferrerPolicy>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        None => ReferrerPolicy::default(),
+    };
+
+    Ok(match track_navigation {
+        Some(true) => builder.with_navigation(policy),
+        _ => builder,
+    })
+}
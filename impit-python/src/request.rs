@@ -3,7 +3,10 @@ use std::{collections::HashMap, io, time::Duration};
 use bytes::Bytes;
 use either::{Either, Left, Right};
 use futures::{stream, Stream};
-use impit::request::ImpitBody;
+use impit::{
+    impit::RedirectBehavior,
+    request::{HeaderOp, ImpitBody},
+};
 use pyo3::{
     exceptions::{PyStopAsyncIteration, PyStopIteration, PyTypeError},
     types::{PyAnyMethods, PyMapping},
@@ -41,6 +44,15 @@ pub(crate) fn parse_timeout(
     }
 }
 
+/// Builds the redirect behavior from the `follow_redirects` and `max_redirects` arguments.
+pub(crate) fn redirect_behavior(follow_redirects: bool, max_redirects: usize) -> RedirectBehavior {
+    if follow_redirects {
+        RedirectBehavior::FollowRedirect(max_redirects)
+    } else {
+        RedirectBehavior::ManualRedirect
+    }
+}
+
 use pyo3::FromPyObject;
 
 #[derive(FromPyObject)]
@@ -57,6 +69,54 @@ pub(crate) enum RequestBody {
     CatchAll(Py<PyAny>), // This extraction never fails
 }
 
+/// The request header operations, in the order of the Python mapping or the sequence of pairs they were passed as.
+///
+/// A `None` value removes the header, an empty string sends it with an empty value and a list of strings or a repeated
+/// name sends the header several times.
+#[derive(Default, Clone)]
+pub(crate) struct Headers(pub(crate) Vec<HeaderOp>);
+
+impl Headers {
+    /// Sets the header, replacing the operation on the same-named one in place.
+    pub(crate) fn insert(&mut self, name: String, value: String) {
+        match self.0.iter_mut().find(|op| op.name() == name) {
+            Some(op) => *op = HeaderOp::Set(name, value),
+            None => self.0.push(HeaderOp::Set(name, value)),
+        }
+    }
+}
+
+impl<'py> FromPyObject<'_, 'py> for Headers {
+    type Error = PyErr;
+
+    fn extract(object: Borrowed<'_, 'py, PyAny>) -> PyResult<Self> {
+        // `HashMap` would lose the order the headers are sent in.
+        let items = if !object.is_instance_of::<PyMapping>() {
+            object.to_owned()
+        } else if object.hasattr("multi_items")? {
+            // `httpx.Headers` keeps the repeated headers apart this way.
+            object.call_method0("multi_items")?
+        } else {
+            object.call_method0("items")?
+        };
+
+        let mut entries: Vec<(String, Option<String>)> = vec![];
+        for item in items.try_iter()? {
+            let (name, value) = item?.extract::<(String, Option<Either<String, Vec<String>>>)>()?;
+            match value {
+                None => entries.push((name, None)),
+                Some(Left(value)) => entries.push((name, Some(value))),
+                Some(Right(values)) if values.is_empty() => entries.push((name, None)),
+                Some(Right(values)) => {
+                    entries.extend(values.into_iter().map(|value| (name.clone(), Some(value))))
+                }
+            }
+        }
+
+        Ok(Self(HeaderOp::from_entries(entries)))
+    }
+}
+
 /// A Python iterator over the chunks of a request body.
 pub(crate) enum PyIterator {
     Sync(Py<PyAny>),
@@ -131,7 +191,7 @@ fn to_stream(iterator: PyIterator) -> impl Stream<Item = io::Result<Bytes>> {
 /// Converts the Python request body into an [`ImpitBody`], streaming it if it is an iterator.
 pub(crate) fn to_body(
     data: Option<RequestBody>,
-    headers: &mut Option<HashMap<String, String>>,
+    headers: &mut Option<Headers>,
 ) -> PyResult<ImpitBody> {
     Ok(match data {
         None => ImpitBody::Empty,
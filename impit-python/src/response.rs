@@ -1,11 +1,17 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
 
 use bytes::Bytes;
 use encoding::label::encoding_from_whatwg_label;
 use futures::{Stream, StreamExt};
-use impit::{errors::ImpitError, utils::ContentType};
+use impit::{
+    errors::ImpitError,
+    local_address::SourceAddress,
+    redirect::{RedirectHistory, RedirectHop},
+    utils::ContentType,
+};
 use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 use reqwest::{Response, StatusCode, Version};
@@ -208,16 +214,20 @@ pub struct ImpitPyResponse {
     is_closed: bool,
     #[pyo3(get)]
     is_stream_consumed: bool,
+    #[pyo3(get)]
+    source_address: Option<String>,
+    // The method of the redirected request, only set for the responses in `history`.
+    #[pyo3(get)]
+    method: Option<String>,
+    // The time until the redirect response headers arrived, only set for the responses in `history`.
+    #[pyo3(get)]
+    elapsed: Option<Duration>,
     // #[pyo3(get)]
     // request: Request,
     // #[pyo3(get)]
     // next_request: Option<Request>,
     // #[pyo3(get)]
     // cookies: Cookies,
-    // #[pyo3(get)]
-    // history: Vec<Response>,
-    // #[pyo3(get)]
-    // elapsed: Duration,
     text: Option<String>,
     content: Option<Vec<u8>>,
     inner: Option<Response>,
@@ -225,6 +235,8 @@ pub struct ImpitPyResponse {
     // Raw, undecoded header name/value byte pairs. The `headers` getter builds the Python-side
     // `Headers` object (httpx-style: str access + `.raw`) from these exact wire bytes.
     raw_headers: Vec<(Vec<u8>, Vec<u8>)>,
+    // The redirects followed before this response, turned into Python responses by the `history` getter.
+    redirects: Vec<RedirectHop>,
 }
 
 #[pymethods]
@@ -270,14 +282,24 @@ impl ImpitPyResponse {
             url: url.unwrap_or_default(),
             is_closed: true,
             is_stream_consumed: true,
+            source_address: None,
+            method: None,
+            elapsed: None,
             text: None,
             content: Some(content.unwrap_or_default()),
             inner: None,
             inner_state: InnerResponseState::Read,
             raw_headers,
+            redirects: Vec::new(),
         }
     }
 
+    /// The redirect responses followed before this response, oldest first.
+    #[getter]
+    fn history(&self) -> Vec<ImpitPyResponse> {
+        self.redirects.iter().map(Self::from_hop).collect()
+    }
+
     fn __repr__(&self) -> String {
         format!("<Response [{} {}]>", self.status_code, self.reason_phrase)
     }
@@ -553,15 +575,17 @@ impl ImpitPyResponse {
             .canonical_reason()
             .unwrap_or_default()
             .to_string();
-        let http_version = match val.version() {
-            Version::HTTP_09 => "HTTP/0.9".to_string(),
-            Version::HTTP_10 => "HTTP/1.0".to_string(),
-            Version::HTTP_11 => "HTTP/1.1".to_string(),
-            Version::HTTP_2 => "HTTP/2".to_string(),
-            Version::HTTP_3 => "HTTP/3".to_string(),
-            _ => "Unknown".to_string(),
-        };
+        let http_version = http_version_name(val.version());
         let is_redirect = val.status().is_redirection();
+        let source_address = val
+            .extensions()
+            .get::<SourceAddress>()
+            .map(|address| address.0.to_string());
+        let redirects = val
+            .extensions()
+            .get::<RedirectHistory>()
+            .map(|history| history.0.clone())
+            .unwrap_or_default();
         // Exact wire header bytes; the Python `Headers` object (str access + `.raw`) is built from
         // these, and it — not Rust — chooses the decoding (ascii/utf-8/iso-8859-1), matching httpx.
         let raw_headers: Vec<(Vec<u8>, Vec<u8>)> = val
@@ -625,9 +649,57 @@ impl ImpitPyResponse {
             content,
             is_closed,
             is_stream_consumed,
+            source_address,
+            method: None,
+            elapsed: None,
             inner_state,
             inner,
             raw_headers,
+            redirects,
         })
     }
+
+    /// Builds a closed response without a body from a followed redirect.
+    fn from_hop(hop: &RedirectHop) -> Self {
+        let raw_headers = hop
+            .headers
+            .iter()
+            .map(|(k, v)| (k.as_str().as_bytes().to_vec(), v.as_bytes().to_vec()))
+            .collect();
+
+        ImpitPyResponse {
+            status_code: hop.status.as_u16(),
+            reason_phrase: hop
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+            http_version: http_version_name(hop.version),
+            encoding: "utf-8".to_string(),
+            is_redirect: true,
+            url: hop.url.to_string(),
+            is_closed: true,
+            is_stream_consumed: true,
+            source_address: None,
+            method: Some(hop.method.clone()),
+            elapsed: Some(hop.elapsed),
+            text: None,
+            content: Some(Vec::new()),
+            inner: None,
+            inner_state: InnerResponseState::Read,
+            raw_headers,
+            redirects: Vec::new(),
+        }
+    }
+}
+
+fn http_version_name(version: Version) -> String {
+    match version {
+        Version::HTTP_09 => "HTTP/0.9".to_string(),
+        Version::HTTP_10 => "HTTP/1.0".to_string(),
+        Version::HTTP_11 => "HTTP/1.1".to_string(),
+        Version::HTTP_2 => "HTTP/2".to_string(),
+        Version::HTTP_3 => "HTTP/3".to_string(),
+        _ => "Unknown".to_string(),
+    }
 }
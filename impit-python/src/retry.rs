@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use impit::retry::RetryPolicy;
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// Builds the retry policy from the `max_retries`, `retry_status_codes` and `retry_backoff` client arguments.
+///
+/// The backoff is given in seconds.
+pub(crate) fn retry_policy(
+    max_retries: Option<usize>,
+    retry_status_codes: Option<Vec<u16>>,
+    retry_backoff: Option<f64>,
+) -> PyResult<Option<RetryPolicy>> {
+    if max_retries.is_none() && retry_status_codes.is_none() && retry_backoff.is_none() {
+        return Ok(None);
+    }
+
+    let mut retry = RetryPolicy::default();
+    if let Some(max_retries) = max_retries {
+        retry.max_attempts = max_retries + 1;
+    }
+    if let Some(statuses) = retry_status_codes {
+        retry.statuses = statuses;
+    }
+    if let Some(backoff) = retry_backoff {
+        retry.initial_backoff = Duration::try_from_secs_f64(backoff)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+
+    Ok(Some(retry))
+}
@@ -0,0 +1,22 @@
+use impit::routing::{ConnectTo, ServerName};
+use pyo3::PyResult;
+
+use crate::errors::ImpitPyError;
+
+/// Parses the `connect_to` client argument.
+pub(crate) fn connect_to_rules(rules: Option<Vec<String>>) -> PyResult<Vec<ConnectTo>> {
+    rules
+        .unwrap_or_default()
+        .iter()
+        .map(|rule| Ok(rule.parse::<ConnectTo>().map_err(ImpitPyError)?))
+        .collect()
+}
+
+/// Builds the TLS server name from the `server_name` and `sni` client arguments.
+pub(crate) fn server_name(server_name: Option<String>, sni: Option<bool>) -> Option<ServerName> {
+    match (server_name, sni) {
+        (_, Some(false)) => Some(ServerName::Disabled),
+        (Some(server_name), _) => Some(ServerName::Custom(server_name)),
+        (None, _) => None,
+    }
+}
@@ -342,6 +342,21 @@ class TestBasicRequests:
         assert response.status_code == 200
         assert 'Sec-Fetch-User' not in response.json()['headers']
 
+    def test_fetch_destination_headers(self, browser: Browser) -> None:
+        if browser is None:
+            pytest.skip('No browser impersonation')
+
+        impit = Client(browser=browser)
+
+        response = impit.get(get_httpbin_url('/headers'), destination='fetch')
+        assert response.status_code == 200
+        headers = response.json()['headers']
+        assert headers['Accept'] == '*/*'
+        assert headers['Sec-Fetch-Dest'] == 'empty'
+        assert headers['Sec-Fetch-Mode'] == 'cors'
+        assert 'Sec-Fetch-User' not in headers
+        assert 'Upgrade-Insecure-Requests' not in headers
+
     @pytest.mark.skip(reason='Flaky under the CI environment')
     def test_http3_works(self, browser: Browser) -> None:
         impit = Client(browser=browser, http3=True)
@@ -400,6 +415,22 @@ class TestBasicRequests:
 
         assert response.url == target_url
 
+    def test_redirect_history(self, browser: Browser) -> None:
+        impit = Client(browser=browser, follow_redirects=True)
+
+        response = impit.get(get_httpbin_url('/absolute-redirect/2'))
+
+        assert response.status_code == 200
+        assert [r.status_code for r in response.history] == [302, 302]
+        assert response.history[0].url == get_httpbin_url('/absolute-redirect/2')
+        assert response.history[1].headers['location'] == response.url
+        assert all(r.is_redirect for r in response.history)
+        assert [r.method for r in response.history] == ['GET', 'GET']
+        assert all(r.elapsed.total_seconds() > 0 for r in response.history)
+        assert response.method is None
+
+        assert impit.get(response.url).history == []
+
     def test_limit_redirects(self, browser: Browser) -> None:
         impit = Client(browser=browser, follow_redirects=True, max_redirects=1)
 
@@ -487,6 +518,96 @@ class TestRequestBody:
         assert sent_before_next_chunk == [True], 'the whole body was read before the request was sent'
         assert response.text.endswith('5\r\nfirst\r\n6\r\nsecond\r\n0\r\n\r\n')
 
+    def test_header_order(self, browser: Browser) -> None:
+        if browser is None:
+            pytest.skip('No browser impersonation')
+
+        def header_names(request: str) -> list[str]:
+            head = request.split('\r\n\r\n')[0]
+            return [line.split(':')[0].lower() for line in head.split('\r\n')[1:]]
+
+        impit = Client(browser=browser, header_position='after:user-agent')
+
+        with echoing_server() as port:
+            baseline = header_names(impit.get(f'http://localhost:{port}/', timeout=5).text)
+        with echoing_server() as port:
+            response = impit.get(
+                f'http://localhost:{port}/',
+                headers={'Accept-Language': 'de', 'X-B': '2', 'X-A': '1'},
+                timeout=5,
+            )
+        names = header_names(response.text)
+
+        assert names[0] == 'host'
+        assert [name for name in names if not name.startswith('x-')] == baseline
+        assert names.index('x-b') == names.index('user-agent') + 1
+        assert names.index('x-a') == names.index('x-b') + 1
+
+    def test_header_operations(self, browser: Browser) -> None:
+        impit = Client(browser=browser, headers={'X-Client': 'client', 'Accept-Language': 'de'})
+
+        with echoing_server() as port:
+            response = impit.get(
+                f'http://localhost:{port}/',
+                headers={
+                    'X-Forwarded-For': ['1.1.1.1', '2.2.2.2'],
+                    'X-Empty': '',
+                    'X-Client': None,
+                    'Accept-Language': None,
+                },
+                timeout=5,
+            )
+
+        head = response.text.split('\r\n\r\n')[0].lower()
+
+        assert '\r\nx-forwarded-for: 1.1.1.1\r\nx-forwarded-for: 2.2.2.2\r\n' in head
+        assert '\r\nx-empty: \r\n' in head
+        assert 'x-client' not in head
+        assert 'accept-language' not in head
+
+        with echoing_server() as port:
+            response = impit.get(
+                f'http://localhost:{port}/',
+                headers=[('Accept', 'text/html'), ('Accept', 'text/plain')],
+                timeout=5,
+            )
+
+        head = response.text.split('\r\n\r\n')[0].lower()
+
+        assert '\r\naccept: text/html\r\naccept: text/plain\r\n' in head
+
+    def test_cookie_header_position(self, browser: Browser) -> None:
+        if browser is None:
+            pytest.skip('No browser impersonation')
+
+        cookies = Cookies()
+        cookies.set('preset-cookie', '123', domain='localhost')
+        impit = Client(browser=browser, cookies=cookies)
+
+        with echoing_server() as port:
+            response = impit.get(f'http://localhost:{port}/', timeout=5)
+
+        head = response.text.split('\r\n\r\n')[0]
+        names = [line.split(':')[0].lower() for line in head.split('\r\n')[1:]]
+        previous = {'chrome': 'accept-language', 'firefox': 'accept-encoding'}[browser]
+
+        assert names.index('cookie') == names.index(previous) + 1
+
+    def test_http1_header_case(self, browser: Browser) -> None:
+        impit = Client(browser=browser)
+
+        with echoing_server() as port:
+            response = impit.get(
+                f'http://localhost:{port}/', headers={'User-Agent': 'impit', 'x-CusTom': '1'}, timeout=5
+            )
+
+        assert '\r\nHost: ' in response.text
+        assert '\r\nUser-Agent: impit\r\n' in response.text
+        assert '\r\nx-CusTom: 1\r\n' in response.text
+        if browser == 'chrome':
+            assert '\r\nsec-ch-ua: ' in response.text
+            assert '\r\nAccept-Language: ' in response.text
+
     def test_passing_str_body(self, browser: Browser) -> None:
         impit = Client(browser=browser)
 
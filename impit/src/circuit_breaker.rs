@@ -0,0 +1,343 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use reqwest::Response;
+use tokio::time::Instant;
+
+use crate::errors::ImpitError;
+
+/// Maximum number of hosts the circuit breaker keeps track of, before the healthy ones are forgotten.
+const MAX_TRACKED_HOSTS: usize = 1024;
+
+/// When to stop sending requests to a host that keeps failing.
+///
+/// Every host has its own circuit. After `failure_threshold` consecutive failed requests, the circuit opens and
+/// the requests to the host fail right away with [`ImpitError::CircuitOpen`]. Once the `cooldown` has passed,
+/// the circuit is half-open - up to `half_open_probes` requests are let through as probes. If all of them succeed,
+/// the circuit closes again, a single failure opens it for another `cooldown`.
+///
+/// Every attempt of a retried request counts separately.
+///
+/// ### Example
+/// ```rust
+/// use std::time::Duration;
+/// use impit::circuit_breaker::CircuitBreaker;
+///
+/// let breaker = CircuitBreaker {
+///     failure_threshold: 10,
+///     cooldown: Duration::from_secs(60),
+///     ..CircuitBreaker::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failures that opens the circuit.
+    pub failure_threshold: usize,
+    /// How long the circuit stays open before the probes are sent.
+    pub cooldown: Duration,
+    /// The number of requests let through (and required to succeed) while the circuit is half-open.
+    pub half_open_probes: usize,
+    /// Whether the connection errors and connection timeouts count as failures.
+    pub count_connect_errors: bool,
+    /// Whether the other timeouts (e.g. reading the response) count as failures.
+    pub count_timeouts: bool,
+    /// The response status codes that count as failures.
+    pub statuses: Vec<u16>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+            half_open_probes: 1,
+            count_connect_errors: true,
+            count_timeouts: true,
+            statuses: vec![500, 502, 503, 504],
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Whether the request ending with `result` counts as a failure.
+    fn is_failure(&self, result: &Result<Response, ImpitError>) -> bool {
+        match result {
+            Ok(response) => self.statuses.contains(&response.status().as_u16()),
+            Err(ImpitError::ConnectError(_) | ImpitError::ConnectTimeout) => {
+                self.count_connect_errors
+            }
+            Err(
+                ImpitError::ReadTimeout
+                | ImpitError::WriteTimeout
+                | ImpitError::TimeoutException(_),
+            ) => self.count_timeouts,
+            Err(_) => false,
+        }
+    }
+
+    fn open(&self, now: Instant) -> Circuit {
+        Circuit::Open {
+            // Cooldowns too long to represent keep the circuit open for good.
+            until: now
+                .checked_add(self.cooldown)
+                .unwrap_or_else(|| now + Duration::from_secs(u32::MAX.into())),
+        }
+    }
+}
+
+/// The state of the circuit of a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// The requests are sent normally.
+    Closed,
+    /// The requests fail right away, for the remaining cooldown.
+    Open(Duration),
+    /// A limited number of probe requests is let through, to find out whether the host has recovered.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { failures: usize },
+    Open { until: Instant },
+    HalfOpen { probes: usize, successes: usize },
+}
+
+/// A request let through by [`Circuits::admit`].
+///
+/// The outcome of the request is recorded with [`Admission::record`]. A probe of a half-open circuit that's dropped
+/// without an outcome (e.g. because the request failed before it was sent, or was cancelled) gives its place
+/// to another request.
+pub(crate) struct Admission<'a> {
+    circuits: &'a Circuits,
+    host: String,
+    probe: bool,
+}
+
+impl Admission<'_> {
+    /// Updates the circuit of the host with the outcome of the request.
+    pub(crate) fn record(mut self, result: &Result<Response, ImpitError>) {
+        self.probe = false;
+        self.circuits.record(&self.host, result);
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        if !self.probe {
+            return;
+        }
+
+        if let Some(Circuit::HalfOpen { probes, .. }) =
+            self.circuits.circuits.lock().unwrap().get_mut(&self.host)
+        {
+            *probes = probes.saturating_sub(1);
+        }
+    }
+}
+
+/// The circuits of the hosts an [`Impit`](crate::impit::Impit) instance sends the requests to.
+pub(crate) struct Circuits {
+    breaker: Option<CircuitBreaker>,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl Circuits {
+    pub(crate) fn new(breaker: Option<CircuitBreaker>) -> Self {
+        Circuits {
+            breaker,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the state of the circuit of `host`.
+    pub(crate) fn state(&self, host: &str) -> CircuitState {
+        let now = Instant::now();
+
+        match self.circuits.lock().unwrap().get(host) {
+            None | Some(Circuit::Closed { .. }) => CircuitState::Closed,
+            Some(Circuit::Open { until }) if *until > now => {
+                CircuitState::Open(until.duration_since(now))
+            }
+            Some(Circuit::Open { .. } | Circuit::HalfOpen { .. }) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Checks whether a request to `host` can be sent, counting it as a probe if the circuit is half-open.
+    pub(crate) fn admit(&self, host: &str) -> Result<Admission<'_>, ImpitError> {
+        let admission = |probe| Admission {
+            circuits: self,
+            host: host.to_string(),
+            probe,
+        };
+        let Some(breaker) = &self.breaker else {
+            return Ok(admission(false));
+        };
+
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(host) else {
+            return Ok(admission(false));
+        };
+
+        match circuit {
+            Circuit::Closed { .. } => Ok(admission(false)),
+            Circuit::Open { until } if *until > now => {
+                Err(ImpitError::CircuitOpen(host.to_string()))
+            }
+            Circuit::Open { .. } => {
+                *circuit = Circuit::HalfOpen {
+                    probes: 1,
+                    successes: 0,
+                };
+                Ok(admission(true))
+            }
+            Circuit::HalfOpen { probes, .. } => {
+                if *probes < breaker.half_open_probes.max(1) {
+                    *probes += 1;
+                    Ok(admission(true))
+                } else {
+                    Err(ImpitError::CircuitOpen(host.to_string()))
+                }
+            }
+        }
+    }
+
+    /// Updates the circuit of `host` with the outcome of a request.
+    fn record(&self, host: &str, result: &Result<Response, ImpitError>) {
+        let Some(breaker) = &self.breaker else {
+            return;
+        };
+
+        let failed = breaker.is_failure(result);
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap();
+        if !failed && !circuits.contains_key(host) {
+            return;
+        }
+
+        if circuits.len() >= MAX_TRACKED_HOSTS {
+            circuits.retain(|_, circuit| !matches!(circuit, Circuit::Closed { failures: 0 }));
+        }
+
+        let circuit = circuits
+            .entry(host.to_string())
+            .or_insert(Circuit::Closed { failures: 0 });
+
+        match circuit {
+            Circuit::Closed { failures } if failed => {
+                *failures += 1;
+                if *failures >= breaker.failure_threshold {
+                    *circuit = breaker.open(now);
+                }
+            }
+            Circuit::Closed { failures } => *failures = 0,
+            Circuit::HalfOpen { .. } if failed => {
+                *circuit = breaker.open(now);
+            }
+            Circuit::HalfOpen { successes, .. } => {
+                *successes += 1;
+                if *successes >= breaker.half_open_probes {
+                    *circuit = Circuit::Closed { failures: 0 };
+                }
+            }
+            // The requests sent before the circuit opened don't change it.
+            Circuit::Open { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circuits(cooldown: Duration) -> Circuits {
+        Circuits::new(Some(CircuitBreaker {
+            failure_threshold: 2,
+            cooldown,
+            half_open_probes: 1,
+            ..CircuitBreaker::default()
+        }))
+    }
+
+    fn connect_error() -> Result<Response, ImpitError> {
+        Err(ImpitError::ConnectError("refused".to_string()))
+    }
+
+    #[test]
+    fn test_circuit_opens_after_consecutive_failures() {
+        let circuits = circuits(Duration::from_secs(60));
+
+        circuits.record("example.com", &connect_error());
+        circuits.record("example.com", &Err(ImpitError::InvalidURL));
+        circuits.record("example.com", &connect_error());
+        assert_eq!(circuits.state("example.com"), CircuitState::Closed);
+
+        circuits
+            .admit("example.com")
+            .unwrap()
+            .record(&connect_error());
+        assert!(matches!(
+            circuits.state("example.com"),
+            CircuitState::Open(_)
+        ));
+        assert!(matches!(
+            circuits.admit("example.com"),
+            Err(ImpitError::CircuitOpen(host)) if host == "example.com"
+        ));
+        assert!(circuits.admit("example.org").is_ok());
+    }
+
+    #[test]
+    fn test_half_open_circuit_lets_probes_through() {
+        let circuits = circuits(Duration::ZERO);
+
+        circuits.record("example.com", &connect_error());
+        circuits.record("example.com", &connect_error());
+        assert_eq!(circuits.state("example.com"), CircuitState::HalfOpen);
+
+        // A failed probe opens the circuit again.
+        circuits
+            .admit("example.com")
+            .unwrap()
+            .record(&connect_error());
+        assert_eq!(circuits.state("example.com"), CircuitState::HalfOpen);
+
+        circuits
+            .admit("example.com")
+            .unwrap()
+            .record(&Err(ImpitError::InvalidURL));
+        assert_eq!(circuits.state("example.com"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_unfinished_probes_are_released() {
+        let circuits = circuits(Duration::ZERO);
+        circuits.record("example.com", &connect_error());
+        circuits.record("example.com", &connect_error());
+
+        let probe = circuits.admit("example.com").unwrap();
+        assert!(matches!(
+            circuits.admit("example.com"),
+            Err(ImpitError::CircuitOpen(_))
+        ));
+
+        // E.g. the request failed in a middleware before it was sent.
+        drop(probe);
+        circuits
+            .admit("example.com")
+            .unwrap()
+            .record(&Err(ImpitError::InvalidURL));
+        assert_eq!(circuits.state("example.com"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_disabled_circuit_breaker() {
+        let circuits = Circuits::new(None);
+
+        for _ in 0..10 {
+            circuits.record("example.com", &connect_error());
+        }
+        assert!(circuits.admit("example.com").is_ok());
+        assert_eq!(circuits.state("example.com"), CircuitState::Closed);
+    }
+}
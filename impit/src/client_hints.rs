@@ -0,0 +1,308 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use reqwest::Response;
+use url::{Host, Url};
+
+use crate::{fingerprint::RequestDestination, request::ImpitRequest};
+
+/// The response header listing the client hints an origin wants on the later requests.
+const ACCEPT_CH: &str = "accept-ch";
+/// The response header listing the client hints the response depends on.
+const CRITICAL_CH: &str = "critical-ch";
+
+/// Keeps the high-entropy client hints each origin asked for with the `Accept-CH` header, like Chrome does.
+///
+/// Only the secure origins (`https` and the local hosts) can ask for the hints, and only in the responses to the
+/// navigations (the [`RequestDestination::Document`] requests). Every `Accept-CH` header replaces the previous list
+/// of the origin. The low-entropy hints (`sec-ch-ua`, `sec-ch-ua-mobile` and `sec-ch-ua-platform`) are part of
+/// the fingerprint's headers and are always sent.
+pub(crate) struct ClientHints {
+    /// The fingerprint's high-entropy hints, empty if it has none.
+    hints: Vec<(String, String)>,
+    /// The lowercase names of the hints accepted by each origin.
+    origins: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl ClientHints {
+    pub(crate) fn new(hints: Vec<(String, String)>) -> Self {
+        ClientHints {
+            hints,
+            origins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds the hints accepted by the request's origin. The headers set by the caller are kept.
+    pub(crate) fn apply(&self, request: &mut ImpitRequest) {
+        if self.hints.is_empty() {
+            return;
+        }
+
+        let accepted = self
+            .origins
+            .lock()
+            .unwrap()
+            .get(&request.url.origin().ascii_serialization())
+            .cloned()
+            .unwrap_or_default();
+
+        let hints: Vec<(String, String)> = self
+            .hints
+            .iter()
+            .filter(|(name, _)| accepted.contains(&name.to_ascii_lowercase()))
+            .filter(|(name, _)| {
+                !request
+                    .headers
+                    .iter()
+                    .any(|(header, _)| header.eq_ignore_ascii_case(name))
+            })
+            .cloned()
+            .collect();
+
+        if !hints.is_empty() {
+            insert_hints(&mut request.headers, hints);
+        }
+    }
+
+    /// Stores the hints asked for by the `Accept-CH` header of the response to a request with `headers`.
+    /// The responses to the requests for other destinations than [`RequestDestination::Document`] are ignored.
+    ///
+    /// Returns whether the request has to be sent again, i.e. whether it lacks a hint from the `Critical-CH` header
+    /// that can be sent now.
+    pub(crate) fn record(
+        &self,
+        destination: RequestDestination,
+        headers: &[(String, String)],
+        response: &Response,
+    ) -> bool {
+        if self.hints.is_empty()
+            || destination != RequestDestination::Document
+            || !is_secure(response.url())
+        {
+            return false;
+        }
+        let Some(accepted) = header_list(response, ACCEPT_CH) else {
+            return false;
+        };
+
+        let accepted: Vec<String> = accepted
+            .into_iter()
+            .filter(|name| {
+                self.hints
+                    .iter()
+                    .any(|(hint, _)| hint.eq_ignore_ascii_case(name))
+            })
+            .collect();
+
+        let missing_critical = header_list(response, CRITICAL_CH)
+            .unwrap_or_default()
+            .iter()
+            .any(|name| {
+                accepted.contains(name)
+                    && !headers
+                        .iter()
+                        .any(|(header, _)| header.eq_ignore_ascii_case(name))
+            });
+
+        self.origins
+            .lock()
+            .unwrap()
+            .insert(response.url().origin().ascii_serialization(), accepted);
+
+        missing_critical
+    }
+}
+
+/// Returns the lowercase names listed in all the `name` headers of the response, or `None` if there are none.
+fn header_list(response: &Response, name: &str) -> Option<Vec<String>> {
+    let values: Vec<&str> = response
+        .headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(
+        values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|token| token.trim().to_ascii_lowercase())
+            .filter(|token| !token.is_empty())
+            .collect(),
+    )
+}
+
+/// Whether the browsers treat the URL as a secure context, i.e. whether it's `https` or on a local host.
+fn is_secure(url: &Url) -> bool {
+    if url.scheme() == "https" {
+        return true;
+    }
+
+    match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// Inserts the hints among the other `sec-ch-ua*` headers, which Chrome sends sorted by name.
+fn insert_hints(headers: &mut Vec<(String, String)>, hints: Vec<(String, String)>) {
+    let is_hint = |name: &str| name.to_ascii_lowercase().starts_with("sec-ch-ua");
+    let index = headers
+        .iter()
+        .position(|(name, _)| is_hint(name))
+        .unwrap_or(headers.len());
+
+    let (mut block, rest): (Vec<_>, Vec<_>) =
+        headers.drain(..).partition(|(name, _)| is_hint(name));
+    *headers = rest;
+
+    block.extend(hints);
+    block.sort_by_key(|(name, _)| name.to_ascii_lowercase());
+    headers.splice(index..index, block);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::ResponseBuilderExt;
+
+    fn response(url: &str, headers: &[(&str, &str)]) -> Response {
+        let mut builder = http::Response::builder().url(Url::parse(url).unwrap());
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Response::from(builder.body("").unwrap())
+    }
+
+    fn request(url: &str) -> ImpitRequest {
+        ImpitRequest {
+            url: Url::parse(url).unwrap(),
+            body: Default::default(),
+            headers: vec![
+                (
+                    "sec-ch-ua".to_string(),
+                    "\"Chromium\";v=\"151\"".to_string(),
+                ),
+                ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
+                ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
+                ("user-agent".to_string(), "Chrome".to_string()),
+            ],
+            method: "GET".to_string(),
+        }
+    }
+
+    fn client_hints() -> ClientHints {
+        ClientHints::new(vec![
+            ("sec-ch-ua-arch".to_string(), "\"x86\"".to_string()),
+            ("sec-ch-ua-model".to_string(), "\"\"".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_accepted_hints_are_sent_to_the_origin() {
+        let client_hints = client_hints();
+        let accept = response(
+            "https://example.com/",
+            &[("accept-ch", "Sec-CH-UA-Model, Sec-CH-UA-Arch, Unknown")],
+        );
+        assert!(!client_hints.record(
+            RequestDestination::Document,
+            &request("https://example.com/").headers,
+            &accept
+        ));
+
+        let mut same_origin = request("https://example.com/other");
+        client_hints.apply(&mut same_origin);
+        let names: Vec<&str> = same_origin
+            .headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "sec-ch-ua",
+                "sec-ch-ua-arch",
+                "sec-ch-ua-mobile",
+                "sec-ch-ua-model",
+                "sec-ch-ua-platform",
+                "user-agent"
+            ]
+        );
+
+        let mut other_origin = request("https://example.org/");
+        client_hints.apply(&mut other_origin);
+        assert_eq!(
+            other_origin.headers,
+            request("https://example.org/").headers
+        );
+
+        // Insecure origins can't ask for the hints.
+        let insecure = response("http://example.net/", &[("accept-ch", "Sec-CH-UA-Arch")]);
+        client_hints.record(
+            RequestDestination::Document,
+            &request("http://example.net/").headers,
+            &insecure,
+        );
+        let mut insecure_request = request("http://example.net/");
+        client_hints.apply(&mut insecure_request);
+        assert_eq!(insecure_request.headers.len(), 4);
+    }
+
+    #[test]
+    fn test_critical_hints_trigger_a_retry() {
+        let client_hints = client_hints();
+        let critical = response(
+            "https://example.com/",
+            &[
+                ("accept-ch", "Sec-CH-UA-Arch"),
+                ("critical-ch", "Sec-CH-UA-Arch, Sec-CH-UA-Model"),
+            ],
+        );
+
+        assert!(client_hints.record(
+            RequestDestination::Document,
+            &request("https://example.com/").headers,
+            &critical
+        ));
+
+        let mut retried = request("https://example.com/");
+        client_hints.apply(&mut retried);
+        assert!(!client_hints.record(RequestDestination::Document, &retried.headers, &critical));
+    }
+
+    #[test]
+    fn test_subresource_responses_are_ignored() {
+        let client_hints = client_hints();
+        let critical = response(
+            "https://example.com/",
+            &[
+                ("accept-ch", "Sec-CH-UA-Arch"),
+                ("critical-ch", "Sec-CH-UA-Arch"),
+            ],
+        );
+
+        for destination in [
+            RequestDestination::Iframe,
+            RequestDestination::Fetch,
+            RequestDestination::Image,
+        ] {
+            assert!(!client_hints.record(
+                destination,
+                &request("https://example.com/").headers,
+                &critical
+            ));
+        }
+
+        let mut later = request("https://example.com/");
+        client_hints.apply(&mut later);
+        assert_eq!(later.headers, request("https://example.com/").headers);
+    }
+}
@@ -0,0 +1,722 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use hickory_proto::rr::{rdata::svcb::SvcParamValue, RData, RecordType};
+use hickory_resolver::{
+    config::{ConnectionConfig, LookupIpStrategy, NameServerConfig, ResolverConfig},
+    net::runtime::TokioRuntimeProvider,
+    TokioResolver,
+};
+use log::debug;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use url::{Host, Url};
+
+use crate::{
+    errors::ImpitError,
+    routing::{normalize_host, Route, ServerName},
+};
+
+/// Configuration of the DNS resolution used by an [`Impit`](crate::impit::Impit) instance.
+///
+/// The same resolver is used for opening the connections (including the HTTP/3 ones) and for the HTTPS record
+/// lookups used for the HTTP/3 discovery. Without any nameservers, the system resolver is used.
+///
+/// ### Example
+/// ```rust
+/// use impit::dns::DnsConfig;
+///
+/// let dns = DnsConfig::default()
+///     // Pin the host to a local test server, like curl's `--resolve`
+///     .with_resolve("example.com:443:127.0.0.1")
+///     .unwrap()
+///     // Resolve everything else with DNS-over-HTTPS
+///     .with_nameserver("https://1.1.1.1/dns-query".parse().unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DnsConfig {
+    /// The static overrides by hostname and port, `None` matching any port.
+    overrides: HashMap<(String, Option<u16>), Vec<IpAddr>>,
+    nameservers: Vec<Nameserver>,
+}
+
+impl DnsConfig {
+    /// Adds a static override in the curl's `--resolve` format, i.e. `host:port:address[,address...]`.
+    ///
+    /// IPv6 addresses can be enclosed in square brackets. Like in curl, the override only applies to the connections
+    /// to the given port, or to any port if it's `*`. The overrides for a specific port take precedence.
+    pub fn with_resolve(mut self, entry: &str) -> Result<Self, ImpitError> {
+        let invalid = || ImpitError::ReqwestError(format!("Invalid DNS override: {entry}"));
+
+        let mut parts = entry.splitn(3, ':');
+        let (Some(host), Some(port), Some(addresses)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let port = match port {
+            "*" => None,
+            port => Some(port.parse::<u16>().map_err(|_| invalid())?),
+        };
+
+        let addresses = addresses
+            .split(',')
+            .map(|address| {
+                let address = address.trim();
+                address
+                    .strip_prefix('[')
+                    .and_then(|address| address.strip_suffix(']'))
+                    .unwrap_or(address)
+                    .parse::<IpAddr>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        self.overrides
+            .entry((host.to_ascii_lowercase(), port))
+            .or_default()
+            .extend(addresses);
+        Ok(self)
+    }
+
+    /// Makes `host` resolve to the given addresses (for any port), without querying any nameservers.
+    pub fn with_host_addresses(mut self, host: String, addresses: Vec<IpAddr>) -> Self {
+        self.overrides
+            .entry((host.to_ascii_lowercase(), None))
+            .or_default()
+            .extend(addresses);
+        self
+    }
+
+    /// Adds a nameserver to query instead of the ones from the system configuration.
+    ///
+    /// When multiple nameservers are set, the resolver picks between them based on their performance.
+    pub fn with_nameserver(mut self, nameserver: Nameserver) -> Self {
+        self.nameservers.push(nameserver);
+        self
+    }
+}
+
+/// A nameserver used by the [`DnsConfig`].
+///
+/// Nameservers are parsed from strings in one of the following formats:
+/// - `8.8.8.8`, `8.8.8.8:53` or `udp://8.8.8.8` - plain DNS (over UDP, with TCP fallback),
+/// - `tls://1.1.1.1` or `tls://one.one.one.one:853` - DNS-over-TLS,
+/// - `https://1.1.1.1/dns-query` or `https://dns.google/dns-query` - DNS-over-HTTPS.
+///
+/// Hostnames of the encrypted nameservers are resolved with the system resolver once, before the first DNS query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nameserver {
+    protocol: NameserverProtocol,
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NameserverProtocol {
+    Plain,
+    Tls,
+    Https { path: String },
+}
+
+impl FromStr for Nameserver {
+    type Err = ImpitError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ImpitError::ReqwestError(format!("Invalid DNS nameserver: {value}"));
+
+        if let Ok(address) = value.parse::<IpAddr>() {
+            return Ok(Nameserver {
+                protocol: NameserverProtocol::Plain,
+                host: address.to_string(),
+                port: 53,
+            });
+        }
+        if let Ok(address) = value.parse::<SocketAddr>() {
+            return Ok(Nameserver {
+                protocol: NameserverProtocol::Plain,
+                host: address.ip().to_string(),
+                port: address.port(),
+            });
+        }
+
+        let url = Url::parse(value).map_err(|_| invalid())?;
+        let host = match url.host().ok_or_else(invalid)? {
+            Host::Domain(domain) => domain.to_string(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+
+        let (protocol, default_port) = match url.scheme() {
+            "udp" | "dns" => (NameserverProtocol::Plain, 53),
+            "tls" => (NameserverProtocol::Tls, 853),
+            "https" => (
+                NameserverProtocol::Https {
+                    path: url.path().to_string(),
+                },
+                443,
+            ),
+            _ => return Err(invalid()),
+        };
+
+        if protocol == NameserverProtocol::Plain && host.parse::<IpAddr>().is_err() {
+            return Err(invalid());
+        }
+
+        Ok(Nameserver {
+            protocol,
+            host,
+            port: url.port().unwrap_or(default_port),
+        })
+    }
+}
+
+impl Nameserver {
+    async fn to_configs(&self) -> io::Result<Vec<NameServerConfig>> {
+        let addresses: Vec<IpAddr> = match self.host.parse::<IpAddr>() {
+            Ok(address) => vec![address],
+            Err(_) => tokio::net::lookup_host((self.host.as_str(), self.port))
+                .await
+                .map_err(|e| {
+                    io::Error::new(
+                        e.kind(),
+                        format!("Failed to resolve the DNS nameserver {}: {e}", self.host),
+                    )
+                })?
+                .map(|address| address.ip())
+                .collect(),
+        };
+
+        let server_name: Arc<str> = Arc::from(self.host.as_str());
+        let mut connections = match &self.protocol {
+            NameserverProtocol::Plain => vec![ConnectionConfig::udp(), ConnectionConfig::tcp()],
+            NameserverProtocol::Tls => vec![ConnectionConfig::tls(server_name)],
+            NameserverProtocol::Https { path } => vec![ConnectionConfig::https(
+                server_name,
+                Some(Arc::from(path.as_str())),
+            )],
+        };
+        for connection in &mut connections {
+            connection.port = self.port;
+        }
+
+        Ok(addresses
+            .into_iter()
+            .map(|address| NameServerConfig::new(address, true, connections.clone()))
+            .collect())
+    }
+}
+
+/// Which IP versions are used for the connections, and in which order they are tried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpFamily {
+    /// Both IPv6 and IPv4 are used, IPv6 addresses are tried first. This is what the browsers do.
+    #[default]
+    PreferIpv6,
+    /// Both IPv4 and IPv6 are used, IPv4 addresses are tried first.
+    PreferIpv4,
+    /// Only IPv4 addresses are used.
+    Ipv4Only,
+    /// Only IPv6 addresses are used.
+    Ipv6Only,
+}
+
+/// Settings for the Happy Eyeballs v2 ([RFC 8305](https://www.rfc-editor.org/rfc/rfc8305)) connection racing.
+///
+/// The resolved addresses are sorted by the [`IpFamily`] preference, alternating between the families.
+/// If the connection to the first address doesn't succeed within the `connection_attempt_delay`,
+/// the next address (from the other family) is raced against it.
+///
+/// The IP family preference applies to all the connections, but the `connection_attempt_delay` is only used
+/// by the HTTP/3 (QUIC) connections. `reqwest` doesn't allow configuring its TCP connection racing,
+/// which always waits 300 ms before trying the other family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HappyEyeballs {
+    /// Which IP versions are used, and in which order.
+    pub ip_family: IpFamily,
+    /// How long to wait for a QUIC connection attempt before starting the next one.
+    ///
+    /// This only applies to the HTTP/3 connections made by `impit` itself,
+    /// the TCP connections made by `reqwest` always use the fixed delay of 300 ms.
+    pub connection_attempt_delay: Duration,
+}
+
+impl Default for HappyEyeballs {
+    fn default() -> Self {
+        HappyEyeballs {
+            ip_family: IpFamily::default(),
+            connection_attempt_delay: Duration::from_millis(300),
+        }
+    }
+}
+
+impl HappyEyeballs {
+    /// Filters the addresses by the allowed IP family and interleaves them, starting with the preferred one.
+    pub(crate) fn sort(&self, addresses: Vec<IpAddr>) -> Vec<IpAddr> {
+        let (ipv6, ipv4): (Vec<IpAddr>, Vec<IpAddr>) =
+            addresses.into_iter().partition(IpAddr::is_ipv6);
+
+        let (preferred, fallback) = match self.ip_family {
+            IpFamily::PreferIpv6 => (ipv6, ipv4),
+            IpFamily::PreferIpv4 => (ipv4, ipv6),
+            IpFamily::Ipv4Only => (ipv4, vec![]),
+            IpFamily::Ipv6Only => (ipv6, vec![]),
+        };
+
+        let mut sorted = Vec::with_capacity(preferred.len() + fallback.len());
+        let mut preferred = preferred.into_iter();
+        let mut fallback = fallback.into_iter();
+        loop {
+            match (preferred.next(), fallback.next()) {
+                (None, None) => break,
+                (first, second) => sorted.extend(first.into_iter().chain(second)),
+            }
+        }
+
+        sorted
+    }
+}
+
+/// How long the addresses from the system resolver are cached, as it doesn't report the record TTLs.
+const SYSTEM_DNS_TTL: Duration = Duration::from_secs(60);
+
+/// Maximum number of hosts kept in the DNS cache.
+const MAX_CACHED_HOSTS: usize = 1024;
+
+struct CachedAddresses {
+    addresses: Vec<IpAddr>,
+    valid_until: Instant,
+}
+
+/// The resolver querying the nameservers from the [`DnsConfig`].
+///
+/// It's built on the first use rather than with the client, as the hostnames of the encrypted nameservers
+/// have to be resolved first, and the builder shouldn't block on that.
+struct NameserverResolver {
+    nameservers: Vec<Nameserver>,
+    ip_strategy: LookupIpStrategy,
+    resolver: tokio::sync::OnceCell<TokioResolver>,
+}
+
+impl NameserverResolver {
+    async fn get(&self) -> io::Result<&TokioResolver> {
+        self.resolver.get_or_try_init(|| self.build()).await
+    }
+
+    async fn build(&self) -> io::Result<TokioResolver> {
+        let mut name_servers = vec![];
+        for nameserver in &self.nameservers {
+            name_servers.extend(nameserver.to_configs().await?);
+        }
+
+        let mut builder = TokioResolver::builder_with_config(
+            ResolverConfig::from_parts(None, vec![], name_servers),
+            TokioRuntimeProvider::default(),
+        );
+        builder.options_mut().ip_strategy = self.ip_strategy;
+
+        builder
+            .build()
+            .map_err(|e| io::Error::other(format!("Failed to create DNS resolver: {e}")))
+    }
+}
+
+/// The resolver built from a [`DnsConfig`], shared by all the clients of an [`Impit`](crate::impit::Impit) instance.
+///
+/// The resolved addresses are cached (respecting the record TTLs), so the new connections to the same host
+/// don't cause new DNS queries.
+#[derive(Clone)]
+pub(crate) struct Resolver {
+    overrides: Arc<HashMap<(String, Option<u16>), Vec<IpAddr>>>,
+    /// Without it, the addresses are resolved with the system resolver.
+    nameservers: Option<Arc<NameserverResolver>>,
+    /// Used for the HTTPS records when no nameservers are configured, created on the first use.
+    system: Arc<OnceLock<Option<TokioResolver>>>,
+    cache: Arc<Mutex<HashMap<String, CachedAddresses>>>,
+    happy_eyeballs: HappyEyeballs,
+    /// The hosts resolved to the addresses of other hosts (and ports), as per the [`Route`].
+    connect_to: Vec<(String, String, u16)>,
+    /// The host whose overrides are looked up for a specific port, as per the [`Route`].
+    resolve_port: Option<(String, u16)>,
+}
+
+impl Resolver {
+    pub(crate) fn new(
+        config: &DnsConfig,
+        happy_eyeballs: HappyEyeballs,
+    ) -> Result<Self, ImpitError> {
+        let nameservers = (!config.nameservers.is_empty()).then(|| {
+            Arc::new(NameserverResolver {
+                nameservers: config.nameservers.clone(),
+                // Don't query the records for the addresses that would be thrown away anyway.
+                ip_strategy: match happy_eyeballs.ip_family {
+                    IpFamily::Ipv4Only => LookupIpStrategy::Ipv4Only,
+                    IpFamily::Ipv6Only => LookupIpStrategy::Ipv6Only,
+                    IpFamily::PreferIpv4 | IpFamily::PreferIpv6 => LookupIpStrategy::Ipv6AndIpv4,
+                },
+                resolver: tokio::sync::OnceCell::new(),
+            })
+        });
+
+        Ok(Resolver {
+            overrides: Arc::new(config.overrides.clone()),
+            nameservers,
+            system: Arc::default(),
+            cache: Arc::default(),
+            happy_eyeballs,
+            connect_to: vec![],
+            resolve_port: None,
+        })
+    }
+
+    /// Returns a resolver (sharing the cache with this one) for the clients sending the requests with `route`.
+    ///
+    /// The connection target port is only used by `reqwest` if the request URL doesn't have an explicit port.
+    pub(crate) fn for_route(&self, route: &Route) -> Resolver {
+        let mut connect_to: Vec<_> = route
+            .connect_to
+            .iter()
+            .map(|(host, connect_host, connect_port)| {
+                (
+                    host.clone(),
+                    connect_host.clone().unwrap_or_else(|| host.clone()),
+                    connect_port.unwrap_or(0),
+                )
+            })
+            .collect();
+        // The requests with a custom server name are made for the server name, see `Route::tcp_url`.
+        if let (ServerName::Custom(server_name), Some((_, connect_host, connect_port))) =
+            (&route.server_name, &route.server_name_target)
+        {
+            connect_to.insert(
+                0,
+                (
+                    normalize_host(server_name),
+                    connect_host.clone(),
+                    *connect_port,
+                ),
+            );
+        }
+
+        Resolver {
+            connect_to,
+            resolve_port: route.resolve_port.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Whether some of the overrides for `host` only apply to a specific port.
+    pub(crate) fn has_port_overrides(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.overrides
+            .keys()
+            .any(|(override_host, port)| *override_host == host && port.is_some())
+    }
+
+    pub(crate) fn happy_eyeballs(&self) -> HappyEyeballs {
+        self.happy_eyeballs
+    }
+
+    /// Resolves `host` to a list of IP addresses, sorted for the Happy Eyeballs connection racing.
+    ///
+    /// The `port` (if known) picks the port-specific overrides.
+    pub(crate) async fn lookup_ip(&self, host: &str, port: Option<u16>) -> io::Result<Vec<IpAddr>> {
+        let host = host.to_ascii_lowercase();
+        let port_override = port.and_then(|port| self.overrides.get(&(host.clone(), Some(port))));
+        let addresses = match port_override.or_else(|| self.overrides.get(&(host.clone(), None))) {
+            Some(addresses) => addresses.clone(),
+            None => self.lookup_cached(&host).await?,
+        };
+
+        let addresses = self.happy_eyeballs.sort(addresses);
+        if addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No addresses of the allowed IP family found for {host}"),
+            ));
+        }
+
+        Ok(addresses)
+    }
+
+    async fn lookup_cached(&self, host: &str) -> io::Result<Vec<IpAddr>> {
+        if let Some(cached) = self.cache.lock().unwrap().get(host) {
+            if cached.valid_until > Instant::now() {
+                return Ok(cached.addresses.clone());
+            }
+        }
+
+        let (addresses, valid_until) = match &self.nameservers {
+            Some(resolver) => resolver
+                .get()
+                .await?
+                .lookup_ip(host)
+                .await
+                .map(|lookup| (lookup.iter().collect(), lookup.valid_until()))
+                .map_err(io::Error::other)?,
+            None => (
+                tokio::net::lookup_host((host, 0))
+                    .await?
+                    .map(|address| address.ip())
+                    .collect(),
+                Instant::now() + SYSTEM_DNS_TTL,
+            ),
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= MAX_CACHED_HOSTS {
+            cache.retain(|_, cached| cached.valid_until > Instant::now());
+        }
+        if cache.len() < MAX_CACHED_HOSTS {
+            cache.insert(
+                host.to_string(),
+                CachedAddresses {
+                    addresses: addresses.clone(),
+                    valid_until,
+                },
+            );
+        }
+
+        Ok(addresses)
+    }
+
+    fn system_resolver() -> Option<TokioResolver> {
+        match TokioResolver::builder_tokio().and_then(|builder| builder.build()) {
+            Ok(resolver) => Some(resolver),
+            Err(err) => {
+                debug!("Failed to create DNS resolver from the system configuration: {err}");
+                None
+            }
+        }
+    }
+
+    /// Returns the ALPN protocols advertised in the HTTPS DNS records of `host`.
+    ///
+    /// Hosts with static overrides are never looked up, the records would describe the real servers.
+    pub(crate) async fn lookup_https_alpn(&self, host: &str) -> Vec<String> {
+        let host = host.to_ascii_lowercase();
+        if self
+            .overrides
+            .keys()
+            .any(|(override_host, _)| *override_host == host)
+        {
+            return vec![];
+        }
+
+        let resolver = match &self.nameservers {
+            Some(resolver) => match resolver.get().await {
+                Ok(resolver) => resolver,
+                Err(err) => {
+                    debug!("{err}");
+                    return vec![];
+                }
+            },
+            None => match self.system.get_or_init(Self::system_resolver) {
+                Some(resolver) => resolver,
+                None => return vec![],
+            },
+        };
+
+        let Ok(lookup) = resolver.lookup(host.as_str(), RecordType::HTTPS).await else {
+            return vec![];
+        };
+
+        lookup
+            .answers()
+            .iter()
+            .filter_map(|answer| match &answer.data {
+                RData::HTTPS(data) => Some(data),
+                _ => None,
+            })
+            .flat_map(|data| data.0.svc_params.iter())
+            .filter_map(|param| match &param.1 {
+                SvcParamValue::Alpn(alpn) => Some(alpn.0.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+
+        Box::pin(async move {
+            let host = name.as_str().to_ascii_lowercase();
+            // The port 0 makes `reqwest` use the port from the request URL.
+            let (host, port) = match resolver
+                .connect_to
+                .iter()
+                .find(|(route_host, _, _)| *route_host == host)
+            {
+                Some((_, connect_host, connect_port)) => (connect_host.clone(), *connect_port),
+                None => (host, 0),
+            };
+            let resolve_port = match &resolver.resolve_port {
+                Some((route_host, route_port)) if *route_host == host => Some(*route_port),
+                _ => None,
+            };
+
+            let addresses = resolver.lookup_ip(&host, resolve_port).await?;
+            let addrs: Addrs = Box::new(
+                addresses
+                    .into_iter()
+                    .map(move |address| SocketAddr::new(address, port)),
+            );
+
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_entries() {
+        let config = DnsConfig::default()
+            .with_resolve("Example.com:443:127.0.0.1,[::1]")
+            .unwrap()
+            .with_resolve("example.org:*:10.0.0.1")
+            .unwrap();
+
+        assert_eq!(
+            config.overrides[&("example.com".to_string(), Some(443))],
+            vec![
+                "127.0.0.1".parse::<IpAddr>().unwrap(),
+                "::1".parse::<IpAddr>().unwrap()
+            ]
+        );
+        assert!(config
+            .overrides
+            .contains_key(&("example.org".to_string(), None)));
+
+        assert!(DnsConfig::default()
+            .with_resolve("example.com:443")
+            .is_err());
+        assert!(DnsConfig::default()
+            .with_resolve("example.com:https:127.0.0.1")
+            .is_err());
+        assert!(DnsConfig::default()
+            .with_resolve("example.com:443:localhost")
+            .is_err());
+    }
+
+    #[test]
+    fn test_nameserver_parsing() {
+        let nameserver: Nameserver = "8.8.8.8".parse().unwrap();
+        assert_eq!(nameserver.protocol, NameserverProtocol::Plain);
+        assert_eq!(nameserver.port, 53);
+
+        let nameserver: Nameserver = "tls://one.one.one.one".parse().unwrap();
+        assert_eq!(nameserver.protocol, NameserverProtocol::Tls);
+        assert_eq!(nameserver.port, 853);
+
+        let nameserver: Nameserver = "https://1.1.1.1:8443/dns-query".parse().unwrap();
+        assert_eq!(
+            nameserver.protocol,
+            NameserverProtocol::Https {
+                path: "/dns-query".to_string()
+            }
+        );
+        assert_eq!(nameserver.port, 8443);
+
+        assert!("udp://dns.google".parse::<Nameserver>().is_err());
+        assert!("ftp://1.1.1.1".parse::<Nameserver>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_overrides_skip_nameservers() {
+        let resolver = Resolver::new(
+            &DnsConfig::default()
+                .with_resolve("example.com:443:127.0.0.1")
+                .unwrap(),
+            HappyEyeballs::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolver.lookup_ip("EXAMPLE.com", Some(443)).await.unwrap(),
+            vec!["127.0.0.1".parse::<IpAddr>().unwrap()]
+        );
+        assert!(resolver.lookup_https_alpn("example.com").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overrides_by_port() {
+        let resolver = Resolver::new(
+            &DnsConfig::default()
+                .with_resolve("example.com:443:127.0.0.1")
+                .unwrap()
+                .with_resolve("example.com:*:127.0.0.2")
+                .unwrap()
+                .with_resolve("example.org:8443:127.0.0.3")
+                .unwrap(),
+            HappyEyeballs::default(),
+        )
+        .unwrap();
+        let ip = |address: &str| vec![address.parse::<IpAddr>().unwrap()];
+
+        assert_eq!(
+            resolver.lookup_ip("example.com", Some(443)).await.unwrap(),
+            ip("127.0.0.1")
+        );
+        assert_eq!(
+            resolver.lookup_ip("example.com", Some(80)).await.unwrap(),
+            ip("127.0.0.2")
+        );
+        assert_eq!(
+            resolver.lookup_ip("example.com", None).await.unwrap(),
+            ip("127.0.0.2")
+        );
+        assert!(resolver.has_port_overrides("example.org"));
+        assert!(!resolver.has_port_overrides("example.net"));
+    }
+
+    #[test]
+    fn test_nameserver_hostnames_are_resolved_lazily() {
+        // Building the resolver must not query the system resolver for the nameserver hostname.
+        let config =
+            DnsConfig::default().with_nameserver("tls://nameserver.invalid".parse().unwrap());
+        assert!(Resolver::new(&config, HappyEyeballs::default()).is_ok());
+    }
+
+    #[test]
+    fn test_happy_eyeballs_sorting() {
+        let addresses: Vec<IpAddr> = ["192.0.2.1", "192.0.2.2", "2001:db8::1", "2001:db8::2"]
+            .iter()
+            .map(|address| address.parse().unwrap())
+            .collect();
+        let sorted = |ip_family| {
+            HappyEyeballs {
+                ip_family,
+                ..Default::default()
+            }
+            .sort(addresses.clone())
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted(IpFamily::PreferIpv6),
+            ["2001:db8::1", "192.0.2.1", "2001:db8::2", "192.0.2.2"]
+        );
+        assert_eq!(
+            sorted(IpFamily::PreferIpv4),
+            ["192.0.2.1", "2001:db8::1", "192.0.2.2", "2001:db8::2"]
+        );
+        assert_eq!(sorted(IpFamily::Ipv4Only), ["192.0.2.1", "192.0.2.2"]);
+        assert_eq!(sorted(IpFamily::Ipv6Only), ["2001:db8::1", "2001:db8::2"]);
+    }
+}
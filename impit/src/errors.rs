@@ -62,6 +62,8 @@ pub enum ImpitError {
     DecodingError,
     #[error("Too many redirects occurred. Maximum allowed: {}.", .0.map_or("unknown".to_string(), |n| n.to_string())) ]
     TooManyRedirects(Option<usize>),
+    #[error("The redirect was rejected by the redirect policy.\nReason: {0}")]
+    RedirectRejected(String),
     #[error("HTTP status error occurred with status code {0}.")]
     HTTPStatusError(u16),
     #[error("The URL is invalid.")]
@@ -86,14 +88,36 @@ pub enum ImpitError {
     UrlProtocolError(String),
     #[error("The request was made with http3_prior_knowledge, but HTTP/3 usage wasn't enabled.")]
     Http3Disabled,
-    #[error("The request method `{0}` is invalid. Only GET, POST, PUT, DELETE, PATCH, HEAD, OPTIONS and TRACE are supported.")]
+    #[error("HTTP/3 can't be used through the proxy `{0}`. Only SOCKS5 proxies supporting UDP ASSOCIATE can carry HTTP/3 traffic.")]
+    Http3ProxyUnsupported(String),
+    #[error("The request method `{0}` is invalid. Methods have to be valid HTTP tokens, e.g. `GET` or `PROPFIND`.")]
     InvalidMethod(String),
+    #[error("The referrer policy `{0}` is invalid. Use one of the policy tokens, e.g. `strict-origin-when-cross-origin`.")]
+    InvalidReferrerPolicy(String),
+    #[error("The request destination `{0}` is invalid. Use one of `document`, `iframe`, `fetch`, `script`, `style` or `image`.")]
+    InvalidRequestDestination(String),
+    #[error("The header position `{0}` is invalid. Use `start`, `end`, `before:<header>` or `after:<header>`.")]
+    InvalidHeaderPosition(String),
+    #[error("The HTTP/3 cache couldn't be imported or exported.\nReason: {0}")]
+    H3CacheError(String),
+    #[error("The request waited for the rate or concurrency limits for longer than the queue timeout ({0} ms).")]
+    QueueTimeout(u128),
+    #[error("The request limits are invalid: {0}")]
+    InvalidLimits(String),
+    #[error(
+        "The circuit breaker for `{0}` is open after repeated failures, the request was not sent."
+    )]
+    CircuitOpen(String),
     #[error("{0}")]
     BindingPassthroughError(String),
     #[error("The header name `{0}` is invalid. Header names must be ASCII and cannot contain control characters or whitespace.")]
     InvalidHeaderName(String),
     #[error("The header value `{0}` is invalid.")]
     InvalidHeaderValue(String),
+    #[error("The server refused the HTTP/2 stream without processing the request.\nReason: {0}")]
+    RefusedStream(String),
+    #[error("The server closed the HTTP/2 connection (GOAWAY) before processing the request.\nReason: {0}")]
+    GoAway(String),
     #[error("The internal HTTP library has thrown an error:\n{0}")]
     ReqwestError(String),
 }
@@ -122,6 +146,15 @@ impl ImpitError {
             return ImpitError::TooManyRedirects(context.max_redirects);
         }
 
+        if let Some(h2_error) = find_source::<h2::Error>(&error).filter(|e| e.is_remote()) {
+            if h2_error.is_go_away() {
+                return ImpitError::GoAway(h2_error.to_string());
+            }
+            if h2_error.reason() == Some(h2::Reason::REFUSED_STREAM) {
+                return ImpitError::RefusedStream(h2_error.to_string());
+            }
+        }
+
         if (error.is_body() || error.is_decode())
             && (format!("{:?}", error).to_lowercase()).contains("unexpectedeof")
         {
@@ -161,3 +194,15 @@ impl ImpitError {
         ImpitError::ReqwestError(format!("{error:#?}"))
     }
 }
+
+/// Returns the first error of type `T` in the source chain of `error`.
+fn find_source<'a, T: Error + 'static>(error: &'a (dyn Error + 'static)) -> Option<&'a T> {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if let Some(found) = error.downcast_ref::<T>() {
+            return Some(found);
+        }
+        source = error.source();
+    }
+    None
+}
@@ -2,6 +2,97 @@
 
 use crate::fingerprint::*;
 
+/// Chrome header overrides for the requests that are not top-level navigations, see
+/// [`BrowserFingerprint::destination_headers`].
+fn destination_headers() -> Vec<(RequestDestination, Vec<(String, String)>)> {
+    vec![
+        (
+            RequestDestination::Iframe,
+            vec![
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "navigate".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "iframe".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Fetch,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "empty".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=1, i".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Script,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "script".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=1".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Style,
+            vec![
+                ("accept".to_string(), "text/css,*/*;q=0.1".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "style".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=0".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Image,
+            vec![
+                (
+                    "accept".to_string(),
+                    "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8".to_string(),
+                ),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "image".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "i".to_string()),
+            ],
+        ),
+    ]
+}
+
+/// Chrome's high-entropy client hints, sent to the origins that ask for them with `Accept-CH`.
+///
+/// Takes the `sec-ch-ua-full-version-list` value, the CPU architecture and the OS version of the captured browser.
+fn client_hints(
+    full_version_list: &str,
+    arch: &str,
+    platform_version: &str,
+) -> Vec<(String, String)> {
+    vec![
+        ("sec-ch-ua-arch".to_string(), format!("\"{arch}\"")),
+        ("sec-ch-ua-bitness".to_string(), "\"64\"".to_string()),
+        (
+            "sec-ch-ua-full-version-list".to_string(),
+            full_version_list.to_string(),
+        ),
+        ("sec-ch-ua-model".to_string(), "\"\"".to_string()),
+        (
+            "sec-ch-ua-platform-version".to_string(),
+            format!("\"{platform_version}\""),
+        ),
+        ("sec-ch-ua-wow64".to_string(), "?0".to_string()),
+    ]
+}
+
 /// Chrome 151 fingerprint module
 ///
 /// Source: capture against <https://tls.peet.ws> from consumer Chrome stable
@@ -24,6 +115,14 @@ pub mod chrome_151 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Not=A?Brand\";v=\"99.0.0.0\", \"Google Chrome\";v=\"151.0.7922.72\", \"Chromium\";v=\"151.0.7922.72\"",
+            "x86",
+            "19.0.0",
+        ))
     }
 
     /// Chrome 151 TLS fingerprint
@@ -146,16 +245,16 @@ pub mod chrome_151 {
             ("sec-ch-ua".to_string(), "\"Not=A?Brand\";v=\"99\", \"Google Chrome\";v=\"151\", \"Chromium\";v=\"151\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/151.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/151.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -173,6 +272,14 @@ pub mod chrome_142 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Chromium\";v=\"142.0.7444.176\", \"Google Chrome\";v=\"142.0.7444.176\", \"Not_A Brand\";v=\"99.0.0.0\"",
+            "arm",
+            "15.7.1",
+        ))
     }
 
     /// Chrome 142 TLS fingerprint
@@ -287,16 +394,16 @@ pub mod chrome_142 {
             ("sec-ch-ua".to_string(), "\"Chromium\";v=\"142\", \"Google Chrome\";v=\"142\", \"Not_A Brand\";v=\"99\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"macOS\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -314,6 +421,14 @@ pub mod chrome_136 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Chromium\";v=\"136.0.7103.114\", \"Google Chrome\";v=\"136.0.7103.114\", \"Not.A/Brand\";v=\"99.0.0.0\"",
+            "arm",
+            "15.4.1",
+        ))
     }
 
     /// Chrome 136 TLS fingerprint
@@ -428,16 +543,16 @@ pub mod chrome_136 {
             ("sec-ch-ua".to_string(), "\"Chromium\";v=\"136\", \"Google Chrome\";v=\"136\", \"Not.A/Brand\";v=\"99\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"macOS\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/136.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/136.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -455,6 +570,14 @@ pub mod chrome_133 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Not(A:Brand\";v=\"99.0.0.0\", \"Google Chrome\";v=\"133.0.6943.142\", \"Chromium\";v=\"133.0.6943.142\"",
+            "arm",
+            "15.3.0",
+        ))
     }
 
     /// Chrome 133 TLS fingerprint
@@ -563,16 +686,16 @@ pub mod chrome_133 {
             ("sec-ch-ua".to_string(), "\"Not(A:Brand\";v=\"99\", \"Google Chrome\";v=\"133\", \"Chromium\";v=\"133\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"macOS\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -590,6 +713,14 @@ pub mod chrome_124 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Chromium\";v=\"124.0.6367.208\", \"Google Chrome\";v=\"124.0.6367.208\", \"Not-A.Brand\";v=\"99.0.0.0\"",
+            "arm",
+            "14.4.1",
+        ))
     }
 
     /// Chrome 124 TLS fingerprint
@@ -698,16 +829,16 @@ pub mod chrome_124 {
             ("sec-ch-ua".to_string(), "\"Chromium\";v=\"124\", \"Google Chrome\";v=\"124\", \"Not-A.Brand\";v=\"99\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"macOS\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -725,6 +856,14 @@ pub mod chrome_131 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Google Chrome\";v=\"131.0.6778.205\", \"Chromium\";v=\"131.0.6778.205\", \"Not_A Brand\";v=\"24.0.0.0\"",
+            "arm",
+            "15.1.0",
+        ))
     }
 
     /// Chrome 131 TLS fingerprint
@@ -836,16 +975,16 @@ pub mod chrome_131 {
             ("sec-ch-ua".to_string(), "\"Google Chrome\";v=\"131\", \"Chromium\";v=\"131\", \"Not_A Brand\";v=\"24\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"macOS\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
         ]
     }
 }
@@ -863,6 +1002,14 @@ pub mod chrome_100 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\" Not A;Brand\";v=\"99.0.0.0\", \"Chromium\";v=\"100.0.4896.75\", \"Google Chrome\";v=\"100.0.4896.75\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     /// Chrome 100 TLS fingerprint
@@ -968,15 +1115,15 @@ pub mod chrome_100 {
             ("sec-ch-ua".to_string(), "\" Not A;Brand\";v=\"99\", \"Chromium\";v=\"100\", \"Google Chrome\";v=\"100\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/100.0.4896.75 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/100.0.4896.75 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -994,6 +1141,14 @@ pub mod chrome_101 {
             chrome_100::http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\" Not A;Brand\";v=\"99.0.0.0\", \"Chromium\";v=\"101.0.4951.67\", \"Google Chrome\";v=\"101.0.4951.67\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     fn headers() -> Vec<(String, String)> {
@@ -1001,15 +1156,15 @@ pub mod chrome_101 {
             ("sec-ch-ua".to_string(), "\" Not A;Brand\";v=\"99\", \"Chromium\";v=\"101\", \"Google Chrome\";v=\"101\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/101.0.4951.67 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/101.0.4951.67 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -1027,6 +1182,14 @@ pub mod chrome_104 {
             chrome_100::http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\" Not A;Brand\";v=\"99.0.0.0\", \"Chromium\";v=\"104.0.5112.102\", \"Google Chrome\";v=\"104.0.5112.102\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     fn headers() -> Vec<(String, String)> {
@@ -1034,15 +1197,15 @@ pub mod chrome_104 {
             ("sec-ch-ua".to_string(), "\" Not A;Brand\";v=\"99\", \"Chromium\";v=\"104\", \"Google Chrome\";v=\"104\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/104.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/104.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -1060,6 +1223,14 @@ pub mod chrome_107 {
             chrome_100::http2_fingerprint(), // TODO Chrome 107 uses different HTTP/2 settings
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\" Not A;Brand\";v=\"99.0.0.0\", \"Chromium\";v=\"107.0.5304.110\", \"Google Chrome\";v=\"107.0.5304.110\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     fn headers() -> Vec<(String, String)> {
@@ -1067,15 +1238,15 @@ pub mod chrome_107 {
             ("sec-ch-ua".to_string(), "\" Not A;Brand\";v=\"99\", \"Chromium\";v=\"107\", \"Google Chrome\";v=\"107\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/107.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/107.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -1093,6 +1264,14 @@ pub mod chrome_110 {
             chrome_100::http2_fingerprint(), // TODO Chrome 110 uses different HTTP/2 settings
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Chromium\";v=\"110.0.5481.178\", \"Not A(Brand\";v=\"24.0.0.0\", \"Google Chrome\";v=\"110.0.5481.178\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     fn headers() -> Vec<(String, String)> {
@@ -1100,15 +1279,15 @@ pub mod chrome_110 {
             ("sec-ch-ua".to_string(), "\"Chromium\";v=\"110\", \"Not A(Brand\";v=\"24\", \"Google Chrome\";v=\"110\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/110.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/110.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -1126,6 +1305,14 @@ pub mod chrome_116 {
             chrome_100::http2_fingerprint(), // TODO Chrome 116 uses different HTTP/2 settings
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Chromium\";v=\"116.0.5845.188\", \"Not)A;Brand\";v=\"24.0.0.0\", \"Google Chrome\";v=\"116.0.5845.188\"",
+            "x86",
+            "10.0.0",
+        ))
     }
 
     fn headers() -> Vec<(String, String)> {
@@ -1133,15 +1320,15 @@ pub mod chrome_116 {
             ("sec-ch-ua".to_string(), "\"Chromium\";v=\"116\", \"Not)A;Brand\";v=\"24\", \"Google Chrome\";v=\"116\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Windows\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/116.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/116.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.9".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
@@ -1159,6 +1346,14 @@ pub mod chrome_125 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-language".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
+        .with_client_hints(client_hints(
+            "\"Google Chrome\";v=\"125.0.6422.141\", \"Chromium\";v=\"125.0.6422.141\", \"Not.A/Brand\";v=\"24.0.0.0\"",
+            "x86",
+            "6.5.0",
+        ))
     }
 
     /// Chrome 125 TLS fingerprint
@@ -1269,15 +1464,15 @@ pub mod chrome_125 {
             ("sec-ch-ua".to_string(), "\"Google Chrome\";v=\"125\", \"Chromium\";v=\"125\", \"Not.A/Brand\";v=\"24\"".to_string()),
             ("sec-ch-ua-mobile".to_string(), "?0".to_string()),
             ("sec-ch-ua-platform".to_string(), "\"Linux\"".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("user-agent".to_string(), "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/125.0.0.0 Safari/537.36".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/125.0.0.0 Safari/537.36".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
         ]
     }
 }
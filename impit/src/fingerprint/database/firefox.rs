@@ -2,6 +2,75 @@
 
 use crate::fingerprint::*;
 
+/// Firefox header overrides for the requests that are not top-level navigations, see
+/// [`BrowserFingerprint::destination_headers`].
+fn destination_headers() -> Vec<(RequestDestination, Vec<(String, String)>)> {
+    vec![
+        (
+            RequestDestination::Iframe,
+            vec![
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "navigate".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "iframe".to_string()),
+                ("priority".to_string(), "u=4".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Fetch,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "empty".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=4".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Script,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "script".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=2".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Style,
+            vec![
+                ("accept".to_string(), "text/css,*/*;q=0.1".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "style".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=2".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Image,
+            vec![
+                (
+                    "accept".to_string(),
+                    "image/avif,image/webp,image/png,image/svg+xml,image/*;q=0.8,*/*;q=0.5"
+                        .to_string(),
+                ),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-user".to_string(), "".to_string()),
+                ("sec-fetch-dest".to_string(), "image".to_string()),
+                ("upgrade-insecure-requests".to_string(), "".to_string()),
+                ("priority".to_string(), "u=5, i".to_string()),
+            ],
+        ),
+    ]
+}
+
 /// Firefox 128 fingerprint module
 pub mod firefox_128 {
     use super::*;
@@ -15,6 +84,9 @@ pub mod firefox_128 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-encoding".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
     }
 
     /// Firefox 128 TLS fingerprint
@@ -133,10 +205,10 @@ pub mod firefox_128 {
             ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/png,image/svg+xml,*/*;q=0.8".to_string()),
             ("Accept-Language".to_string(), "en,cs;q=0.7,en-US;q=0.3".to_string()),
             ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
             ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
             ("Priority".to_string(), "u=0, i".to_string()),
         ]
@@ -156,6 +228,9 @@ pub mod firefox_133 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-encoding".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
     }
 
     /// Firefox 133 TLS fingerprint
@@ -270,17 +345,17 @@ pub mod firefox_133 {
     /// Firefox 133 HTTP headers
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:133.0) Gecko/20100101 Firefox/133.0".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.5".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
-            ("te".to_string(), "trailers".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:133.0) Gecko/20100101 Firefox/133.0".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.5".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
+            ("TE".to_string(), "trailers".to_string()),
         ]
     }
 }
@@ -298,6 +373,9 @@ pub mod firefox_135 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-encoding".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
     }
 
     /// Firefox 135 TLS fingerprint
@@ -416,17 +494,17 @@ pub mod firefox_135 {
     /// Firefox 135 HTTP headers
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:135.0) Gecko/20100101 Firefox/135.0".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.5".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
-            ("te".to_string(), "trailers".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:135.0) Gecko/20100101 Firefox/135.0".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.5".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
+            ("TE".to_string(), "trailers".to_string()),
         ]
     }
 }
@@ -445,22 +523,25 @@ pub mod firefox_144 {
             firefox_135::http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
+        .with_cookie_position(HeaderPosition::After("accept-encoding".to_string()))
+        .with_referer_position(HeaderPosition::Before("accept-encoding".to_string()))
     }
 
     /// Firefox 144 HTTP headers
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:144.0) Gecko/20100101 Firefox/144.0".to_string()),
-            ("accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.5".to_string()),
-            ("accept-encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
-            ("upgrade-insecure-requests".to_string(), "1".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-user".to_string(), "?1".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
-            ("te".to_string(), "trailers".to_string()),
+            ("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:144.0) Gecko/20100101 Firefox/144.0".to_string()),
+            ("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.5".to_string()),
+            ("Accept-Encoding".to_string(), "gzip, deflate, br, zstd".to_string()),
+            ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-User".to_string(), "?1".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
+            ("TE".to_string(), "trailers".to_string()),
         ]
     }
 }
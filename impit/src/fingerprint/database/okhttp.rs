@@ -72,6 +72,7 @@ pub mod okhttp3 {
             shared_http2_fingerprint(),
             headers(),
         )
+        .with_cookie_position(HeaderPosition::Before("user-agent".to_string()))
     }
 
     pub(crate) fn tls_fingerprint() -> TlsFingerprint {
@@ -134,8 +135,8 @@ pub mod okhttp3 {
 
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "okhttp/3.14.9".to_string()),
-            ("accept-encoding".to_string(), "gzip".to_string()),
+            ("User-Agent".to_string(), "okhttp/3.14.9".to_string()),
+            ("Accept-Encoding".to_string(), "gzip".to_string()),
         ]
     }
 }
@@ -155,6 +156,7 @@ pub mod okhttp4 {
             shared_http2_fingerprint(),
             headers(),
         )
+        .with_cookie_position(HeaderPosition::Before("user-agent".to_string()))
     }
 
     /// OkHttp 4 TLS fingerprint (Conscrypt/BoringSSL on Android)
@@ -224,8 +226,8 @@ pub mod okhttp4 {
 
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "okhttp/4.12.0".to_string()),
-            ("accept-encoding".to_string(), "gzip".to_string()),
+            ("User-Agent".to_string(), "okhttp/4.12.0".to_string()),
+            ("Accept-Encoding".to_string(), "gzip".to_string()),
         ]
     }
 }
@@ -248,12 +250,13 @@ pub mod okhttp5 {
             shared_http2_fingerprint(),
             headers(),
         )
+        .with_cookie_position(HeaderPosition::Before("user-agent".to_string()))
     }
 
     fn headers() -> Vec<(String, String)> {
         vec![
-            ("user-agent".to_string(), "okhttp/5.0.0".to_string()),
-            ("accept-encoding".to_string(), "gzip".to_string()),
+            ("User-Agent".to_string(), "okhttp/5.0.0".to_string()),
+            ("Accept-Encoding".to_string(), "gzip".to_string()),
         ]
     }
 }
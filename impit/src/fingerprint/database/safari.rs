@@ -14,6 +14,61 @@
 
 use crate::fingerprint::*;
 
+/// Safari header overrides for the requests that are not top-level navigations, see
+/// [`BrowserFingerprint::destination_headers`].
+fn destination_headers() -> Vec<(RequestDestination, Vec<(String, String)>)> {
+    vec![
+        (
+            RequestDestination::Iframe,
+            vec![
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "navigate".to_string()),
+                ("sec-fetch-dest".to_string(), "iframe".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Fetch,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "cors".to_string()),
+                ("sec-fetch-dest".to_string(), "empty".to_string()),
+                ("priority".to_string(), "u=3, i".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Script,
+            vec![
+                ("accept".to_string(), "*/*".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-dest".to_string(), "script".to_string()),
+                ("priority".to_string(), "u=2".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Style,
+            vec![
+                ("accept".to_string(), "text/css,*/*;q=0.1".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-dest".to_string(), "style".to_string()),
+                ("priority".to_string(), "u=1".to_string()),
+            ],
+        ),
+        (
+            RequestDestination::Image,
+            vec![
+                ("accept".to_string(), "image/webp,image/avif,image/jxl,image/heic,image/heic-sequence,video/*;q=0.8,image/png,image/svg+xml,image/*;q=0.8,*/*;q=0.5".to_string()),
+                ("sec-fetch-site".to_string(), "same-origin".to_string()),
+                ("sec-fetch-mode".to_string(), "no-cors".to_string()),
+                ("sec-fetch-dest".to_string(), "image".to_string()),
+                ("priority".to_string(), "u=5, i".to_string()),
+            ],
+        ),
+    ]
+}
+
 /// iOS 18 system TLS fingerprint module
 pub mod ios_18 {
     use super::*;
@@ -26,6 +81,7 @@ pub mod ios_18 {
             http2_fingerprint(),
             headers(),
         )
+        .with_destination_headers(destination_headers())
     }
 
     fn tls_fingerprint() -> TlsFingerprint {
@@ -138,20 +194,20 @@ pub mod ios_18 {
     fn headers() -> Vec<(String, String)> {
         vec![
             (
-                "user-agent".to_string(),
+                "User-Agent".to_string(),
                 "Mozilla/5.0 (iPhone; CPU iPhone OS 18_7 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/26.5 Mobile/15E148 Safari/604.1".to_string(),
             ),
             (
-                "accept".to_string(),
+                "Accept".to_string(),
                 "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8".to_string(),
             ),
-            ("sec-fetch-site".to_string(), "none".to_string()),
-            ("sec-fetch-mode".to_string(), "navigate".to_string()),
-            ("sec-fetch-dest".to_string(), "document".to_string()),
-            ("accept-language".to_string(), "en-US,en;q=0.9".to_string()),
-            ("priority".to_string(), "u=0, i".to_string()),
+            ("Sec-Fetch-Site".to_string(), "none".to_string()),
+            ("Sec-Fetch-Mode".to_string(), "navigate".to_string()),
+            ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ("Accept-Language".to_string(), "en-US,en;q=0.9".to_string()),
+            ("Priority".to_string(), "u=0, i".to_string()),
             (
-                "accept-encoding".to_string(),
+                "Accept-Encoding".to_string(),
                 "gzip, deflate, br, zstd".to_string(),
             ),
         ]
@@ -6,8 +6,12 @@
 pub mod database;
 mod types;
 
+use std::str::FromStr;
+
 use rand::Rng;
 
+use crate::errors::ImpitError;
+
 pub use types::*;
 
 /// A complete browser fingerprint containing TLS, HTTP/2, and HTTP header configurations.
@@ -17,7 +21,102 @@ pub struct BrowserFingerprint {
     pub version: String,
     pub tls: TlsFingerprint,
     pub http2: Http2Fingerprint,
+    /// The headers sent with every request, in order.
+    ///
+    /// The names are spelled as the browser writes them over HTTP/1.1, e.g. Chrome's `User-Agent` and `sec-ch-ua`.
+    /// HTTP/2 and HTTP/3 always use lowercase names.
     pub headers: Vec<(String, String)>,
+    /// Overrides of the destination-dependent [`headers`](Self::headers) for the requests that are not navigations.
+    ///
+    /// An override replaces the value of the header with the same (case-insensitive) name in place, an empty value
+    /// removes it. Headers missing from [`headers`](Self::headers) are never added.
+    ///
+    /// The subresources and `fetch()` calls are listed as if they came from a same-origin document. The actual
+    /// `sec-fetch-site` is set by [`Impit`](crate::impit::Impit), from the current document or the request's initiator.
+    pub destination_headers: Vec<(RequestDestination, Vec<(String, String)>)>,
+    /// Where the `Cookie` header from the cookie store goes among the other headers.
+    pub cookie_position: HeaderPosition,
+    /// Where the `Referer` header added by the navigation tracking and the redirects goes among the other headers.
+    pub referer_position: HeaderPosition,
+    /// The high-entropy User-Agent Client Hints (e.g. `sec-ch-ua-full-version-list` or `sec-ch-ua-arch`), only sent to
+    /// the origins that asked for them with the `Accept-CH` response header. Empty for the browsers without Client Hints.
+    pub client_hints: Vec<(String, String)>,
+}
+
+/// What a request is fetching, i.e. the value of the browser's `Sec-Fetch-Dest` header.
+///
+/// Browsers send different `Accept`, `Sec-Fetch-*`, `Upgrade-Insecure-Requests` and `Priority` headers
+/// depending on the destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RequestDestination {
+    /// A top-level navigation, e.g. typing the URL into the address bar or clicking a link.
+    #[default]
+    Document,
+    /// A navigation inside an `<iframe>`.
+    Iframe,
+    /// A `fetch()` call or an `XMLHttpRequest`.
+    Fetch,
+    /// A `<script>` element.
+    Script,
+    /// A stylesheet, e.g. a `<link rel="stylesheet">` element.
+    Style,
+    /// An `<img>` element or a CSS background image.
+    Image,
+}
+
+impl FromStr for RequestDestination {
+    type Err = ImpitError;
+
+    /// Parses a destination name, e.g. `script`. The `Sec-Fetch-Dest` value `empty` stands for [`RequestDestination::Fetch`].
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "document" | "navigate" => Ok(RequestDestination::Document),
+            "iframe" => Ok(RequestDestination::Iframe),
+            "fetch" | "xhr" | "empty" => Ok(RequestDestination::Fetch),
+            "script" => Ok(RequestDestination::Script),
+            "style" => Ok(RequestDestination::Style),
+            "image" => Ok(RequestDestination::Image),
+            _ => Err(ImpitError::InvalidRequestDestination(value.to_string())),
+        }
+    }
+}
+
+/// Where a header that isn't part of the fingerprint goes among the other headers, e.g. the extra custom headers
+/// or the `Cookie` header.
+///
+/// The custom headers overriding a fingerprint header always keep that header's position.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HeaderPosition {
+    /// Before all the other headers.
+    Start,
+    /// After all the other headers.
+    #[default]
+    End,
+    /// Right before the given header (matched case-insensitively), or at the end if it isn't sent.
+    Before(String),
+    /// Right after the given header (matched case-insensitively), or at the end if it isn't sent.
+    After(String),
+}
+
+impl FromStr for HeaderPosition {
+    type Err = ImpitError;
+
+    /// Parses `start`, `end`, `before:<header>` or `after:<header>`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        match value.split_once(':') {
+            Some((position, header)) if !header.trim().is_empty() => {
+                match position.trim().to_ascii_lowercase().as_str() {
+                    "before" => Ok(HeaderPosition::Before(header.trim().to_string())),
+                    "after" => Ok(HeaderPosition::After(header.trim().to_string())),
+                    _ => Err(ImpitError::InvalidHeaderPosition(value.to_string())),
+                }
+            }
+            None if value.eq_ignore_ascii_case("start") => Ok(HeaderPosition::Start),
+            None if value.eq_ignore_ascii_case("end") => Ok(HeaderPosition::End),
+            _ => Err(ImpitError::InvalidHeaderPosition(value.to_string())),
+        }
+    }
 }
 
 impl BrowserFingerprint {
@@ -34,9 +133,62 @@ impl BrowserFingerprint {
             tls,
             http2,
             headers,
+            destination_headers: vec![],
+            cookie_position: HeaderPosition::default(),
+            referer_position: HeaderPosition::default(),
+            client_hints: vec![],
         }
     }
 
+    /// Sets where the `Cookie` header goes, see [`BrowserFingerprint::cookie_position`].
+    pub fn with_cookie_position(mut self, cookie_position: HeaderPosition) -> Self {
+        self.cookie_position = cookie_position;
+        self
+    }
+
+    /// Sets where the automatic `Referer` header goes, see [`BrowserFingerprint::referer_position`].
+    pub fn with_referer_position(mut self, referer_position: HeaderPosition) -> Self {
+        self.referer_position = referer_position;
+        self
+    }
+
+    /// Sets the high-entropy client hints, see [`BrowserFingerprint::client_hints`].
+    pub fn with_client_hints(mut self, client_hints: Vec<(String, String)>) -> Self {
+        self.client_hints = client_hints;
+        self
+    }
+
+    /// Sets the per-destination header overrides, see [`BrowserFingerprint::destination_headers`].
+    pub fn with_destination_headers(
+        mut self,
+        destination_headers: Vec<(RequestDestination, Vec<(String, String)>)>,
+    ) -> Self {
+        self.destination_headers = destination_headers;
+        self
+    }
+
+    /// Returns the headers this browser sends for a request to the given destination.
+    pub fn headers_for(&self, destination: RequestDestination) -> Vec<(String, String)> {
+        let Some((_, overrides)) = self
+            .destination_headers
+            .iter()
+            .find(|(target, _)| *target == destination)
+        else {
+            return self.headers.clone();
+        };
+
+        self.headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = overrides
+                    .iter()
+                    .find(|(overridden, _)| overridden.eq_ignore_ascii_case(name))
+                    .map_or(value, |(_, value)| value);
+                (!value.is_empty()).then(|| (name.clone(), value.clone()))
+            })
+            .collect()
+    }
+
     pub fn generate_multipart_boundary(&self) -> String {
         match self.name.as_str() {
             "Chrome" => {
@@ -444,3 +596,75 @@ impl TlsFingerprint {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn destination_headers_replace_the_navigation_headers() {
+        let fingerprint = database::firefox_128::fingerprint();
+
+        assert_eq!(
+            fingerprint.headers_for(RequestDestination::Document),
+            fingerprint.headers
+        );
+
+        let headers = fingerprint.headers_for(RequestDestination::Fetch);
+        assert_eq!(headers.len(), fingerprint.headers.len() - 2);
+        assert_eq!(header(&headers, "accept"), Some("*/*"));
+        assert_eq!(header(&headers, "sec-fetch-dest"), Some("empty"));
+        assert_eq!(header(&headers, "sec-fetch-user"), None);
+        assert_eq!(header(&headers, "upgrade-insecure-requests"), None);
+        // The original header names and positions are kept.
+        assert_eq!(headers[1].0, "Accept");
+    }
+
+    #[test]
+    fn destination_headers_are_not_added() {
+        let fingerprint = database::okhttp4::fingerprint();
+
+        assert_eq!(
+            fingerprint.headers_for(RequestDestination::Image),
+            fingerprint.headers
+        );
+
+        let fingerprint = database::ios_18::fingerprint();
+        let headers = fingerprint.headers_for(RequestDestination::Image);
+        assert_eq!(header(&headers, "sec-fetch-user"), None);
+        assert_eq!(header(&headers, "sec-fetch-mode"), Some("no-cors"));
+    }
+
+    #[test]
+    fn invalid_destinations_are_rejected() {
+        assert_eq!(
+            "empty".parse::<RequestDestination>().unwrap(),
+            RequestDestination::Fetch
+        );
+        assert!(matches!(
+            "video".parse::<RequestDestination>(),
+            Err(ImpitError::InvalidRequestDestination(destination)) if destination == "video"
+        ));
+    }
+
+    #[test]
+    fn invalid_header_positions_are_rejected() {
+        assert_eq!(
+            "after: Accept-Language".parse::<HeaderPosition>().unwrap(),
+            HeaderPosition::After("Accept-Language".to_string())
+        );
+        for position in ["middle", "after:", "around:accept"] {
+            assert!(matches!(
+                position.parse::<HeaderPosition>(),
+                Err(ImpitError::InvalidHeaderPosition(_))
+            ));
+        }
+    }
+}
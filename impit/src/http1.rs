@@ -0,0 +1,908 @@
+//! HTTP/1.1 client that keeps the casing of the header names.
+//!
+//! `hyper` keeps the header names in a [`HeaderMap`], which lowercases them. It only writes them in their original
+//! casing from a private extension, which it attaches to the responses parsed with `preserve_header_case` and reads
+//! from the requests it sends. `reqwest` doesn't pass the request extensions on to `hyper` (it only offers
+//! `http1_title_case_headers`, while the browsers mix the casings, e.g. `User-Agent` and `sec-ch-ua`), so the requests
+//! whose header names aren't all lowercase are sent by the [`Http1Client`], on `hyper` connections it opens itself.
+//! The other requests, and the ones through proxies it can't connect through, are left to `reqwest`.
+//!
+//! HTTPS origins pick the protocol in the TLS handshake. Their requests are sent here until they pick another protocol
+//! than HTTP/1.1, the request is then sent by `reqwest` instead and the origin isn't tried here again.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use http::Extensions;
+use hyper::{
+    body::{Body as _, Incoming},
+    client::conn::http1::{self, SendRequest},
+};
+use hyper_util::{
+    client::proxy::matcher::{Intercept, Matcher},
+    rt::TokioIo,
+};
+use log::debug;
+use percent_encoding::percent_decode_str;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, PROXY_AUTHORIZATION},
+    Method, Response, ResponseBuilderExt, Version,
+};
+use rustls::pki_types::ServerName;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+    task::JoinSet,
+    time::Instant,
+};
+use tokio_rustls::TlsConnector;
+use url::{Host, Position, Url};
+
+use crate::{
+    dns::Resolver,
+    errors::ImpitError,
+    quic::{decode_body, SocketBinding, Socks5Proxy, TargetAddr},
+    request::ImpitBody,
+    routing::Route,
+};
+
+/// The headers added after the header list is final (by `Impit` or by `hyper`), in the casing the browsers use.
+const ADDED_HEADERS: [&str; 4] = ["Host", "Cookie", "Content-Length", "Transfer-Encoding"];
+
+/// The longest response to a `CONNECT` request we read before giving up on the proxy.
+const MAX_TUNNEL_RESPONSE: usize = 8 * 1024;
+
+/// How long an idle connection is kept in the pool, like in `reqwest`.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Maximum number of connections kept in the pool for an origin, like the browsers' limit for HTTP/1.1.
+const MAX_CONNECTIONS_PER_ORIGIN: usize = 6;
+
+/// Maximum number of HTTPS origins remembered to pick another protocol than HTTP/1.1.
+const MAX_TRACKED_ORIGINS: usize = 1024;
+
+/// Maximum number of header name lists whose casing is kept for reuse.
+const MAX_HEADER_CASES: usize = 64;
+
+/// Whether any of the header names is spelled in another casing than lowercase, i.e. has to be sent by
+/// the [`Http1Client`] to keep it.
+pub(crate) fn has_header_case(names: &[String]) -> bool {
+    names
+        .iter()
+        .any(|name| name.bytes().any(|byte| byte.is_ascii_uppercase()))
+}
+
+/// Errors returned by [`Http1Client::send`].
+#[derive(Debug)]
+pub(crate) enum Http1Error {
+    /// The server picked another protocol than HTTP/1.1 in the TLS handshake (or the client can't connect through
+    /// the proxy for the request), so the request wasn't sent.
+    ProtocolMismatch,
+    /// The request failed.
+    Request(ImpitError),
+}
+
+impl From<Http1Error> for ImpitError {
+    fn from(err: Http1Error) -> Self {
+        match err {
+            Http1Error::ProtocolMismatch => ImpitError::ProtocolError,
+            Http1Error::Request(err) => err,
+        }
+    }
+}
+
+/// A proxy the [`Http1Client`] can send the requests through.
+#[derive(Debug, Clone)]
+enum Proxy {
+    /// An HTTP proxy, forwarding the plain `http` requests and tunneling the HTTPS ones with `CONNECT`.
+    Http {
+        host: String,
+        port: u16,
+        authorization: Option<HeaderValue>,
+    },
+    Socks5(Socks5Proxy),
+}
+
+impl Proxy {
+    /// Parses the proxy URL, returning `None` for the proxies the [`Http1Client`] can't connect through.
+    fn from_url(proxy_url: &str) -> Option<Self> {
+        if let Some(proxy) = Socks5Proxy::from_url(proxy_url) {
+            return Some(Proxy::Socks5(proxy));
+        }
+
+        let url = Url::parse(proxy_url).ok()?;
+        if url.scheme() != "http" {
+            return None;
+        }
+
+        let authorization = if url.username().is_empty() {
+            None
+        } else {
+            let decode = |value: &str| percent_decode_str(value).decode_utf8_lossy().to_string();
+            let credentials = format!(
+                "{}:{}",
+                decode(url.username()),
+                decode(url.password().unwrap_or_default())
+            );
+            let mut value =
+                HeaderValue::from_str(&format!("Basic {}", BASE64_STANDARD.encode(credentials)))
+                    .ok()?;
+            value.set_sensitive(true);
+            Some(value)
+        };
+
+        Some(Proxy::Http {
+            host: url.host_str()?.trim_matches(['[', ']']).to_string(),
+            port: url.port_or_known_default().unwrap_or(80),
+            authorization,
+        })
+    }
+
+    /// Converts a proxy picked from the environment, returning `None` for the proxies the [`Http1Client`]
+    /// can't connect through.
+    fn from_intercept(intercept: &Intercept) -> Option<Self> {
+        let uri = intercept.uri();
+        match uri.scheme_str()? {
+            "http" => Some(Proxy::Http {
+                host: uri.host()?.trim_matches(['[', ']']).to_string(),
+                port: uri.port_u16().unwrap_or(80),
+                authorization: intercept.basic_auth().cloned(),
+            }),
+            "socks5" | "socks5h" => {
+                let mut url = Url::parse(&uri.to_string()).ok()?;
+                if let Some((username, password)) = intercept.raw_auth() {
+                    url.set_username(username).ok()?;
+                    url.set_password(Some(password)).ok()?;
+                }
+                Socks5Proxy::from_url(url.as_str()).map(Proxy::Socks5)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The proxies the [`Http1Client`] sends the requests through.
+enum Proxies {
+    /// The proxy from the `Impit` config (if any), used for all the requests.
+    Configured(Option<Proxy>),
+    /// The proxies from the environment, picked for every request like `reqwest` does, including `NO_PROXY`.
+    System(Box<Matcher>),
+}
+
+/// A connection in the pool of an origin.
+struct PooledConnection {
+    send_request: SendRequest<reqwest::Body>,
+    /// When the last request was sent on the connection.
+    used: Instant,
+}
+
+impl PooledConnection {
+    fn is_expired(&self) -> bool {
+        self.send_request.is_closed() || self.used.elapsed() >= IDLE_TIMEOUT
+    }
+}
+
+/// Any stream a `hyper` connection can run on.
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+/// Sends HTTP/1.1 requests with the header names in their original casing, see the [module docs](self).
+pub(crate) struct Http1Client {
+    tls: TlsConnector,
+    proxies: Proxies,
+    binding: SocketBinding,
+    resolver: Arc<Resolver>,
+    route: Route,
+    /// The open connections for each origin, both the idle and the busy ones, the least recently used first.
+    connections: Mutex<HashMap<String, Vec<PooledConnection>>>,
+    /// The HTTPS origins that picked another protocol than HTTP/1.1.
+    other_protocol_origins: Mutex<HashSet<String>>,
+    /// The request extensions keeping the casing of the recently sent header name lists.
+    header_cases: Mutex<HashMap<Vec<String>, Extensions>>,
+}
+
+impl Http1Client {
+    /// Creates the client, or returns `None` if it can't connect through the proxy (e.g. an HTTPS proxy).
+    ///
+    /// Without a proxy URL, the proxies are picked from the environment, like `reqwest` does.
+    pub(crate) fn new(
+        tls_config: rustls::ClientConfig,
+        proxy_url: &str,
+        binding: SocketBinding,
+        resolver: Arc<Resolver>,
+        route: Route,
+    ) -> Option<Self> {
+        let proxies = match proxy_url {
+            "" => Proxies::System(Box::new(Matcher::from_system())),
+            proxy_url => Proxies::Configured(Some(Proxy::from_url(proxy_url)?)),
+        };
+
+        Some(Http1Client {
+            tls: TlsConnector::from(Arc::new(tls_config)),
+            proxies,
+            binding,
+            resolver,
+            route,
+            connections: Mutex::new(HashMap::new()),
+            other_protocol_origins: Mutex::new(HashSet::new()),
+            header_cases: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Whether the request to `url` can be sent by this client, i.e. it's not known to go over another protocol
+    /// than HTTP/1.1 and the client can connect through its proxy.
+    pub(crate) fn handles(&self, url: &Url) -> bool {
+        let http1 = match url.scheme() {
+            "http" => true,
+            "https" => !self
+                .other_protocol_origins
+                .lock()
+                .unwrap()
+                .contains(&origin(url)),
+            _ => false,
+        };
+        http1 && self.proxy(url).is_some()
+    }
+
+    /// Remembers the protocol an HTTPS origin answered a request with.
+    pub(crate) fn record_version(&self, url: &Url, version: Version) {
+        if url.scheme() != "https" {
+            return;
+        }
+
+        let mut other_protocol_origins = self.other_protocol_origins.lock().unwrap();
+        if version == Version::HTTP_11 {
+            other_protocol_origins.remove(&origin(url));
+        } else {
+            if other_protocol_origins.len() >= MAX_TRACKED_ORIGINS {
+                other_protocol_origins.clear();
+            }
+            other_protocol_origins.insert(origin(url));
+        }
+    }
+
+    /// Returns the proxy for a request to `url` (if any), or `None` if the client can't connect through it.
+    fn proxy(&self, url: &Url) -> Option<Option<Proxy>> {
+        match &self.proxies {
+            Proxies::Configured(proxy) => Some(proxy.clone()),
+            Proxies::System(matcher) => {
+                let uri = url.as_str().parse::<http::Uri>().ok()?;
+                match matcher.intercept(&uri) {
+                    Some(intercept) => Proxy::from_intercept(&intercept).map(Some),
+                    None => Some(None),
+                }
+            }
+        }
+    }
+
+    /// Sends the request, writing the header names of `headers` as they are spelled in `header_names`.
+    ///
+    /// The `body` is only taken once the request can be sent, so it's kept on [`Http1Error::ProtocolMismatch`].
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        headers: HeaderMap,
+        header_names: &[String],
+        body: &mut ImpitBody,
+        timeout: Duration,
+    ) -> Result<Response, Http1Error> {
+        // The callers check `handles` first, so this only happens if the environment has changed since.
+        let Some(proxy) = self.proxy(url) else {
+            return Err(Http1Error::ProtocolMismatch);
+        };
+        let key = origin(url);
+        let exchange = async {
+            let pooled = self.pooled(&key);
+            let fresh = pooled.is_none();
+            let mut send_request = match pooled {
+                Some(send_request) => send_request,
+                None => self.connect(url, proxy.as_ref()).await?,
+            };
+
+            let mut request = self.request(
+                method,
+                url,
+                headers,
+                body.take().unwrap_or_default(),
+                proxy.as_ref(),
+            )?;
+            *request.extensions_mut() = self.header_case(header_names).await;
+
+            let sent = send_request.try_send_request(request);
+            self.release(&key, send_request);
+            match sent.await {
+                Ok(response) => Ok(response),
+                // The server may have closed an idle connection just as the request was sent on it.
+                Err(mut err) if !fresh => match err.take_message() {
+                    Some(request) => {
+                        let mut send_request = self.connect(url, proxy.as_ref()).await?;
+                        let sent = send_request.send_request(request);
+                        self.release(&key, send_request);
+                        sent.await.map_err(request_error)
+                    }
+                    None => Err(request_error(err.into_error())),
+                },
+                Err(err) => Err(request_error(err.into_error())),
+            }
+        };
+
+        let response = match tokio::time::timeout(timeout, exchange).await {
+            Ok(response) => response?,
+            Err(_) => {
+                return Err(Http1Error::Request(ImpitError::TimeoutException(Some(
+                    timeout.as_millis(),
+                ))))
+            }
+        };
+
+        let (parts, body) = response.into_parts();
+        let mut headers = parts.headers;
+        let body = decode_body(&mut headers, ResponseBody(body));
+
+        let mut response = http::Response::builder()
+            .status(parts.status)
+            .version(Version::HTTP_11)
+            .url(url.clone())
+            .body(body)
+            .map_err(|e| Http1Error::Request(ImpitError::ReqwestError(e.to_string())))?;
+        *response.headers_mut() = headers;
+
+        Ok(Response::from(response))
+    }
+
+    /// Builds the request for `url`, in the absolute form if it's forwarded by an HTTP proxy.
+    fn request(
+        &self,
+        method: Method,
+        url: &Url,
+        headers: HeaderMap,
+        body: reqwest::Body,
+        proxy: Option<&Proxy>,
+    ) -> Result<http::Request<reqwest::Body>, Http1Error> {
+        let forwarded_by = match proxy {
+            Some(Proxy::Http { authorization, .. }) if url.scheme() == "http" => {
+                Some(authorization)
+            }
+            _ => None,
+        };
+        let uri = match forwarded_by {
+            Some(_) => url.as_str(),
+            None => &url[Position::BeforePath..Position::AfterQuery],
+        };
+
+        let mut request = http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .version(Version::HTTP_11)
+            .body(body)
+            .map_err(|e| Http1Error::Request(ImpitError::ReqwestError(e.to_string())))?;
+        *request.headers_mut() = headers;
+        if let Some(Some(authorization)) = forwarded_by {
+            request
+                .headers_mut()
+                .insert(PROXY_AUTHORIZATION, authorization.clone());
+        }
+
+        Ok(request)
+    }
+
+    /// Takes the most recently used idle connection to the origin out of the pool.
+    fn pooled(&self, key: &str) -> Option<SendRequest<reqwest::Body>> {
+        let mut connections = self.connections.lock().unwrap();
+        let pooled = connections.get_mut(key)?;
+        pooled.retain(|connection| !connection.is_expired());
+        let index = pooled
+            .iter()
+            .rposition(|connection| connection.send_request.is_ready())?;
+
+        Some(pooled.remove(index).send_request)
+    }
+
+    /// Puts a connection back into the pool. It's idle again once the response body has been read.
+    ///
+    /// The connections closed or idle for longer than [`IDLE_TIMEOUT`] are dropped from the pools of all the origins,
+    /// and the least recently used ones over [`MAX_CONNECTIONS_PER_ORIGIN`] are dropped too. `hyper` closes
+    /// the dropped connections once their responses have been read.
+    fn release(&self, key: &str, send_request: SendRequest<reqwest::Body>) {
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|_, pooled| {
+            pooled.retain(|connection| !connection.is_expired());
+            !pooled.is_empty()
+        });
+
+        let pooled = connections.entry(key.to_string()).or_default();
+        pooled.push(PooledConnection {
+            send_request,
+            used: Instant::now(),
+        });
+        let excess = pooled.len().saturating_sub(MAX_CONNECTIONS_PER_ORIGIN);
+        pooled.drain(..excess);
+    }
+
+    /// Returns the request extensions that make `hyper` write the header names as they are spelled in `names`,
+    /// see [`header_case`].
+    async fn header_case(&self, names: &[String]) -> Extensions {
+        if let Some(extensions) = self.header_cases.lock().unwrap().get(names) {
+            return extensions.clone();
+        }
+
+        let extensions = match header_case(names).await {
+            Ok(extensions) => extensions,
+            Err(err) => {
+                debug!("Couldn't keep the casing of the header names: {err}");
+                return Extensions::new();
+            }
+        };
+        let mut header_cases = self.header_cases.lock().unwrap();
+        if header_cases.len() >= MAX_HEADER_CASES {
+            header_cases.clear();
+        }
+        header_cases.insert(names.to_vec(), extensions.clone());
+        extensions
+    }
+
+    /// Opens a new connection for a request to `url`, through `proxy` if set.
+    async fn connect(
+        &self,
+        url: &Url,
+        proxy: Option<&Proxy>,
+    ) -> Result<SendRequest<reqwest::Body>, Http1Error> {
+        let host = match url.host() {
+            Some(Host::Domain(domain)) => domain.to_string(),
+            Some(Host::Ipv4(ip)) => ip.to_string(),
+            Some(Host::Ipv6(ip)) => ip.to_string(),
+            None => {
+                return Err(Http1Error::Request(ImpitError::UrlMissingHostnameError(
+                    url.to_string(),
+                )))
+            }
+        };
+        let port = url.port_or_known_default().unwrap_or(80);
+        let (target_host, target_port) = self.route.target(&host, port);
+        let https = url.scheme() == "https";
+
+        let stream = match proxy {
+            None => self.connect_direct(&target_host, target_port).await?,
+            Some(Proxy::Http {
+                host: proxy_host,
+                port: proxy_port,
+                authorization,
+            }) => {
+                let mut stream = self.connect_direct(proxy_host, *proxy_port).await?;
+                if https {
+                    tunnel(
+                        &mut stream,
+                        &target_host,
+                        target_port,
+                        authorization.as_ref(),
+                    )
+                    .await?;
+                }
+                stream
+            }
+            Some(Proxy::Socks5(proxy)) => {
+                let target = if proxy.resolves_remotely() && target_host.parse::<IpAddr>().is_err()
+                {
+                    TargetAddr::Domain(target_host, target_port)
+                } else {
+                    TargetAddr::Ip(self.resolve(&target_host, target_port).await?[0])
+                };
+                proxy
+                    .connect(target, &self.binding, &self.resolver)
+                    .await
+                    .map_err(|e| Http1Error::Request(ImpitError::ConnectError(e.to_string())))?
+            }
+        };
+
+        let io: Box<dyn Io> = if https {
+            let server_name = ServerName::try_from(self.route.server_name(&host).to_string())
+                .map_err(|e| Http1Error::Request(ImpitError::ConnectError(e.to_string())))?;
+            let stream = self
+                .tls
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| Http1Error::Request(ImpitError::ConnectError(e.to_string())))?;
+
+            let (_, connection) = stream.get_ref();
+            if connection
+                .alpn_protocol()
+                .is_some_and(|protocol| protocol != b"http/1.1")
+            {
+                self.record_version(url, Version::HTTP_2);
+                return Err(Http1Error::ProtocolMismatch);
+            }
+            Box::new(stream)
+        } else {
+            Box::new(stream)
+        };
+
+        let (send_request, connection) = http1::Builder::new()
+            .handshake(TokioIo::new(io))
+            .await
+            .map_err(|e| Http1Error::Request(ImpitError::ConnectError(e.to_string())))?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                debug!("HTTP/1.1 connection closed: {err}");
+            }
+        });
+
+        Ok(send_request)
+    }
+
+    /// Races the TCP connections to the addresses of `host`, starting a new one every connection attempt delay
+    /// or as soon as the previous one fails, as per Happy Eyeballs v2.
+    async fn connect_direct(&self, host: &str, port: u16) -> Result<TcpStream, Http1Error> {
+        let attempt_delay = self.resolver.happy_eyeballs().connection_attempt_delay;
+        let mut peers = self.resolve(host, port).await?.into_iter().peekable();
+        let mut attempts = JoinSet::new();
+        let mut last_error = None;
+
+        loop {
+            if let Some(peer) = peers.next() {
+                let binding = self.binding.clone();
+                attempts.spawn(async move { binding.tcp_socket(peer)?.connect(peer).await });
+            }
+
+            if attempts.is_empty() {
+                return Err(Http1Error::Request(ImpitError::ConnectError(
+                    last_error.unwrap_or_else(|| "no addresses to connect to".to_string()),
+                )));
+            }
+
+            tokio::select! {
+                Some(result) = attempts.join_next() => match result {
+                    Ok(Ok(stream)) => {
+                        // Browsers disable Nagle's algorithm, and so does `reqwest`.
+                        let _ = stream.set_nodelay(true);
+                        return Ok(stream);
+                    }
+                    Ok(Err(err)) => last_error = Some(err.to_string()),
+                    Err(err) => last_error = Some(err.to_string()),
+                },
+                _ = tokio::time::sleep(attempt_delay), if peers.peek().is_some() => {}
+            }
+        }
+    }
+
+    /// Resolves `host` locally, with the addresses sorted for Happy Eyeballs.
+    ///
+    /// With a local address set, only the addresses from its family are returned.
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, Http1Error> {
+        let ips = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => self
+                .resolver
+                .lookup_ip(host, Some(port))
+                .await
+                .unwrap_or_default(),
+        };
+
+        let peers: Vec<SocketAddr> = ips
+            .into_iter()
+            .filter(|ip| {
+                self.binding
+                    .local_address
+                    .is_none_or(|local_address| local_address.is_ipv4() == ip.is_ipv4())
+            })
+            .map(|ip| SocketAddr::new(ip, port))
+            .collect();
+
+        if peers.is_empty() {
+            return Err(Http1Error::Request(ImpitError::ConnectError(format!(
+                "Failed to resolve {host}"
+            ))));
+        }
+
+        Ok(peers)
+    }
+}
+
+/// Returns the key of the origin of `url`, the connections are pooled by.
+fn origin(url: &Url) -> String {
+    format!(
+        "{}://{}:{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+fn request_error(err: hyper::Error) -> Http1Error {
+    Http1Error::Request(ImpitError::ReqwestError(format!(
+        "HTTP/1.1 request failed: {err}"
+    )))
+}
+
+/// Opens a `CONNECT` tunnel to `host:port` through the HTTP proxy connected to with `stream`.
+async fn tunnel(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    authorization: Option<&HeaderValue>,
+) -> Result<(), Http1Error> {
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]:{port}"),
+        _ => format!("{host}:{port}"),
+    };
+
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n").into_bytes();
+    if let Some(authorization) = authorization {
+        request.extend_from_slice(b"Proxy-Authorization: ");
+        request.extend_from_slice(authorization.as_bytes());
+        request.extend_from_slice(b"\r\n");
+    }
+    request.extend_from_slice(b"\r\n");
+
+    let connect_error = |e: io::Error| Http1Error::Request(ImpitError::ConnectError(e.to_string()));
+    stream.write_all(&request).await.map_err(connect_error)?;
+
+    // The response is read byte by byte, so that none of the tunneled data is consumed with it.
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > MAX_TUNNEL_RESPONSE {
+            return Err(Http1Error::Request(ImpitError::ProxyTunnelError(None)));
+        }
+        response.push(stream.read_u8().await.map_err(connect_error)?);
+    }
+
+    let status = response
+        .split(|byte| *byte == b' ')
+        .nth(1)
+        .and_then(|status| std::str::from_utf8(status).ok())
+        .and_then(|status| status.parse::<u16>().ok());
+    match status {
+        Some(200..=299) => Ok(()),
+        Some(407) => Err(Http1Error::Request(ImpitError::ProxyAuthRequired)),
+        status => Err(Http1Error::Request(ImpitError::ProxyTunnelError(status))),
+    }
+}
+
+/// Returns the request extensions that make `hyper` write the header names as they are spelled in `names`.
+///
+/// `hyper` only creates its header case map when it parses a message, so a response with the header names is parsed
+/// on an in-memory connection, and its extensions are copied. The names missing from the map are written in
+/// lowercase, so the [`ADDED_HEADERS`] get the browsers' casing unless `names` has them already.
+async fn header_case(
+    names: &[String],
+) -> Result<Extensions, Box<dyn std::error::Error + Send + Sync>> {
+    let added = ADDED_HEADERS
+        .iter()
+        .filter(|added| !names.iter().any(|name| name.eq_ignore_ascii_case(added)));
+
+    let mut head = b"HTTP/1.1 204 No Content\r\n".to_vec();
+    for name in names.iter().map(String::as_str).chain(added.copied()) {
+        head.extend_from_slice(name.as_bytes());
+        head.extend_from_slice(b": 0\r\n");
+    }
+    head.extend_from_slice(b"\r\n");
+
+    let (client, mut server) = tokio::io::duplex(head.len() + 1024);
+    let (mut send_request, connection) = http1::Builder::new()
+        .preserve_header_case(true)
+        .max_headers(names.len() + ADDED_HEADERS.len())
+        .handshake::<_, String>(TokioIo::new(client))
+        .await?;
+    tokio::spawn(connection);
+
+    let response = send_request.send_request(http::Request::new(String::new()));
+    // `hyper` only reads the response after the request has been written.
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        request.push(server.read_u8().await?);
+    }
+    server.write_all(&head).await?;
+
+    Ok(response.await?.into_parts().0.extensions)
+}
+
+/// Streams the response body of a `hyper` response.
+struct ResponseBody(Incoming);
+
+impl futures_core::Stream for ResponseBody {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            return match ready!(Pin::new(&mut self.0).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(data) => Poll::Ready(Some(Ok(data))),
+                    // Trailers aren't exposed on the responses.
+                    Err(_) => continue,
+                },
+                Some(Err(err)) => Poll::Ready(Some(Err(io::Error::other(err)))),
+                None => Poll::Ready(None),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dns::{DnsConfig, HappyEyeballs},
+        tls::TlsConfig,
+    };
+    use tokio::net::TcpListener;
+
+    fn client() -> Http1Client {
+        Http1Client::new(
+            TlsConfig::builder().build(),
+            "",
+            SocketBinding::default(),
+            Arc::new(Resolver::new(&DnsConfig::default(), HappyEyeballs::default()).unwrap()),
+            Route::default(),
+        )
+        .unwrap()
+    }
+
+    /// Accepts one connection and returns the request head sent on it, answering with an empty response.
+    async fn capture_request(listener: TcpListener) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        while !request.ends_with(b"\r\n\r\n") {
+            request.push(stream.read_u8().await.unwrap());
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+
+        String::from_utf8(request).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_header_names_keep_their_casing() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/path?query",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let server = tokio::spawn(capture_request(listener));
+
+        let headers = [
+            ("Host", url.authority()),
+            ("sec-ch-ua", "\"Chromium\";v=\"151\""),
+            ("User-Agent", "impit"),
+            ("X-CUSTOM", "1"),
+            ("x-custom", "2"),
+            ("accept", "*/*"),
+        ];
+        let header_names: Vec<String> = headers.iter().map(|(name, _)| name.to_string()).collect();
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.append(
+                name.parse::<http::HeaderName>().unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+
+        let response = client()
+            .send(
+                Method::GET,
+                &url,
+                header_map,
+                &header_names,
+                &mut ImpitBody::Empty,
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        assert_eq!(
+            server.await.unwrap(),
+            format!(
+                "GET /path?query HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 sec-ch-ua: \"Chromium\";v=\"151\"\r\n\
+                 User-Agent: impit\r\n\
+                 X-CUSTOM: 1\r\n\
+                 x-custom: 2\r\n\
+                 accept: */*\r\n\r\n",
+                url.authority()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_system_proxies_skip_no_proxy_hosts() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let server = tokio::spawn(capture_request(listener));
+
+        let no_proxy_client = Http1Client {
+            proxies: Proxies::System(Box::new(
+                Matcher::builder()
+                    .http("http://127.0.0.1:9")
+                    .no("127.0.0.1")
+                    .build(),
+            )),
+            ..client()
+        };
+        assert!(no_proxy_client.handles(&url));
+        let response = no_proxy_client
+            .send(
+                Method::GET,
+                &url,
+                HeaderMap::new(),
+                &[],
+                &mut ImpitBody::Empty,
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(server.await.unwrap().starts_with("GET / HTTP/1.1\r\n"));
+
+        // `reqwest` sends the requests through the proxies this client can't connect through.
+        let https_proxy_client = Http1Client {
+            proxies: Proxies::System(Box::new(
+                Matcher::builder().all("https://proxy.example.com").build(),
+            )),
+            ..client()
+        };
+        assert!(!https_proxy_client.handles(&Url::parse("http://example.com/").unwrap()));
+    }
+
+    #[test]
+    fn test_https_origins_are_tried_until_they_pick_another_protocol() {
+        let client = client();
+        let url = Url::parse("https://example.com/").unwrap();
+        assert!(client.handles(&url));
+
+        client.record_version(&url, Version::HTTP_2);
+        assert!(!client.handles(&url));
+        assert!(client.handles(&Url::parse("https://example.org/").unwrap()));
+
+        client.record_version(&url, Version::HTTP_11);
+        assert!(client.handles(&url));
+    }
+
+    #[tokio::test]
+    async fn test_pool_keeps_a_limited_number_of_connections() {
+        let client = client();
+        let mut servers = Vec::new();
+        for _ in 0..MAX_CONNECTIONS_PER_ORIGIN + 2 {
+            let (io, server) = tokio::io::duplex(1024);
+            let (send_request, connection) = http1::handshake(TokioIo::new(io)).await.unwrap();
+            tokio::spawn(connection);
+            servers.push(server);
+            client.release("http://example.com:80", send_request);
+        }
+        assert_eq!(
+            client.connections.lock().unwrap()["http://example.com:80"].len(),
+            MAX_CONNECTIONS_PER_ORIGIN
+        );
+
+        // The closed connections are dropped from the pool.
+        drop(servers);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(client.pooled("http://example.com:80").is_none());
+        assert!(client.connections.lock().unwrap()["http://example.com:80"].is_empty());
+    }
+
+    #[test]
+    fn test_has_header_case() {
+        assert!(!has_header_case(&[
+            "user-agent".to_string(),
+            "accept".to_string()
+        ]));
+        assert!(has_header_case(&[
+            "user-agent".to_string(),
+            "Accept".to_string()
+        ]));
+    }
+}
@@ -1,87 +1,590 @@
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use hickory_proto::rr::rdata::svcb::SvcParamValue;
-use hickory_proto::rr::{Name, RData, RecordType};
-use hickory_resolver::TokioResolver;
 use log::debug;
+use percent_encoding::percent_decode_str;
+
+use crate::{dns::Resolver, errors::ImpitError};
+
+/// How long the alternative services are cached if the `Alt-Svc` header doesn't specify the `ma` parameter.
+const DEFAULT_ALT_SVC_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long the HTTP/3 support discovered from the HTTPS DNS records (or the lack of it) is cached.
+const DNS_DISCOVERY_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long HTTP/3 isn't used for an origin after its first QUIC failure. Every further failure doubles the period.
+const BROKEN_QUIC_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// The longest period HTTP/3 isn't used for an origin with repeated QUIC failures.
+const MAX_BROKEN_QUIC_PERIOD: Duration = Duration::from_secs(48 * 60 * 60);
+
+/// The first line of the exported [`H3Cache`], identifying the format version.
+const EXPORT_HEADER: &str = "impit-h3-cache 1";
+
+/// The host and port of an HTTP/3 endpoint serving an origin.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Authority {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+}
+
+/// One alternative service from the `Alt-Svc` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AltService {
+    /// The ALPN protocol ID, e.g. `h3`.
+    pub(crate) protocol: String,
+    /// The alternative host, `None` if the service is on the same host as the origin.
+    pub(crate) host: Option<String>,
+    pub(crate) port: u16,
+    pub(crate) max_age: Duration,
+}
+
+/// The parsed value of the `Alt-Svc` header, see [RFC 7838](https://www.rfc-editor.org/rfc/rfc7838).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AltSvc {
+    /// All the alternative services of the origin are invalidated.
+    Clear,
+    /// The alternative services replacing the previously advertised ones.
+    Services(Vec<AltService>),
+}
+
+impl AltSvc {
+    /// Parses the `Alt-Svc` header value, skipping the malformed entries.
+    pub(crate) fn parse(value: &str) -> Self {
+        if value.trim() == "clear" {
+            return AltSvc::Clear;
+        }
+
+        AltSvc::Services(
+            split_unquoted(value, ',')
+                .into_iter()
+                .filter_map(AltService::parse)
+                .collect(),
+        )
+    }
+}
+
+/// Splits the header value on the `separator`, except inside the quoted strings.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, ch) in value.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if ch == separator && !quoted => {
+                parts.push(&value[start..index]);
+                start = index + ch.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+
+    parts
+}
+
+/// Returns the value of a `quoted-string` without the quotes and the escaping backslashes, or the token as it is.
+fn unquote(value: &str) -> Option<String> {
+    let value = value.trim();
+    let Some(quoted) = value.strip_prefix('"') else {
+        return Some(value.to_string());
+    };
+    let quoted = quoted.strip_suffix('"')?;
+
+    let mut unquoted = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unquoted.push(chars.next()?),
+            ch => unquoted.push(ch),
+        }
+    }
+    Some(unquoted)
+}
+
+impl AltService {
+    fn parse(entry: &str) -> Option<Self> {
+        let mut parts = split_unquoted(entry, ';').into_iter();
+        let (protocol, authority) = parts.next()?.trim().split_once('=')?;
+
+        let protocol = percent_decode_str(protocol.trim()).decode_utf8().ok()?;
+        // The alternative authority is always a quoted string.
+        if !authority.trim().starts_with('"') {
+            return None;
+        }
+        let authority = unquote(authority)?;
+        let (host, port) = authority.rsplit_once(':')?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+
+        let mut service = AltService {
+            protocol: protocol.to_string(),
+            host: (!host.is_empty()).then(|| host.to_ascii_lowercase()),
+            port: port.parse().ok()?,
+            max_age: DEFAULT_ALT_SVC_MAX_AGE,
+        };
+
+        // Other parameters (e.g. `persist`, which only matters on network changes) are ignored.
+        for param in parts {
+            let Some((name, value)) = param.trim().split_once('=') else {
+                continue;
+            };
+
+            if name.trim() == "ma" {
+                service.max_age = Duration::from_secs(unquote(value)?.parse().ok()?);
+            }
+        }
+
+        Some(service)
+    }
+}
+
+/// A cached HTTP/3 endpoint of an origin, or the knowledge that the origin doesn't support HTTP/3.
+#[derive(Debug, Clone)]
+struct AltSvcEntry {
+    endpoint: Option<Authority>,
+    expires: Instant,
+}
+
+/// The HTTP/3 support of the origins, discovered from the `Alt-Svc` headers and the HTTPS DNS records.
+///
+/// By default, every [`Impit`](crate::impit::Impit) instance discovers the HTTP/3 support on its own.
+/// A cache passed to [`ImpitBuilder::with_h3_cache`](crate::impit::ImpitBuilder::with_h3_cache) can be shared
+/// by multiple instances, as the clones of [`H3Cache`] share the same entries.
+///
+/// The cache can also be exported and imported back (e.g. by the next run of a short-lived worker), just like
+/// the browsers persist the alternative services across restarts. The entries keep their expiration times.
+///
+/// ### Example
+/// ```rust,no_run
+/// use impit::{http3::H3Cache, impit::Impit};
+/// use reqwest::cookie::Jar;
+///
+/// let cache = H3Cache::load("h3-cache.txt").unwrap_or_default();
+///
+/// let impit = Impit::<Jar>::builder()
+///     .with_http3()
+///     .with_h3_cache(cache.clone())
+///     .build()
+///     .unwrap();
+///
+/// // ... make the requests ...
+///
+/// cache.save("h3-cache.txt").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct H3Cache {
+    /// The entries by origin (`host:port`).
+    entries: Arc<RwLock<HashMap<String, AltSvcEntry>>>,
+}
+
+impl H3Cache {
+    /// Creates a new empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Exports the unexpired entries of the cache, along with their expiration times.
+    pub fn export(&self) -> Vec<u8> {
+        let now = Instant::now();
+        let mut exported = format!("{EXPORT_HEADER}\n");
+
+        for (origin, entry) in self.entries.read().unwrap().iter() {
+            let Some(remaining) = entry.expires.checked_duration_since(now) else {
+                continue;
+            };
+            let expires = (SystemTime::now() + remaining)
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let endpoint = entry.endpoint.as_ref().map_or("-".to_string(), |endpoint| {
+                format!("{}:{}", endpoint.host, endpoint.port)
+            });
+
+            exported.push_str(&format!("{origin} {endpoint} {expires}\n"));
+        }
+
+        exported.into_bytes()
+    }
+
+    /// Imports the entries exported with [`H3Cache::export`], replacing the cached entries of the same origins.
+    ///
+    /// The entries that have expired since the export are skipped.
+    pub fn import(&self, data: &[u8]) -> Result<(), ImpitError> {
+        let invalid = |reason: &str| ImpitError::H3CacheError(reason.to_string());
+
+        let data = std::str::from_utf8(data).map_err(|_| invalid("the data is not valid UTF-8"))?;
+        let mut lines = data.lines();
+        if lines.next() != Some(EXPORT_HEADER) {
+            return Err(invalid("unknown format"));
+        }
+
+        let mut imported = vec![];
+        for line in lines.filter(|line| !line.is_empty()) {
+            let malformed = || invalid(&format!("malformed entry `{line}`"));
+
+            let [origin, endpoint, expires] = line.split(' ').collect::<Vec<_>>()[..] else {
+                return Err(malformed());
+            };
+            let endpoint = match endpoint {
+                "-" => None,
+                endpoint => {
+                    let (host, port) = endpoint.rsplit_once(':').ok_or_else(malformed)?;
+                    Some(Authority {
+                        host: host.to_string(),
+                        port: port.parse().map_err(|_| malformed())?,
+                    })
+                }
+            };
+            let expires: u64 = expires.parse().map_err(|_| malformed())?;
+
+            let remaining = (UNIX_EPOCH + Duration::from_secs(expires))
+                .duration_since(SystemTime::now())
+                .ok();
+            if let Some(expires) =
+                remaining.and_then(|remaining| Instant::now().checked_add(remaining))
+            {
+                imported.push((origin.to_string(), AltSvcEntry { endpoint, expires }));
+            }
+        }
+
+        self.entries.write().unwrap().extend(imported);
+        Ok(())
+    }
+
+    /// Loads a cache saved with [`H3Cache::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ImpitError> {
+        let data = std::fs::read(path).map_err(|err| ImpitError::H3CacheError(err.to_string()))?;
+
+        let cache = Self::new();
+        cache.import(&data)?;
+        Ok(cache)
+    }
+
+    /// Saves the exported cache to a file, see [`H3Cache::export`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ImpitError> {
+        std::fs::write(path, self.export()).map_err(|err| ImpitError::H3CacheError(err.to_string()))
+    }
+
+    fn get(&self, origin: &str) -> Option<AltSvcEntry> {
+        self.entries
+            .read()
+            .unwrap()
+            .get(origin)
+            .filter(|entry| entry.expires > Instant::now())
+            .cloned()
+    }
+
+    fn insert(&self, origin: String, entry: AltSvcEntry) {
+        self.entries.write().unwrap().insert(origin, entry);
+    }
+}
+
+/// The QUIC failures of an origin, like the "broken alternative services" in Chromium.
+#[derive(Debug, Clone)]
+struct BrokenQuic {
+    failures: u32,
+    until: Instant,
+}
+
+/// Returns how long HTTP/3 isn't used for an origin after its `failures`-th consecutive QUIC failure.
+fn broken_period(failures: u32) -> Duration {
+    BROKEN_QUIC_PERIOD
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(MAX_BROKEN_QUIC_PERIOD)
+}
 
 /// A struct encapsulating the components required to make HTTP/3 requests.
-pub struct H3Engine {
+pub(crate) struct H3Engine {
     /// The DNS resolver used to query HTTPS records for h3 discovery.
-    resolver: Option<TokioResolver>,
-    /// A map of hosts that support HTTP/3.
+    resolver: Option<Arc<Resolver>>,
+    /// The HTTP/3 endpoints of the origins, possibly shared with other engines.
     ///
-    /// This is populated by the DNS queries and manual calls to `set_h3_support` (based on the `Alt-Svc` header).
-    /// Implicitly used as a cache for the DNS queries.
-    h3_alt_svc: Arc<RwLock<HashMap<String, bool>>>,
+    /// This is populated by the DNS queries and the `Alt-Svc` headers of the responses.
+    /// The entries expire according to the `ma` parameter of the `Alt-Svc` header.
+    alt_svc: H3Cache,
+    /// The origins (`host:port`) QUIC has recently failed for, these are reached over TCP until the entry expires.
+    broken: Arc<RwLock<HashMap<String, BrokenQuic>>>,
 }
 
 impl H3Engine {
-    pub async fn init() -> Self {
-        let resolver = match TokioResolver::builder_tokio().and_then(|builder| builder.build()) {
-            Ok(resolver) => Some(resolver),
-            Err(err) => {
-                debug!(
-                    "Failed to create DNS resolver for HTTP3 resolution: {}",
-                    err
-                );
-                None
-            }
-        };
-
+    /// Creates a new engine.
+    ///
+    /// Without a `resolver`, the engine never queries the HTTPS DNS records and relies
+    /// on the `Alt-Svc` headers only. This is used with proxies that resolve hostnames remotely,
+    /// where local DNS queries would reveal the requested hosts.
+    pub(crate) async fn init(resolver: Option<Arc<Resolver>>, alt_svc: H3Cache) -> Self {
         H3Engine {
             resolver,
-            h3_alt_svc: Arc::new(RwLock::new(HashMap::new())),
+            alt_svc,
+            broken: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    pub async fn host_supports_h3(&self, host: &String) -> bool {
-        {
-            let cache = self.h3_alt_svc.read().await;
-            if let Some(&supports_h3) = cache.get(host) {
-                return supports_h3;
+    /// Returns the HTTP/3 endpoint of the `host:port` origin, or `None` if the origin doesn't support HTTP/3
+    /// or QUIC is marked as broken for it.
+    pub(crate) async fn h3_endpoint(&self, host: &str, port: u16) -> Option<Authority> {
+        let origin = format!("{host}:{port}");
+
+        if let Some(broken) = self.broken.read().unwrap().get(&origin) {
+            if broken.until > Instant::now() {
+                return None;
             }
         }
 
-        let domain_name = match Name::from_utf8(host) {
-            Ok(name) => name,
-            Err(_) => return false,
-        };
+        if let Some(entry) = self.alt_svc.get(&origin) {
+            return entry.endpoint;
+        }
+
+        let resolver = self.resolver.as_ref()?;
+        let dns_h3_support = resolver
+            .lookup_https_alpn(host)
+            .await
+            .iter()
+            .any(|alpn| alpn == "h3");
 
-        if let Some(resolver) = self.resolver.as_ref() {
-            let response = resolver.lookup(domain_name, RecordType::HTTPS).await;
+        let endpoint = dns_h3_support.then(|| Authority {
+            host: host.to_string(),
+            port,
+        });
 
-            let dns_h3_support = response.is_ok_and(|response| {
-                response.answers().iter().any(|answer| {
-                    if let RData::HTTPS(data) = &answer.data {
-                        return data.0.svc_params.iter().any(|param| {
-                            if let SvcParamValue::Alpn(alpn_protocols) = &param.1 {
-                                return alpn_protocols.0.iter().any(|alpn| alpn == "h3");
-                            }
+        self.alt_svc.insert(
+            origin,
+            AltSvcEntry {
+                endpoint: endpoint.clone(),
+                expires: Instant::now() + DNS_DISCOVERY_TTL,
+            },
+        );
 
-                            false
-                        });
+        endpoint
+    }
+
+    /// Updates the cached alternative services of the `host:port` origin from its `Alt-Svc` header.
+    pub(crate) async fn update_alt_svc(&self, host: &str, port: u16, header: &str) {
+        let origin = format!("{host}:{port}");
+
+        let entry = match AltSvc::parse(header) {
+            AltSvc::Services(services) => {
+                match services
+                    .into_iter()
+                    .find(|service| service.protocol == "h3")
+                {
+                    Some(service) => {
+                        debug!(
+                            "{origin} advertises HTTP/3 on {}:{} for {:?}",
+                            service.host.as_deref().unwrap_or(host),
+                            service.port,
+                            service.max_age,
+                        );
+
+                        AltSvcEntry {
+                            endpoint: Some(Authority {
+                                host: service.host.unwrap_or_else(|| host.to_string()),
+                                port: service.port,
+                            }),
+                            expires: Instant::now() + service.max_age,
+                        }
                     }
-                    false
-                })
-            });
+                    // The new header replaces the previously advertised services.
+                    None => AltSvcEntry {
+                        endpoint: None,
+                        expires: Instant::now() + DNS_DISCOVERY_TTL,
+                    },
+                }
+            }
+            AltSvc::Clear => {
+                debug!("{origin} cleared its alternative services");
+                AltSvcEntry {
+                    endpoint: None,
+                    expires: Instant::now() + DNS_DISCOVERY_TTL,
+                }
+            }
+        };
+
+        self.alt_svc.insert(origin, entry);
+    }
 
-            self.set_h3_support(host, dns_h3_support).await;
+    /// Records a QUIC failure for the `host:port` origin, so that its requests are sent over TCP for a while.
+    pub(crate) async fn mark_broken(&self, host: &str, port: u16) {
+        let origin = format!("{host}:{port}");
+        let mut broken = self.broken.write().unwrap();
 
-            return dns_h3_support;
-        }
+        let failures = broken.get(&origin).map_or(0, |entry| entry.failures) + 1;
+        let period = broken_period(failures);
+        debug!("QUIC is broken for {origin}, using TCP for the next {period:?}");
 
-        false
+        broken.insert(
+            origin,
+            BrokenQuic {
+                failures,
+                until: Instant::now() + period,
+            },
+        );
     }
 
-    pub async fn set_h3_support(&self, host: &String, supports_h3: bool) {
-        let mut cache = self.h3_alt_svc.write().await;
-        if cache.contains_key(host) {
-            return;
+    /// Records a successful HTTP/3 request to the `host:port` origin, resetting its QUIC failures.
+    pub(crate) async fn mark_working(&self, host: &str, port: u16) {
+        let origin = format!("{host}:{port}");
+
+        if self.broken.read().unwrap().contains_key(&origin) {
+            self.broken.write().unwrap().remove(&origin);
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alt_svc_parsing() {
+        assert_eq!(AltSvc::parse("clear"), AltSvc::Clear);
+
+        let AltSvc::Services(services) = AltSvc::parse(
+            r#"h3=":443"; ma=86400, h3-29="alt.example.com:8443"; persist=1, h2%3D="[2001:db8::1]:443"; ma=60, broken"#,
+        ) else {
+            panic!("expected a list of services");
+        };
+
+        assert_eq!(
+            services,
+            vec![
+                AltService {
+                    protocol: "h3".to_string(),
+                    host: None,
+                    port: 443,
+                    max_age: Duration::from_secs(86400),
+                },
+                AltService {
+                    protocol: "h3-29".to_string(),
+                    host: Some("alt.example.com".to_string()),
+                    port: 8443,
+                    max_age: DEFAULT_ALT_SVC_MAX_AGE,
+                },
+                AltService {
+                    protocol: "h2=".to_string(),
+                    host: Some("2001:db8::1".to_string()),
+                    port: 443,
+                    max_age: Duration::from_secs(60),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alt_svc_quoted_separators() {
+        let AltSvc::Services(services) = AltSvc::parse(
+            r#"h3=":443"; ext="a,b;c", h3="alt.example.com:8443"; ma="120"; note="\"quoted\", fine""#,
+        ) else {
+            panic!("expected a list of services");
+        };
+
+        assert_eq!(
+            services,
+            vec![
+                AltService {
+                    protocol: "h3".to_string(),
+                    host: None,
+                    port: 443,
+                    max_age: DEFAULT_ALT_SVC_MAX_AGE,
+                },
+                AltService {
+                    protocol: "h3".to_string(),
+                    host: Some("alt.example.com".to_string()),
+                    port: 8443,
+                    max_age: Duration::from_secs(120),
+                },
+            ]
+        );
+
+        assert_eq!(
+            split_unquoted(r#"a="x,y", b"#, ','),
+            vec![r#"a="x,y""#, " b"]
+        );
+        assert_eq!(unquote(r#""a\"b""#).as_deref(), Some(r#"a"b"#));
+        assert_eq!(unquote(r#""unterminated"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_alt_svc_updates_replace_entries() {
+        let engine = H3Engine::init(None, H3Cache::new()).await;
+        assert_eq!(engine.h3_endpoint("example.com", 443).await, None);
+
+        engine
+            .update_alt_svc("example.com", 443, r#"h3="alt.example.com:8443""#)
+            .await;
+        assert_eq!(
+            engine.h3_endpoint("example.com", 443).await,
+            Some(Authority {
+                host: "alt.example.com".to_string(),
+                port: 8443
+            })
+        );
+
+        engine.update_alt_svc("example.com", 443, "clear").await;
+        assert_eq!(engine.h3_endpoint("example.com", 443).await, None);
+
+        engine
+            .update_alt_svc("example.com", 443, r#"h3=":443"; ma=0"#)
+            .await;
+        assert_eq!(engine.h3_endpoint("example.com", 443).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_broken_quic_backoff() {
+        assert_eq!(broken_period(1), BROKEN_QUIC_PERIOD);
+        assert_eq!(broken_period(3), BROKEN_QUIC_PERIOD * 4);
+        assert_eq!(broken_period(40), MAX_BROKEN_QUIC_PERIOD);
+
+        let engine = H3Engine::init(None, H3Cache::new()).await;
+        engine
+            .update_alt_svc("example.com", 443, r#"h3=":443""#)
+            .await;
+
+        engine.mark_broken("example.com", 443).await;
+        engine.mark_broken("example.com", 443).await;
+        assert_eq!(engine.h3_endpoint("example.com", 443).await, None);
+        assert_eq!(engine.broken.read().unwrap()["example.com:443"].failures, 2);
+
+        engine.mark_working("example.com", 443).await;
+        assert!(engine.h3_endpoint("example.com", 443).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_h3_cache_export_import() {
+        let cache = H3Cache::new();
+        let engine = H3Engine::init(None, cache.clone()).await;
+        engine
+            .update_alt_svc("example.com", 443, r#"h3="[2001:db8::1]:8443""#)
+            .await;
+        engine.update_alt_svc("example.org", 443, "clear").await;
+        engine
+            .update_alt_svc("example.net", 443, r#"h3=":443"; ma=0"#)
+            .await;
+
+        let imported = H3Cache::new();
+        imported.import(&cache.export()).unwrap();
+        assert_eq!(imported.entries.read().unwrap().len(), 2);
+
+        let engine = H3Engine::init(None, imported.clone()).await;
+        assert_eq!(
+            engine.h3_endpoint("example.com", 443).await,
+            Some(Authority {
+                host: "2001:db8::1".to_string(),
+                port: 8443
+            })
+        );
+        assert_eq!(engine.h3_endpoint("example.org", 443).await, None);
 
-        cache.insert(host.to_owned(), supports_h3);
+        assert!(imported.import(b"example.com:443 - 0").is_err());
+        assert!(imported
+            .import(format!("{EXPORT_HEADER}\nexample.com:443 -").as_bytes())
+            .is_err());
     }
 }
@@ -1,6 +1,10 @@
-use crate::{errors::ImpitError, fingerprint::BrowserFingerprint};
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use std::{collections::HashSet, str::FromStr};
+use crate::{
+    errors::ImpitError,
+    fingerprint::{BrowserFingerprint, HeaderPosition, RequestDestination},
+    request::HeaderOp,
+};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, COOKIE};
+use std::str::FromStr;
 
 pub struct HttpHeaders {
     context: HttpHeadersBuilder,
@@ -18,79 +22,198 @@ impl HttpHeaders {
     }
 }
 
+impl From<Vec<(String, String)>> for HttpHeaders {
+    fn from(val: Vec<(String, String)>) -> Self {
+        let mut builder = HttpHeaders::get_builder();
+        #[allow(deprecated)]
+        builder.with_custom_headers(Some(val));
+        builder.build()
+    }
+}
+
+impl From<HttpHeaders> for Result<HeaderMap, ImpitError> {
+    fn from(val: HttpHeaders) -> Self {
+        let headers: Vec<(String, String)> = val.iter().collect();
+        let repeatable = repeated_names(&headers);
+        to_header_map(headers, &repeatable)
+    }
+}
+
 impl HttpHeaders {
     pub fn iter(&self) -> impl Iterator<Item = (String, String)> + '_ {
         // Use fingerprint headers if available, otherwise fall back to browser enum
         let impersonated_headers: Vec<(String, String)> =
             if let Some(ref fp) = self.context.fingerprint {
-                fp.headers.to_vec()
+                fp.headers_for(self.context.destination)
             } else {
                 vec![]
             };
 
-        let custom_headers = self
-            .context
-            .custom_headers
+        // The overridden headers keep their slot in the browser's order, the new ones are collected in `extras`.
+        let mut headers = impersonated_headers;
+        let mut extras: Vec<(String, String)> = vec![];
+        for op in &self.context.header_ops {
+            let name = op.name();
+            let matches = |(header, _): &(String, String)| header.eq_ignore_ascii_case(name);
+            // A header is either the browser's one or an extra one, never both.
+            let target = if headers.iter().any(matches) {
+                &mut headers
+            } else {
+                &mut extras
+            };
+
+            match op {
+                HeaderOp::Set(_, value) => set_header(target, name, value),
+                HeaderOp::SetEmpty(_) => set_header(target, name, ""),
+                HeaderOp::Append(_, value) => {
+                    let index = target
+                        .iter()
+                        .rposition(matches)
+                        .map_or(target.len(), |i| i + 1);
+                    target.insert(index, (name.to_string(), value.clone()));
+                }
+                HeaderOp::Remove(_) => target.retain(|header| !matches(header)),
+            }
+        }
+
+        let index = position_index(&headers, &self.context.header_position);
+        headers.splice(index..index, extras);
+
+        headers.into_iter()
+    }
+}
+
+/// Replaces the first value of the header in place and drops the other ones, or adds the header at the end.
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    let Some(index) = headers
+        .iter()
+        .position(|(header, _)| header.eq_ignore_ascii_case(name))
+    else {
+        headers.push((name.to_string(), value.to_string()));
+        return;
+    };
+
+    headers[index] = (name.to_string(), value.to_string());
+    let mut current = 0;
+    headers.retain(|(header, _)| {
+        current += 1;
+        current - 1 == index || !header.eq_ignore_ascii_case(name)
+    });
+}
+
+/// Returns the index in `headers` a new header goes to, falling back to the end if the anchor header is missing.
+fn position_index(headers: &[(String, String)], position: &HeaderPosition) -> usize {
+    let find = |target: &str| {
+        headers
             .iter()
-            .map(|(k, v)| (k.clone(), v.clone()));
+            .position(|(name, _)| name.eq_ignore_ascii_case(target))
+    };
+    match position {
+        HeaderPosition::Start => 0,
+        HeaderPosition::End => headers.len(),
+        HeaderPosition::Before(target) => find(target).unwrap_or(headers.len()),
+        HeaderPosition::After(target) => find(target).map_or(headers.len(), |i| i + 1),
+    }
+}
 
-        let mut used_header_names: HashSet<String> = HashSet::new();
+/// Inserts a header at the given position, keeping the order of the other headers.
+pub(crate) fn insert_header(
+    headers: &mut Vec<(String, String)>,
+    name: &str,
+    value: String,
+    position: &HeaderPosition,
+) {
+    let index = position_index(headers, position);
+    headers.insert(index, (name.to_string(), value));
+}
 
-        custom_headers
-            .chain(impersonated_headers)
-            .filter_map(move |(name, value)| {
-                if used_header_names.contains(&name.to_lowercase()) {
-                    None
-                } else {
-                    used_header_names.insert(name.to_lowercase());
-                    if value.is_empty() {
-                        None
-                    } else {
-                        Some((name, value))
-                    }
-                }
-            })
+/// Adds the `Cookie` header from the cookie store at the given position, keeping the order of the other headers.
+///
+/// A `Cookie` header set by the user wins, in which case the headers are returned as they are.
+pub fn with_cookie_header(
+    headers: HeaderMap,
+    cookies: HeaderValue,
+    position: &HeaderPosition,
+) -> HeaderMap {
+    if headers.contains_key(COOKIE) {
+        return headers;
     }
+
+    let names: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, _)| (name.to_string(), String::new()))
+        .collect();
+    let index = position_index(&names, position);
+
+    let mut entries: Vec<(HeaderName, HeaderValue)> = headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    entries.insert(index, (COOKIE, cookies));
+
+    let mut result = HeaderMap::with_capacity(entries.len());
+    for (name, value) in entries {
+        result.append(name, value);
+    }
+    result
 }
 
-impl From<Vec<(String, String)>> for HttpHeaders {
-    fn from(val: Vec<(String, String)>) -> Self {
-        let mut builder = HttpHeaders::get_builder();
-        builder.with_custom_headers(Some(val));
-        builder.build()
+/// Returns the (lowercase) names of the headers listed more than once, i.e. added with [`HeaderOp::Append`].
+pub(crate) fn repeated_names(headers: &[(String, String)]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for (index, (name, _)) in headers.iter().enumerate() {
+        let name = name.to_ascii_lowercase();
+        let repeated = headers[..index]
+            .iter()
+            .any(|(previous, _)| previous.eq_ignore_ascii_case(&name));
+        if repeated && !names.contains(&name) {
+            names.push(name);
+        }
     }
+    names
 }
 
-impl From<HttpHeaders> for Result<HeaderMap, ImpitError> {
-    fn from(val: HttpHeaders) -> Self {
-        let mut headers = HeaderMap::new();
+/// Converts the final list of headers to a [`HeaderMap`], keeping the empty headers.
+///
+/// Only the headers in `repeatable` (see [`repeated_names`]) are sent several times. A later value of any other
+/// header replaces the earlier one in place, e.g. when a middleware pushes a header that's set already.
+pub fn to_header_map(
+    headers: Vec<(String, String)>,
+    repeatable: &[String],
+) -> Result<HeaderMap, ImpitError> {
+    let mut header_map = HeaderMap::with_capacity(headers.len());
 
-        for (name, value) in val.iter() {
-            let header_name = HeaderName::from_str(&name);
-            let header_value = HeaderValue::from_str(&value);
+    for (name, value) in headers {
+        let header_name = HeaderName::from_str(&name);
+        let header_value = HeaderValue::from_str(&value);
 
-            match (header_name, header_value) {
-                (Err(_), _) => {
-                    return Err(ImpitError::InvalidHeaderName(name));
-                }
-                (_, Err(_)) => {
-                    return Err(ImpitError::InvalidHeaderValue(value));
-                }
-                (Ok(header_name), Ok(header_value)) => {
-                    headers.append(header_name, header_value);
+        match (header_name, header_value) {
+            (Err(_), _) => {
+                return Err(ImpitError::InvalidHeaderName(name));
+            }
+            (_, Err(_)) => {
+                return Err(ImpitError::InvalidHeaderValue(value));
+            }
+            (Ok(header_name), Ok(header_value)) => {
+                if repeatable.iter().any(|name| name == header_name.as_str()) {
+                    header_map.append(header_name, header_value);
+                } else {
+                    header_map.insert(header_name, header_value);
                 }
             }
         }
-        Ok(headers)
     }
+    Ok(header_map)
 }
 
 #[derive(Default, Clone)]
 pub struct HttpHeadersBuilder {
     host: String,
     fingerprint: Option<BrowserFingerprint>,
+    destination: RequestDestination,
+    header_position: HeaderPosition,
     https: bool,
-    custom_headers: Vec<(String, String)>,
+    header_ops: Vec<HeaderOp>,
 }
 
 impl HttpHeadersBuilder {
@@ -105,31 +228,206 @@ impl HttpHeadersBuilder {
         self
     }
 
+    pub fn with_destination(&mut self, destination: RequestDestination) -> &mut Self {
+        self.destination = destination;
+        self
+    }
+
+    pub fn with_header_position(&mut self, header_position: &HeaderPosition) -> &mut Self {
+        self.header_position = header_position.clone();
+        self
+    }
+
     pub fn with_https(&mut self, https: bool) -> &mut Self {
         self.https = https;
         self
     }
 
+    /// Adds custom headers, overriding the ones added before. An empty value removes the header.
+    #[deprecated(note = "use `with_header_ops`, where an empty value sends the header empty")]
     pub fn with_custom_headers(
         &mut self,
         custom_headers: Option<Vec<(String, String)>>,
     ) -> &mut Self {
-        match custom_headers {
-            Some(headers) => {
-                // Later call to with_custom_headers should override existing headers.
-                // We need to prepend the new headers (higher prio) to the existing ones (lower prio).
-                self.custom_headers = headers
-                    .iter()
-                    .chain(self.custom_headers.iter())
-                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
-                    .collect();
-                self
-            }
-            None => self,
+        // The first value of a header used to win, while the later header operations override the earlier ones.
+        let header_ops = custom_headers.map(|headers| {
+            headers
+                .into_iter()
+                .rev()
+                .map(|(name, value)| {
+                    if value.is_empty() {
+                        HeaderOp::Remove(name)
+                    } else {
+                        HeaderOp::Set(name, value)
+                    }
+                })
+                .collect()
+        });
+        self.with_header_ops(header_ops)
+    }
+
+    /// Adds header operations, applied after the ones added before.
+    pub fn with_header_ops(&mut self, header_ops: Option<Vec<HeaderOp>>) -> &mut Self {
+        if let Some(header_ops) = header_ops {
+            self.header_ops.extend(header_ops);
         }
+        self
     }
 
     pub fn build(&self) -> HttpHeaders {
         HttpHeaders::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::database::chrome_151;
+
+    fn names(builder: &HttpHeadersBuilder) -> Vec<String> {
+        builder.build().iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn overridden_headers_keep_the_fingerprint_order() {
+        let mut builder = HttpHeaders::get_builder();
+        builder
+            .with_fingerprint(&Some(chrome_151::fingerprint()))
+            .with_header_ops(Some(vec![
+                ("accept-language", "de").into(),
+                ("X-Extra", "1").into(),
+                HeaderOp::Remove("sec-fetch-user".to_string()),
+            ]));
+
+        let expected: Vec<String> = chrome_151::fingerprint()
+            .headers
+            .into_iter()
+            .filter(|(name, _)| name != "Sec-Fetch-User")
+            .map(|(name, _)| {
+                if name == "Accept-Language" {
+                    "accept-language".to_string()
+                } else {
+                    name
+                }
+            })
+            .chain(["X-Extra".to_string()])
+            .collect();
+        assert_eq!(names(&builder), expected);
+    }
+
+    #[test]
+    fn extra_headers_go_to_the_configured_position() {
+        let mut builder = HttpHeaders::get_builder();
+        builder
+            .with_fingerprint(&Some(chrome_151::fingerprint()))
+            .with_header_ops(Some(vec![("X-Extra", "1").into()]));
+
+        builder.with_header_position(&HeaderPosition::Start);
+        assert_eq!(names(&builder)[0], "X-Extra");
+
+        builder.with_header_position(&"after:User-Agent".parse().unwrap());
+        let headers = names(&builder);
+        let user_agent = headers.iter().position(|name| name == "User-Agent");
+        assert_eq!(headers[user_agent.unwrap() + 1], "X-Extra");
+
+        builder.with_header_position(&HeaderPosition::Before("x-missing".to_string()));
+        assert_eq!(names(&builder).last().unwrap(), "X-Extra");
+    }
+
+    #[test]
+    fn cookies_go_to_the_fingerprint_position() {
+        let mut builder = HttpHeaders::get_builder();
+        builder.with_fingerprint(&Some(chrome_151::fingerprint()));
+        let headers = to_header_map(builder.build().iter().collect(), &[]);
+        let cookies = HeaderValue::from_static("a=1");
+        let position = chrome_151::fingerprint().cookie_position;
+
+        let headers = with_cookie_header(headers.unwrap(), cookies.clone(), &position);
+        let names: Vec<&str> = headers.keys().map(|name| name.as_str()).collect();
+        let language = names.iter().position(|name| *name == "accept-language");
+        assert_eq!(names[language.unwrap() + 1], "cookie");
+
+        let mut custom = HeaderMap::new();
+        custom.insert(COOKIE, HeaderValue::from_static("b=2"));
+        let custom = with_cookie_header(custom, cookies, &position);
+        assert_eq!(custom.get_all(COOKIE).iter().collect::<Vec<_>>(), ["b=2"]);
+    }
+
+    #[test]
+    fn header_ops_are_applied_in_order() {
+        let mut builder = HttpHeaders::get_builder();
+        builder
+            .with_fingerprint(&Some(chrome_151::fingerprint()))
+            .with_header_ops(Some(vec![
+                HeaderOp::Append("Accept".to_string(), "text/plain".to_string()),
+                HeaderOp::Append("X-Forwarded-For".to_string(), "1.1.1.1".to_string()),
+                HeaderOp::Append("X-Forwarded-For".to_string(), "2.2.2.2".to_string()),
+                HeaderOp::SetEmpty("X-Empty".to_string()),
+            ]))
+            .with_header_ops(Some(vec![
+                ("user-agent", "impit").into(),
+                HeaderOp::Remove("x-empty".to_string()),
+                HeaderOp::SetEmpty("Referer".to_string()),
+            ]));
+
+        let headers: Vec<(String, String)> = builder.build().iter().collect();
+        let values = |target: &str| {
+            headers
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(target))
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values("accept").len(), 2);
+        assert_eq!(values("accept")[1], "text/plain");
+        assert_eq!(values("x-forwarded-for"), ["1.1.1.1", "2.2.2.2"]);
+        assert_eq!(values("user-agent"), ["impit"]);
+        assert!(values("x-empty").is_empty());
+        assert_eq!(values("referer"), [""]);
+
+        let repeatable = repeated_names(&headers);
+        assert_eq!(repeatable, ["accept", "x-forwarded-for"]);
+        let header_map = to_header_map(headers, &repeatable).unwrap();
+        assert_eq!(header_map.get_all("x-forwarded-for").iter().count(), 2);
+        assert_eq!(header_map.get("referer").unwrap(), "");
+    }
+
+    #[test]
+    fn headers_pushed_again_replace_the_earlier_value() {
+        let header = |name: &str, value: &str| (name.to_string(), value.to_string());
+        let headers = vec![
+            header("Authorization", "Basic a"),
+            header("Accept", "*/*"),
+            header("authorization", "Bearer b"),
+        ];
+
+        let header_map = to_header_map(headers, &[]).unwrap();
+        let names: Vec<&str> = header_map.keys().map(|name| name.as_str()).collect();
+        assert_eq!(names, ["authorization", "accept"]);
+        assert_eq!(
+            header_map
+                .get_all("authorization")
+                .iter()
+                .collect::<Vec<_>>(),
+            ["Bearer b"]
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn custom_headers_keep_the_first_value_and_remove_the_empty_ones() {
+        let header = |name: &str, value: &str| (name.to_string(), value.to_string());
+        let mut builder = HttpHeaders::get_builder();
+        builder
+            .with_custom_headers(Some(vec![header("X-Old", "1"), header("Referer", "a")]))
+            .with_custom_headers(Some(vec![
+                header("X-New", "2"),
+                header("x-new", "3"),
+                header("X-Old", ""),
+            ]));
+
+        let headers: Vec<(String, String)> = builder.build().iter().collect();
+        assert_eq!(headers, [header("Referer", "a"), header("X-New", "2")]);
+    }
+}
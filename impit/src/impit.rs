@@ -1,16 +1,41 @@
 use tokio::sync::RwLock;
 
 use log::debug;
-use reqwest::{cookie::CookieStore, header::HeaderMap, Method, Response, Version};
-use std::{fmt::Debug, net::IpAddr, str::FromStr, sync::Arc, time::Duration};
+use reqwest::{
+    cookie::CookieStore,
+    header::{HeaderMap, HeaderValue, HOST, SET_COOKIE},
+    Method, Response, ResponseBuilderExt, Version,
+};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use url::Url;
 
 use crate::{
+    circuit_breaker::{CircuitBreaker, CircuitState, Circuits},
+    client_hints::ClientHints,
+    dns::{DnsConfig, HappyEyeballs, Resolver},
     errors::{ErrorContext, ImpitError},
-    fingerprint::BrowserFingerprint,
-    http3::H3Engine,
-    http_headers::HttpHeaders,
-    request::{ImpitBody, ImpitRequest, RequestOptions},
+    fingerprint::{BrowserFingerprint, HeaderPosition, RequestDestination},
+    http1::{has_header_case, Http1Client, Http1Error},
+    http3::{Authority, H3Cache, H3Engine},
+    http_headers::{repeated_names, to_header_map, with_cookie_header, HttpHeaders},
+    limits::{ConcurrencyLimit, Limiter, RateLimit},
+    local_address::{LocalAddressPool, LocalAddressSelection, LocalAddressSelector, SourceAddress},
+    middleware::{Middleware, MiddlewareChain},
+    navigation::{Document, Navigation, Referrer, ReferrerPolicy},
+    quic::{QuicClient, QuicError, SocketBinding, Socks5Proxy},
+    redirect::{
+        self, RedirectAction, RedirectAttempt, RedirectHistory, RedirectHop, RedirectPolicy,
+        MAX_POLICY_REDIRECTS,
+    },
+    request::{parse_method, HeaderOp, ImpitBody, ImpitRequest, RequestOptions},
+    retry::RetryPolicy,
+    routing::{normalize_host, ConnectTo, Route, ServerName},
     tls,
 };
 
@@ -20,18 +45,72 @@ use crate::{
 ///
 /// To create a new [`Impit`] instance, use the [`Impit::builder()`](ImpitBuilder) method.
 pub struct Impit<CookieStoreImpl: CookieStore + 'static> {
-    pub(self) base_client: reqwest::Client,
-    pub(self) h3_client: Option<reqwest::Client>,
-    pub(self) vanilla_client: Option<reqwest::Client>,
+    clients: Arc<Clients>,
+    /// Picks the local addresses for requests, unless one address is used for the whole session.
+    local_addresses: Option<LocalAddressSelector>,
+    /// Clients bound to the local addresses picked by `local_addresses`, or built for a non-default [`Route`].
+    derived_clients: Mutex<HashMap<(Option<IpAddr>, Route), Arc<Clients>>>,
+    /// The resolver (and DNS cache) shared by all the clients.
+    resolver: Arc<Resolver>,
     h3_engine: Arc<RwLock<Option<H3Engine>>>,
+    /// Enforces the rate and concurrency limits set with the builder.
+    limiter: Limiter,
+    /// Tracks the failing hosts for the circuit breaker set with the builder.
+    circuits: Circuits,
+    /// Tracks the current document, if the navigation tracking is enabled.
+    navigation: Navigation,
+    /// The client hints each origin asked for, sent if the fingerprint has them.
+    client_hints: ClientHints,
     config: ImpitBuilder<CookieStoreImpl>,
 }
 
+/// The clients sending the requests with one set of connection settings.
+struct Clients {
+    base: reqwest::Client,
+    h3: Option<reqwest::Client>,
+    vanilla: Option<reqwest::Client>,
+    quic: Option<QuicClient>,
+    /// Sends the HTTP/1.1 requests whose header names aren't all lowercase, see [`Http1Client`].
+    http1: Option<Http1Client>,
+    /// The route the clients were built for.
+    route: Route,
+}
+
+/// Maximum number of client sets bound to different local addresses (or routes) kept around for reuse.
+const MAX_DERIVED_CLIENTS: usize = 64;
+
+/// How long the QUIC connection is waited for before the request is sent over TCP, see [`ImpitBuilder::with_quic_head_start`].
+const DEFAULT_QUIC_HEAD_START: Duration = Duration::from_millis(300);
+
+/// The connect-to rules and the server name of a request, the route of every attempt is picked from.
+struct RouteRules {
+    connect_to: Vec<ConnectTo>,
+    server_name: ServerName,
+}
+
 struct PreparedRequest {
     method: Method,
     url: Url,
     headers: HeaderMap,
+    /// The header names as they are spelled in the request, as `HeaderMap` lowercases them.
+    header_names: Vec<String>,
     body: ImpitBody,
+    /// The redirect limit of the request, reported in the error context.
+    max_redirects: Option<usize>,
+}
+
+impl PreparedRequest {
+    /// Returns a copy of the request, or `None` if its body is streamed and can only be sent once.
+    fn try_clone(&self) -> Option<Self> {
+        Some(PreparedRequest {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            header_names: self.header_names.clone(),
+            body: self.body.try_clone()?,
+            max_redirects: self.max_redirects,
+        })
+    }
 }
 
 impl<CookieStoreImpl: CookieStore + 'static> Default for Impit<CookieStoreImpl> {
@@ -44,6 +123,7 @@ impl<CookieStoreImpl: CookieStore + 'static> Default for Impit<CookieStoreImpl>
 ///
 /// The `RedirectBehavior` enum is used to specify how the client should handle redirects.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum RedirectBehavior {
     /// Follow up to `usize` redirects.
     ///
@@ -53,6 +133,21 @@ pub enum RedirectBehavior {
     ///
     /// The client will return the response for the first request, even with the `3xx` status code.
     ManualRedirect,
+    /// Decide every redirect with a custom callback.
+    ///
+    /// See [`RedirectPolicy`] for more details.
+    Custom(RedirectPolicy),
+}
+
+impl RedirectBehavior {
+    /// The maximum number of redirects followed.
+    fn max_redirects(&self) -> usize {
+        match self {
+            RedirectBehavior::FollowRedirect(max) => *max,
+            RedirectBehavior::ManualRedirect => 0,
+            RedirectBehavior::Custom(_) => MAX_POLICY_REDIRECTS,
+        }
+    }
 }
 
 /// A builder struct used to create a new [`Impit`] instance.
@@ -89,8 +184,24 @@ pub struct ImpitBuilder<CookieStoreImpl: CookieStore + 'static> {
     max_http_version: Version,
     redirect: RedirectBehavior,
     cookie_store: Option<Arc<CookieStoreImpl>>,
-    headers: Option<Vec<(String, String)>>,
+    headers: Option<Vec<HeaderOp>>,
+    header_position: HeaderPosition,
     local_address: Option<IpAddr>,
+    local_address_pool: Option<(LocalAddressPool, LocalAddressSelection)>,
+    interface: Option<String>,
+    dns: Option<DnsConfig>,
+    happy_eyeballs: HappyEyeballs,
+    connect_to: Vec<ConnectTo>,
+    server_name: ServerName,
+    quic_head_start: Duration,
+    h3_cache: H3Cache,
+    retry: RetryPolicy,
+    rate_limits: Vec<RateLimit>,
+    concurrency_limits: Vec<ConcurrencyLimit>,
+    queue_timeout: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    middleware: MiddlewareChain,
+    navigation: Option<ReferrerPolicy>,
 }
 
 impl<CookieStoreImpl: CookieStore + 'static> Clone for ImpitBuilder<CookieStoreImpl> {
@@ -105,7 +216,23 @@ impl<CookieStoreImpl: CookieStore + 'static> Clone for ImpitBuilder<CookieStoreI
             redirect: self.redirect.clone(),
             cookie_store: self.cookie_store.clone(),
             headers: self.headers.clone(),
+            header_position: self.header_position.clone(),
             local_address: self.local_address,
+            local_address_pool: self.local_address_pool.clone(),
+            interface: self.interface.clone(),
+            dns: self.dns.clone(),
+            happy_eyeballs: self.happy_eyeballs,
+            connect_to: self.connect_to.clone(),
+            server_name: self.server_name.clone(),
+            quic_head_start: self.quic_head_start,
+            h3_cache: self.h3_cache.clone(),
+            retry: self.retry.clone(),
+            rate_limits: self.rate_limits.clone(),
+            concurrency_limits: self.concurrency_limits.clone(),
+            queue_timeout: self.queue_timeout,
+            circuit_breaker: self.circuit_breaker.clone(),
+            middleware: self.middleware.clone(),
+            navigation: self.navigation,
         }
     }
 }
@@ -122,7 +249,23 @@ impl<CookieStoreImpl: CookieStore + 'static> Default for ImpitBuilder<CookieStor
             redirect: RedirectBehavior::FollowRedirect(10),
             cookie_store: None,
             headers: None,
+            header_position: HeaderPosition::default(),
             local_address: None,
+            local_address_pool: None,
+            interface: None,
+            dns: None,
+            happy_eyeballs: HappyEyeballs::default(),
+            connect_to: vec![],
+            server_name: ServerName::Host,
+            quic_head_start: DEFAULT_QUIC_HEAD_START,
+            h3_cache: H3Cache::default(),
+            retry: RetryPolicy::none(),
+            rate_limits: vec![],
+            concurrency_limits: vec![],
+            queue_timeout: None,
+            circuit_breaker: None,
+            middleware: MiddlewareChain::default(),
+            navigation: None,
         }
     }
 }
@@ -156,6 +299,10 @@ impl<CookieStoreImpl: CookieStore + 'static> ImpitBuilder<CookieStoreImpl> {
     ///
     /// Note that this proxy will be used for all the requests
     /// made by the built [`Impit`] instance.
+    ///
+    /// With HTTP/3 enabled, QUIC traffic is relayed through `socks5://` and `socks5h://` proxies using
+    /// the `UDP ASSOCIATE` command. If the proxy can't relay UDP (or isn't a SOCKS5 proxy), the requests
+    /// fall back to TCP through the proxy - HTTP/3 traffic never bypasses the configured proxy.
     pub fn with_proxy(mut self, proxy_url: String) -> Self {
         self.proxy_url = proxy_url;
         self
@@ -172,6 +319,8 @@ impl<CookieStoreImpl: CookieStore + 'static> ImpitBuilder<CookieStoreImpl> {
     /// Enables HTTP/3 usage for requests.
     ///
     /// `impit` currently supports HTTP/3 negotiation via the HTTPS DNS record and the `Alt-Svc` header.
+    /// The alternative services from `Alt-Svc` are cached until their `ma` (max-age) expires, and the QUIC
+    /// connections are made to the advertised alternative host and port.
     /// To enforce HTTP/3 usage, use the `http3_prior_knowledge` option in the `RequestOptions` struct when
     /// making the request.
     ///
@@ -181,11 +330,106 @@ impl<CookieStoreImpl: CookieStore + 'static> ImpitBuilder<CookieStoreImpl> {
         self
     }
 
+    /// Sets how long the QUIC connection to an origin is waited for before the request is sent over TCP instead.
+    ///
+    /// Like in Chrome, the origins discovered to support HTTP/3 are still reached over TCP if QUIC is blocked
+    /// (e.g. by a firewall dropping UDP traffic). Every request is sent once, over the QUIC connection if it's
+    /// established within the head start, over TCP otherwise. The exception is the `reqwest` HTTP/3 client
+    /// (used for the direct connections without an interface, a route or an alternative endpoint), which only
+    /// connects when sending the request: there, the `GET` and `HEAD` requests are sent over TCP as well once the
+    /// head start is over, the others are retried over TCP if the QUIC connection can't be established.
+    /// After a QUIC failure, HTTP/3 isn't used for the origin for a while (5 minutes, doubling with every further
+    /// failure).
+    ///
+    /// Defaults to 300 milliseconds. Use `Duration::MAX` to never race the requests.
+    /// The requests sent with `http3_prior_knowledge` are never raced nor retried over TCP.
+    pub fn with_quic_head_start(mut self, head_start: Duration) -> Self {
+        self.quic_head_start = head_start;
+        self
+    }
+
+    /// Sets the cache of the HTTP/3 endpoints discovered from the `Alt-Svc` headers and the HTTPS DNS records.
+    ///
+    /// Pass clones of the same [`H3Cache`] to share the discovered HTTP/3 support between multiple [`Impit`] instances.
+    /// By default, every instance starts with an empty cache of its own.
+    pub fn with_h3_cache(mut self, cache: H3Cache) -> Self {
+        self.h3_cache = cache;
+        self
+    }
+
+    /// Sets which failed requests are retried, and how long to wait between the attempts.
+    ///
+    /// By default, the requests are not retried (apart from the vanilla fallback, see
+    /// [`ImpitBuilder::with_fallback_to_vanilla`]). Can be overridden per request with [`RequestOptions::retry`].
+    /// See [`RetryPolicy`] for the errors and status codes that are retried.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Adds a limit on how many requests are started per period, e.g. per host.
+    ///
+    /// The requests exceeding the limit wait in a queue, see [`ImpitBuilder::with_queue_timeout`].
+    /// Multiple limits (e.g. per host and per proxy) can be combined, a request has to satisfy all of them.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limits.push(limit);
+        self
+    }
+
+    /// Adds a limit on how many requests can be in flight at the same time, e.g. per host.
+    ///
+    /// The requests exceeding the limit wait in a queue, see [`ImpitBuilder::with_queue_timeout`].
+    /// See [`ConcurrencyLimit`] for how long the requests occupy their slots.
+    pub fn with_concurrency_limit(mut self, limit: ConcurrencyLimit) -> Self {
+        self.concurrency_limits.push(limit);
+        self
+    }
+
+    /// Sets how long a request can wait for the rate and concurrency limits before failing with
+    /// [`ImpitError::QueueTimeout`].
+    ///
+    /// By default, the requests wait for as long as needed. The request timeout only starts once the request
+    /// leaves the queue.
+    pub fn with_queue_timeout(mut self, queue_timeout: Duration) -> Self {
+        self.queue_timeout = Some(queue_timeout);
+        self
+    }
+
+    /// Enables the circuit breaker, which stops sending requests to the hosts that keep failing.
+    ///
+    /// While the circuit of a host is open, the requests to it fail with [`ImpitError::CircuitOpen`] without being
+    /// sent. See [`CircuitBreaker`] for what counts as a failure, and [`Impit::circuit_state`] to query the state.
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Enables the navigation tracking, which sends every request as a navigation from the previous page.
+    ///
+    /// The final URL of every response (except for the unfollowed redirects) becomes the current document.
+    /// The next requests get the `Referer` header allowed by the document's referrer policy - the one from
+    /// its `Referrer-Policy` header, or `policy` - and the `Sec-Fetch-Site` header computed with
+    /// the Public Suffix List. See [`Impit::document`] and [`Impit::set_document`] to inspect or reset the state.
+    pub fn with_navigation(mut self, policy: ReferrerPolicy) -> Self {
+        self.navigation = Some(policy);
+        self
+    }
+
+    /// Adds a middleware hooking into every request sent by the client, see [`Middleware`].
+    ///
+    /// The middleware is called in the order of registration.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
     /// Sets the desired redirect behavior.
     ///
     /// By default, the client will follow up to 10 redirects.
     /// By passing the `RedirectBehavior::ManualRedirect` option, the client will not follow any redirects
     /// (i.e. it will return the response for the first request, with the 3xx status code).
+    ///
+    /// The behavior can be overridden per request with [`RequestOptions::redirect`].
     pub fn with_redirect(mut self, behavior: RedirectBehavior) -> Self {
         self.redirect = behavior;
         self
@@ -215,14 +459,93 @@ impl<CookieStoreImpl: CookieStore + 'static> ImpitBuilder<CookieStoreImpl> {
         Ok(self)
     }
 
+    /// Sets a pool of local addresses to bind the client to.
+    ///
+    /// The `selection` decides whether a new address is picked for every request, for every host, or once
+    /// for the whole [`Impit`] instance. See [`LocalAddressPool`] for details on how the addresses are picked.
+    ///
+    /// The address the request was sent from is available in the response extensions as [`SourceAddress`].
+    /// This setting takes precedence over [`ImpitBuilder::with_local_address`].
+    pub fn with_local_address_pool(
+        mut self,
+        pool: LocalAddressPool,
+        selection: LocalAddressSelection,
+    ) -> Self {
+        self.local_address_pool = Some((pool, selection));
+        self
+    }
+
+    /// Binds all the connections to the network interface with the given name (e.g. `eth1` or `wg0`).
+    ///
+    /// This uses the `SO_BINDTODEVICE` socket option for both the TCP connections and the QUIC sockets,
+    /// so it's only supported on Linux (and Android). Building the [`Impit`] instance fails on other platforms.
+    ///
+    /// Can be combined with [`ImpitBuilder::with_local_address`] to pick the source address on the interface.
+    pub fn with_interface(mut self, interface: String) -> Self {
+        self.interface = Some(interface);
+        self
+    }
+
+    /// Sets the DNS resolution for all the requests made by the built [`Impit`] instance.
+    ///
+    /// The resolver is used both for opening the connections and for the HTTP/3 discovery via the HTTPS DNS records.
+    /// See [`DnsConfig`] for the static overrides and custom (or encrypted) nameservers.
+    ///
+    /// Note that with a `socks5h://` proxy, the hostnames are resolved by the proxy instead.
+    pub fn with_dns(mut self, dns: DnsConfig) -> Self {
+        self.dns = Some(dns);
+        self
+    }
+
+    /// Sets the IP family preference and the connection racing between the IPv6 and IPv4 addresses.
+    ///
+    /// By default, both families are used with IPv6 addresses tried first, just like in the browsers.
+    /// Note that the [`HappyEyeballs::connection_attempt_delay`] only applies to the HTTP/3 connections,
+    /// the TCP connections always wait 300 ms. See [`HappyEyeballs`] for details.
+    pub fn with_happy_eyeballs(mut self, happy_eyeballs: HappyEyeballs) -> Self {
+        self.happy_eyeballs = happy_eyeballs;
+        self
+    }
+
+    /// Adds a rule to open the connections for some hosts to a different host or port, like curl's `--connect-to`.
+    ///
+    /// The requests keep the `Host` header, the TLS server name and the cookies of the URL host.
+    /// The first matching rule is used, the rules from [`RequestOptions::connect_to`] are checked before these.
+    ///
+    /// Note that over TCP, the rules only apply to URLs with a hostname (not an IP address), and the connection port
+    /// is only changed if the URL doesn't contain an explicit port. See [`ConnectTo`] for the rule format.
+    pub fn with_connect_to(mut self, rule: ConnectTo) -> Self {
+        self.connect_to.push(rule);
+        self
+    }
+
+    /// Sets the server name sent in the TLS handshake (SNI), independently of the `Host` header.
+    ///
+    /// By default, the hostname from the request URL is sent. See [`ServerName`] for the limitations
+    /// of the custom server names. Can be overridden per request with [`RequestOptions::server_name`].
+    pub fn with_server_name(mut self, server_name: ServerName) -> Self {
+        self.server_name = server_name;
+        self
+    }
+
     /// Sets additional headers to include in every request made by the built [`Impit`] instance.
     ///
     /// This can be used to add e.g. custom user-agent or authorization headers that should be included in every request.
     /// These headers override the "impersonation" headers set by the `with_browser` method.
     ///
+    /// Takes `(name, value)` pairs, which set the headers, or [`HeaderOp`]s to also append, remove or empty them.
+    ///
     /// If you want to add custom headers to a specific request, use the `RequestOptions` struct instead.
-    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
-        self.headers = Some(headers);
+    pub fn with_headers<H: Into<HeaderOp>>(mut self, headers: Vec<H>) -> Self {
+        self.headers = Some(headers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets where the custom headers that aren't part of the fingerprint are placed, at the end by default.
+    ///
+    /// The custom headers overriding the fingerprint's headers always keep the browser's order.
+    pub fn with_header_position(mut self, position: HeaderPosition) -> Self {
+        self.header_position = position;
         self
     }
 
@@ -237,16 +560,67 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         ImpitBuilder::default()
     }
 
+    /// Returns the state of the circuit breaker for `host`.
+    ///
+    /// The circuits are always closed unless the circuit breaker is enabled with
+    /// [`ImpitBuilder::with_circuit_breaker`].
+    pub fn circuit_state(&self, host: &str) -> CircuitState {
+        self.circuits.state(host)
+    }
+
+    /// Returns the current document, i.e. the page the next request is sent from.
+    ///
+    /// Always `None` unless the navigation tracking is enabled with [`ImpitBuilder::with_navigation`].
+    pub fn document(&self) -> Option<Document> {
+        self.navigation.document()
+    }
+
+    /// Sets the page the next request is sent from, e.g. to make it look like a click on a search result.
+    ///
+    /// Passing `None` resets the navigation, the next request is then sent as if the URL was typed into
+    /// the address bar. Does nothing unless the navigation tracking is enabled.
+    pub fn set_document(&self, url: Option<Url>) {
+        self.navigation.set_document(url);
+    }
+
+    fn new_tls_config(
+        config: &ImpitBuilder<CookieStoreImpl>,
+        route: &Route,
+    ) -> rustls::ClientConfig {
+        let mut tls_config_builder = tls::TlsConfig::builder();
+
+        if let Some(ref fingerprint) = config.fingerprint {
+            tls_config_builder.with_tls_fingerprint(fingerprint.tls.clone());
+        }
+
+        if config.max_http_version == Version::HTTP_3 {
+            tls_config_builder.with_http3();
+        }
+
+        tls_config_builder.with_ignore_tls_errors(config.ignore_tls_errors);
+        tls_config_builder.with_sni(route.server_name != ServerName::Disabled);
+
+        let mut tls_config = tls_config_builder.build();
+        // The custom server name replaces the URL host in the TCP requests, so the original host has to go
+        // in the `Host` header, which only HTTP/1.1 has (see `Route::tcp_url`).
+        if matches!(route.server_name, ServerName::Custom(_))
+            && config.max_http_version != Version::HTTP_3
+        {
+            tls_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        }
+
+        tls_config
+    }
+
     fn new_reqwest_client(
         config: &ImpitBuilder<CookieStoreImpl>,
+        resolver: &Arc<Resolver>,
+        route: &Route,
     ) -> Result<reqwest::Client, ImpitError> {
         let mut client = reqwest::Client::builder();
-        let mut tls_config_builder = tls::TlsConfig::builder();
 
         // Use fingerprint if provided, otherwise fall back to browser enum
         if let Some(ref fingerprint) = config.fingerprint {
-            tls_config_builder.with_tls_fingerprint(fingerprint.tls.clone());
-
             if let Some(window_size) = fingerprint.http2.initial_stream_window_size {
                 client = client.http2_initial_stream_window_size(window_size);
             }
@@ -260,13 +634,7 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
             }
         }
 
-        if config.max_http_version == Version::HTTP_3 {
-            tls_config_builder.with_http3();
-        }
-
-        tls_config_builder.with_ignore_tls_errors(config.ignore_tls_errors);
-
-        let tls_config = tls_config_builder.build();
+        let tls_config = Self::new_tls_config(config, route);
 
         client = client
             .danger_accept_invalid_certs(config.ignore_tls_errors)
@@ -274,9 +642,16 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
             .use_preconfigured_tls(tls_config)
             .timeout(config.request_timeout);
 
-        if let Some(cookie_provider) = &config.cookie_store {
+        // The requests with a custom server name are made for a different URL, so their cookies are
+        // taken care of in `Impit::execute_request`.
+        let custom_server_name = matches!(route.server_name, ServerName::Custom(_));
+        if let Some(cookie_provider) = config.cookie_store.as_ref().filter(|_| !custom_server_name)
+        {
             client = client.cookie_provider(cookie_provider.clone());
         }
+        if custom_server_name && config.max_http_version != Version::HTTP_3 {
+            client = client.http1_only();
+        }
 
         if config.max_http_version == Version::HTTP_3 {
             client = client.http3_prior_knowledge();
@@ -293,33 +668,101 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
             client = client.local_address(ip_addr);
         }
 
-        match config.redirect {
-            RedirectBehavior::FollowRedirect(max) => {
-                client = client.redirect(reqwest::redirect::Policy::limited(max));
-            }
-            RedirectBehavior::ManualRedirect => {
-                client = client.redirect(reqwest::redirect::Policy::none());
+        client = client.dns_resolver(resolver.clone());
+
+        if let Some(interface) = &config.interface {
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            {
+                client = client.interface(interface);
             }
+
+            #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+            return Err(ImpitError::ReqwestError(format!(
+                "Binding to the network interface `{interface}` is only supported on Linux"
+            )));
         }
 
+        // The redirects are followed by `Impit` itself, see `Impit::follow_redirects`.
+        client = client.redirect(reqwest::redirect::Policy::none());
+
         client
             .build()
             .map_err(|e| ImpitError::ReqwestError(format!("{e:#?}")))
     }
 
-    /// Creates a new [`Impit`] instance based on the options stored in the [`ImpitBuilder`] instance.
-    fn new(config: ImpitBuilder<CookieStoreImpl>) -> Result<Self, ImpitError> {
-        let mut h3_client: Option<reqwest::Client> = None;
-        let mut base_client = Self::new_reqwest_client(&config)?;
+    /// Creates the clients for the given configuration, sending the requests with `route`.
+    fn new_clients(
+        config: &ImpitBuilder<CookieStoreImpl>,
+        resolver: &Arc<Resolver>,
+        route: &Route,
+    ) -> Result<Clients, ImpitError> {
+        let resolver = &if route.is_default() {
+            resolver.clone()
+        } else {
+            Arc::new(resolver.for_route(route))
+        };
 
-        if config.max_http_version == Version::HTTP_3 {
-            h3_client = Some(base_client);
-            base_client = Self::new_reqwest_client(&ImpitBuilder::<CookieStoreImpl> {
+        let mut h3_client: Option<reqwest::Client> = None;
+        let mut quic_client: Option<QuicClient> = None;
+        let base_client = Self::new_reqwest_client(
+            &ImpitBuilder::<CookieStoreImpl> {
                 max_http_version: Version::HTTP_2,
                 ..config.clone()
-            })?;
+            },
+            resolver,
+            route,
+        )?;
+
+        let binding = SocketBinding {
+            local_address: config.local_address,
+            interface: config.interface.clone(),
+        };
+
+        if config.max_http_version == Version::HTTP_3 {
+            if config.proxy_url.is_empty() {
+                if config.interface.is_none() && route.is_default() {
+                    h3_client = Some(Self::new_reqwest_client(config, resolver, route)?);
+                }
+                // `reqwest` can't bind its QUIC sockets to an interface, follow the route or connect
+                // to the alternative endpoints from `Alt-Svc`, so these go through our own client.
+                quic_client = Some(QuicClient::new(
+                    Self::new_tls_config(config, route),
+                    None,
+                    binding.clone(),
+                    resolver.clone(),
+                    route.clone(),
+                )?);
+            } else if let Some(proxy) = Socks5Proxy::from_url(&config.proxy_url) {
+                // `reqwest` would open the QUIC connections directly, so proxied HTTP/3 goes through our own client.
+                match QuicClient::new(
+                    Self::new_tls_config(config, route),
+                    Some(proxy),
+                    binding.clone(),
+                    resolver.clone(),
+                    route.clone(),
+                ) {
+                    Ok(client) => quic_client = Some(client),
+                    Err(err) => debug!("HTTP/3 through the proxy is unavailable: {err}"),
+                }
+            } else {
+                debug!("Only SOCKS5 proxies can carry HTTP/3 traffic, all requests will use TCP.");
+            }
         }
 
+        let http1_client = Http1Client::new(
+            Self::new_tls_config(
+                &ImpitBuilder::<CookieStoreImpl> {
+                    max_http_version: Version::HTTP_2,
+                    ..config.clone()
+                },
+                route,
+            ),
+            &config.proxy_url,
+            binding,
+            resolver.clone(),
+            route.clone(),
+        );
+
         let vanilla_client = if config.vanilla_fallback && config.fingerprint.is_some() {
             Some(Self::new_reqwest_client(
                 &ImpitBuilder::<CookieStoreImpl> {
@@ -327,11 +770,43 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
                     max_http_version: Version::HTTP_2,
                     ..config.clone()
                 },
+                resolver,
+                route,
             )?)
         } else {
             None
         };
 
+        Ok(Clients {
+            base: base_client,
+            h3: h3_client,
+            vanilla: vanilla_client,
+            quic: quic_client,
+            http1: http1_client,
+            route: route.clone(),
+        })
+    }
+
+    /// Creates a new [`Impit`] instance based on the options stored in the [`ImpitBuilder`] instance.
+    fn new(mut config: ImpitBuilder<CookieStoreImpl>) -> Result<Self, ImpitError> {
+        let mut local_addresses = config
+            .local_address_pool
+            .clone()
+            .map(|(pool, selection)| LocalAddressSelector::new(pool, selection));
+
+        // A session-wide address is picked once and behaves just like `with_local_address`.
+        if let Some(selector) = local_addresses
+            .take_if(|selector| selector.selection() == LocalAddressSelection::PerSession)
+        {
+            config.local_address = selector.select("", None);
+        }
+
+        let resolver = Arc::new(Resolver::new(
+            &config.dns.clone().unwrap_or_default(),
+            config.happy_eyeballs,
+        )?);
+        let clients = Self::new_clients(&config, &resolver, &Route::default())?;
+
         // Set pseudo-header order from fingerprint or fall back to browser enum
         let pseudo_headers_order: Vec<String> = if let Some(ref fingerprint) = config.fingerprint {
             fingerprint.http2.pseudo_header_order.to_vec()
@@ -347,14 +822,98 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         }
 
         Ok(Impit {
-            base_client,
-            h3_client,
-            vanilla_client,
-            config,
+            clients: Arc::new(clients),
+            local_addresses,
+            derived_clients: Mutex::new(HashMap::new()),
+            resolver,
             h3_engine: Arc::new(RwLock::new(None)),
+            limiter: Limiter::new(
+                config.rate_limits.clone(),
+                config.concurrency_limits.clone(),
+                config.queue_timeout,
+            )?,
+            circuits: Circuits::new(config.circuit_breaker.clone()),
+            navigation: Navigation::new(config.navigation, referer_position(&config)),
+            client_hints: ClientHints::new(
+                config
+                    .fingerprint
+                    .as_ref()
+                    .map(|fp| fp.client_hints.clone())
+                    .unwrap_or_default(),
+            ),
+            config,
         })
     }
 
+    /// Returns the clients to send a request to `host` with `route`, along with the local address they are bound to.
+    async fn clients_for(
+        &self,
+        host: &str,
+        route: &Route,
+    ) -> Result<(Arc<Clients>, Option<IpAddr>), ImpitError> {
+        let selected_address = match &self.local_addresses {
+            Some(selector) => selector.select(host, self.target_address(host, route).await),
+            None => None,
+        };
+
+        if selected_address.is_none() && route.is_default() {
+            return Ok((self.clients.clone(), self.config.local_address));
+        }
+
+        let local_address = selected_address.or(self.config.local_address);
+        let key = (local_address, route.clone());
+
+        if let Some(clients) = self.derived_clients.lock().unwrap().get(&key) {
+            return Ok((clients.clone(), local_address));
+        }
+
+        let clients = Arc::new(Self::new_clients(
+            &ImpitBuilder::<CookieStoreImpl> {
+                local_address,
+                ..self.config.clone()
+            },
+            &self.resolver,
+            route,
+        )?);
+
+        let mut derived_clients = self.derived_clients.lock().unwrap();
+        // Addresses from large prefixes are rarely reused, so the cache is simply reset once it's full.
+        if derived_clients.len() >= MAX_DERIVED_CLIENTS {
+            derived_clients.clear();
+        }
+        derived_clients.insert(key, clients.clone());
+
+        Ok((clients, local_address))
+    }
+
+    /// Returns the address the connection to `host` most likely goes to, so that the local address of the same
+    /// IP family can be picked. The lookup is cached, so the connection itself doesn't cause another DNS query.
+    async fn target_address(&self, host: &str, route: &Route) -> Option<IpAddr> {
+        let host = route
+            .connect_to
+            .as_ref()
+            .and_then(|(_, connect_host, _)| connect_host.as_deref())
+            .unwrap_or(host)
+            .trim_matches(['[', ']']);
+
+        let port = route
+            .resolve_port
+            .as_ref()
+            .filter(|(route_host, _)| route_host == host)
+            .map(|(_, port)| *port);
+
+        match host.parse::<IpAddr>() {
+            Ok(address) => Some(address),
+            Err(_) => self
+                .resolver
+                .lookup_ip(host, port)
+                .await
+                .ok()?
+                .first()
+                .copied(),
+        }
+    }
+
     fn parse_url(&self, url: String) -> Result<Url, ImpitError> {
         let url = Url::parse(&url).map_err(|_| ImpitError::UrlParsingError(url.clone()))?;
 
@@ -371,47 +930,119 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         }
     }
 
-    async fn should_use_h3(&self, host: &String) -> bool {
+    /// Returns the HTTP/3 endpoint to send the request to `url` to, or `None` if it should be sent over TCP.
+    async fn h3_endpoint(&self, clients: &Clients, url: &Url) -> Option<Authority> {
         if self.config.max_http_version < Version::HTTP_3 {
             debug!("HTTP/3 is disabled, falling back to TCP-based requests.");
-            return false;
+            return None;
+        }
+
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or(443);
+
+        if clients.h3.is_none() {
+            match clients.quic.as_ref() {
+                Some(quic_client) if !quic_client.is_relay_unavailable() => {}
+                _ => {
+                    debug!("HTTP/3 is unavailable for {host}, falling back to TCP-based requests.");
+                    return None;
+                }
+            }
         }
 
         {
             let engine_guard = self.h3_engine.read().await;
             if let Some(engine) = engine_guard.as_ref() {
-                return engine.host_supports_h3(host).await;
+                return engine.h3_endpoint(host, port).await;
             }
         }
 
         {
             let mut engine_guard = self.h3_engine.write().await;
             if engine_guard.is_none() {
-                *engine_guard = Some(H3Engine::init().await);
+                // With a `socks5h://` proxy, the hostnames must not be resolved locally.
+                let dns_discovery = Socks5Proxy::from_url(&self.config.proxy_url)
+                    .is_none_or(|proxy| !proxy.resolves_remotely());
+                let resolver = dns_discovery.then(|| self.resolver.clone());
+                *engine_guard = Some(H3Engine::init(resolver, self.config.h3_cache.clone()).await);
             }
 
             match engine_guard.as_ref() {
-                None => false,
-                Some(engine) => engine.host_supports_h3(host).await,
+                None => None,
+                Some(engine) => engine.h3_endpoint(host, port).await,
             }
         }
     }
 
+    /// Updates the HTTP/3 endpoints of the origin of `url` from the `Alt-Svc` header of its response.
+    async fn record_alt_svc(&self, url: &Url, response: &Response) {
+        // Like the browsers, only the alternative services of secure origins are used.
+        if url.scheme() != "https" {
+            return;
+        }
+
+        let Some(alt_svc) = response
+            .headers()
+            .get("Alt-Svc")
+            .and_then(|alt_svc| alt_svc.to_str().ok())
+        else {
+            return;
+        };
+
+        let engine_guard = self.h3_engine.read().await;
+        if let Some(h3_engine) = engine_guard.as_ref() {
+            h3_engine
+                .update_alt_svc(
+                    url.host_str().unwrap_or_default(),
+                    url.port_or_known_default().unwrap_or(443),
+                    alt_svc,
+                )
+                .await;
+        }
+    }
+
+    /// Records whether the HTTP/3 request to the origin of `url` succeeded, see [`H3Engine::mark_broken`].
+    async fn record_quic_result(&self, url: &Url, working: bool) {
+        let engine_guard = self.h3_engine.read().await;
+        let Some(h3_engine) = engine_guard.as_ref() else {
+            return;
+        };
+
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or(443);
+        if working {
+            h3_engine.mark_working(host, port).await;
+        } else {
+            h3_engine.mark_broken(host, port).await;
+        }
+    }
+
+    /// Marks QUIC as broken for the origin of `url`, unless `err` is a failure of the request rather than
+    /// of the QUIC connection itself (e.g. a reset stream on a working connection).
+    async fn record_quic_failure(&self, url: &Url, err: &QuicError) {
+        if err.is_handshake_failure() {
+            self.record_quic_result(url, false).await;
+        }
+    }
+
     fn build_request(
         &self,
         method: Method,
         url: Url,
         body: Option<ImpitBody>,
-        headers: Vec<(String, String)>,
+        headers: Vec<HeaderOp>,
+        destination: RequestDestination,
     ) -> ImpitRequest {
         let host = url.host_str().unwrap_or_default().to_string();
 
         let headers = HttpHeaders::get_builder()
             .with_fingerprint(&self.config.fingerprint)
+            .with_destination(destination)
+            .with_header_position(&self.config.header_position)
             .with_host(&host)
             .with_https(url.scheme() == "https")
-            .with_custom_headers(self.config.headers.to_owned())
-            .with_custom_headers(Some(headers))
+            .with_header_ops(self.config.headers.to_owned())
+            .with_header_ops(Some(headers))
             .build();
 
         ImpitRequest {
@@ -422,16 +1053,29 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         }
     }
 
+    /// Sends the request with a `reqwest` client.
+    ///
+    /// With a `tcp_url` (see [`Route::tcp_url`]), the request is made for that URL instead, with the `Host` header
+    /// of the original one. The response then gets the original URL back, and the cookies are stored here.
     async fn execute_request(
         &self,
         client: &reqwest::Client,
         prepared: &mut PreparedRequest,
         timeout: Option<Duration>,
         h3: bool,
+        tcp_url: Option<&Url>,
     ) -> Result<Response, reqwest::Error> {
+        let mut headers = self.headers_with_cookies(prepared);
+        if tcp_url.is_some() {
+            headers = with_host_header(headers, &prepared.url);
+        }
+
         let mut req = client
-            .request(prepared.method.clone(), prepared.url.clone())
-            .headers(prepared.headers.clone());
+            .request(
+                prepared.method.clone(),
+                tcp_url.unwrap_or(&prepared.url).clone(),
+            )
+            .headers(headers);
 
         if h3 {
             req = req.version(Version::HTTP_3);
@@ -445,114 +1089,797 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
             req = req.body(body);
         }
 
-        req.send().await
+        let response = req.send().await?;
+        if tcp_url.is_none() {
+            return Ok(response);
+        }
+
+        self.store_cookies(&response, &prepared.url);
+        Ok(with_url(response, &prepared.url))
     }
 
-    async fn send(
+    /// Stores the cookies set by a response to `url`, for the clients that don't handle cookies by themselves.
+    fn store_cookies(&self, response: &Response, url: &Url) {
+        if let Some(cookie_store) = &self.config.cookie_store {
+            let mut set_cookies = response.headers().get_all(SET_COOKIE).iter();
+            cookie_store.set_cookies(&mut set_cookies, url);
+        }
+    }
+
+    /// Returns the request headers with the cookies for the request URL placed at the fingerprint's cookie position.
+    ///
+    /// This runs for every attempt, so that redirects and retries see the current state of the cookie store.
+    /// The `reqwest` cookie provider doesn't add another `Cookie` header, as it skips requests that already have one.
+    fn headers_with_cookies(&self, prepared: &PreparedRequest) -> HeaderMap {
+        let cookies = self
+            .config
+            .cookie_store
+            .as_ref()
+            .and_then(|cookie_store| cookie_store.cookies(&prepared.url));
+
+        match cookies {
+            Some(cookies) => {
+                let position = self
+                    .config
+                    .fingerprint
+                    .as_ref()
+                    .map(|fp| fp.cookie_position.clone())
+                    .unwrap_or_default();
+                with_cookie_header(prepared.headers.clone(), cookies, &position)
+            }
+            None => prepared.headers.clone(),
+        }
+    }
+
+    /// Sends the request over HTTP/3 with our own [`QuicClient`], used for proxied and interface-bound connections.
+    ///
+    /// Unlike the `reqwest` clients, [`QuicClient`] doesn't handle cookies by itself, so these are taken care of here.
+    async fn execute_quic_request(
         &self,
-        request: ImpitRequest,
+        quic_client: &QuicClient,
+        endpoint: Option<&Authority>,
+        prepared: &PreparedRequest,
         timeout: Option<Duration>,
-        http3_prior_knowledge: Option<bool>,
-    ) -> Result<Response, ImpitError> {
-        let http3_prior_knowledge = http3_prior_knowledge.unwrap_or(false);
-        if http3_prior_knowledge && self.config.max_http_version < Version::HTTP_3 {
-            return Err(ImpitError::Http3Disabled);
+    ) -> Result<Response, QuicError> {
+        let Some(body) = prepared.body.to_bytes() else {
+            return Err(QuicError::ConnectFailed(
+                "streamed request bodies can't be sent over this HTTP/3 client".to_string(),
+            ));
+        };
+
+        let headers = self.headers_with_cookies(prepared);
+
+        let response = quic_client
+            .send(
+                prepared.method.clone(),
+                &prepared.url,
+                endpoint,
+                headers,
+                body,
+                timeout.unwrap_or(self.config.request_timeout),
+            )
+            .await?;
+
+        self.store_cookies(&response, &prepared.url);
+
+        Ok(response)
+    }
+
+    fn error_context(&self, prepared: &PreparedRequest, timeout: Option<Duration>) -> ErrorContext {
+        ErrorContext {
+            timeout: Some(timeout.unwrap_or(self.config.request_timeout)),
+            max_redirects: prepared.max_redirects,
+            method: Some(prepared.method.to_string()),
+            protocol: Some(prepared.url.scheme().to_string()),
+            url: Some(prepared.url.to_string()),
         }
+    }
 
-        let url = request.url.to_string();
-        let host = request.url.host_str().unwrap_or_default().to_string();
-        let h3 = http3_prior_knowledge
-            || self
-                .should_use_h3(&request.url.host_str().unwrap_or_default().to_string())
-                .await;
-        let client = if h3 {
-            debug!("Using QUIC for request to {url}");
-            self.h3_client.as_ref().unwrap_or(&self.base_client)
-        } else {
-            debug!("{url} doesn't seem to have HTTP3 support");
-            &self.base_client
-        };
+    /// Sends the request over HTTP/1.1 with our own [`Http1Client`], keeping the casing of the header names.
+    ///
+    /// Like [`QuicClient`], [`Http1Client`] doesn't handle cookies by itself, so these are taken care of here.
+    async fn execute_http1_request(
+        &self,
+        http1_client: &Http1Client,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, Http1Error> {
+        let headers = with_host_header(self.headers_with_cookies(prepared), &prepared.url);
+
+        let response = http1_client
+            .send(
+                prepared.method.clone(),
+                &prepared.url,
+                headers,
+                &prepared.header_names,
+                &mut prepared.body,
+                timeout.unwrap_or(self.config.request_timeout),
+            )
+            .await?;
+
+        self.store_cookies(&response, &prepared.url);
 
-        let header_map_result: Result<HeaderMap, ImpitError> =
-            HttpHeaders::from(request.headers).into();
-        let header_map = header_map_result?;
+        Ok(response)
+    }
 
-        let method = Method::from_str(&request.method).map_err(|_| {
-            ImpitError::InvalidMethod(format!("Invalid HTTP method: {}", request.method))
-        })?;
+    /// Sends the request over TCP, retrying with the vanilla client (if enabled) when the connection fails.
+    ///
+    /// The requests not known to go over another protocol than HTTP/1.1 are sent with our own [`Http1Client`]
+    /// if their header names have to keep their casing.
+    async fn execute_tcp_request(
+        &self,
+        clients: &Clients,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ImpitError> {
+        let http1_client = clients.http1.as_ref().filter(|http1_client| {
+            http1_client.handles(&prepared.url) && has_header_case(&prepared.header_names)
+        });
+        let http1_result = match http1_client {
+            Some(http1_client) => {
+                match self
+                    .execute_http1_request(http1_client, prepared, timeout)
+                    .await
+                {
+                    // The server picked HTTP/2, `reqwest` sends the request instead.
+                    Err(Http1Error::ProtocolMismatch) => None,
+                    result => Some(result.map_err(Into::into)),
+                }
+            }
+            None => None,
+        };
 
-        let max_redirects = match self.config.redirect {
-            RedirectBehavior::FollowRedirect(max) => max,
-            RedirectBehavior::ManualRedirect => 0,
+        let tcp_url = clients.route.tcp_url(&prepared.url);
+        let result = match http1_result {
+            Some(result) => result,
+            None => self
+                .execute_request(&clients.base, prepared, timeout, false, tcp_url.as_ref())
+                .await
+                .inspect(|response| {
+                    if let Some(http1_client) = &clients.http1 {
+                        http1_client.record_version(&prepared.url, response.version());
+                    }
+                })
+                .map_err(|err| ImpitError::from(err, Some(self.error_context(prepared, timeout)))),
+        };
+        let primary_error = match result {
+            Ok(response) => return Ok(response),
+            Err(err) => err,
         };
 
-        let mut prepared = PreparedRequest {
-            method: method.clone(),
-            url: request.url.clone(),
-            headers: header_map,
-            body: request.body,
+        let fallback_client = clients
+            .vanilla
+            .as_ref()
+            .filter(|_| primary_error.is_connect_error() && prepared.body.is_sendable());
+        let Some(vanilla_client) = fallback_client else {
+            return Err(primary_error);
         };
 
-        let primary_result = self
-            .execute_request(client, &mut prepared, timeout, h3)
-            .await;
+        debug!(
+            "Primary request to {} failed with {primary_error}, retrying with vanilla client",
+            prepared.url
+        );
+        self.execute_request(vanilla_client, prepared, timeout, false, tcp_url.as_ref())
+            .await
+            .map_err(|_| primary_error)
+    }
 
-        let response = match primary_result {
-            Ok(resp) => resp,
-            Err(err) => {
-                let primary_error = ImpitError::from(
+    /// Sends the request over HTTP/3, with our own [`QuicClient`] if set, or with the `reqwest` HTTP/3 client.
+    async fn execute_h3_request(
+        &self,
+        clients: &Clients,
+        quic_client: Option<&QuicClient>,
+        endpoint: Option<&Authority>,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, QuicError> {
+        if let Some(quic_client) = quic_client {
+            return self
+                .execute_quic_request(quic_client, endpoint, prepared, timeout)
+                .await;
+        }
+
+        let client = clients.h3.as_ref().unwrap_or(&clients.base);
+        self.execute_request(client, prepared, timeout, true, None)
+            .await
+            .map_err(|err| {
+                QuicError::Request(ImpitError::from(
                     err,
-                    Some(ErrorContext {
-                        timeout: Some(timeout.unwrap_or(self.config.request_timeout)),
-                        max_redirects: Some(max_redirects),
-                        method: Some(method.to_string()),
-                        protocol: Some(request.url.scheme().to_string()),
-                        url: Some(url.clone()),
-                    }),
-                );
+                    Some(self.error_context(prepared, timeout)),
+                ))
+            })
+    }
 
-                let fallback_client = self
-                    .vanilla_client
-                    .as_ref()
-                    .filter(|_| primary_error.is_connect_error() && prepared.body.is_sendable());
-                let Some(vanilla_client) = fallback_client else {
-                    return Err(primary_error);
+    /// Sends the request to an origin discovered to support HTTP/3, falling back to TCP when QUIC fails.
+    ///
+    /// With our own [`QuicClient`], the QUIC connection is raced against the request over TCP,
+    /// see [`Impit::race_quic_connection`]. `reqwest` only connects when the request is sent, so with its HTTP/3
+    /// client, just the `GET` and `HEAD` requests are also sent over TCP if the HTTP/3 request doesn't finish
+    /// within the head start (see [`ImpitBuilder::with_quic_head_start`]), the first successful response wins.
+    /// The other requests are only retried over TCP if the QUIC connection couldn't be established.
+    async fn race_h3(
+        &self,
+        clients: &Clients,
+        quic_client: Option<&QuicClient>,
+        endpoint: Option<&Authority>,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ImpitError> {
+        let url = prepared.url.clone();
+        debug!("Using QUIC for request to {url}");
+
+        if let Some(quic_client) = quic_client {
+            return self
+                .race_quic_connection(clients, quic_client, endpoint, prepared, timeout)
+                .await;
+        }
+
+        let racing = [Method::GET, Method::HEAD]
+            .contains(&prepared.method)
+            .then(|| prepared.try_clone())
+            .flatten();
+        let Some(mut tcp_prepared) = racing else {
+            return self
+                .execute_h3_with_fallback(clients, None, endpoint, prepared, timeout)
+                .await;
+        };
+
+        let h3_attempt = self.execute_h3_request(clients, None, endpoint, prepared, timeout);
+        tokio::pin!(h3_attempt);
+
+        tokio::select! {
+            result = &mut h3_attempt => {
+                return match result {
+                    Ok(response) => {
+                        self.record_quic_result(&url, true).await;
+                        Ok(response)
+                    }
+                    Err(err) => {
+                        debug!("HTTP/3 request to {url} failed, retrying over TCP");
+                        self.record_quic_failure(&url, &err).await;
+                        self.execute_tcp_request(clients, &mut tcp_prepared, timeout)
+                            .await
+                    }
                 };
+            }
+            _ = tokio::time::sleep(self.config.quic_head_start) => {}
+        }
+
+        debug!(
+            "HTTP/3 request to {url} is still pending after {:?}, racing it over TCP",
+            self.config.quic_head_start
+        );
+        let tcp_attempt = self.execute_tcp_request(clients, &mut tcp_prepared, timeout);
+        tokio::pin!(tcp_attempt);
+
+        tokio::select! {
+            result = &mut h3_attempt => match result {
+                Ok(response) => {
+                    self.record_quic_result(&url, true).await;
+                    Ok(response)
+                }
+                Err(err) => {
+                    debug!("HTTP/3 request to {url} failed, waiting for the TCP request");
+                    self.record_quic_failure(&url, &err).await;
+                    tcp_attempt.await
+                }
+            },
+            result = &mut tcp_attempt => match result {
+                Ok(response) => {
+                    debug!("The TCP request to {url} finished first, dropping the HTTP/3 request");
+                    Ok(response)
+                }
+                Err(tcp_error) => match h3_attempt.await {
+                    Ok(response) => {
+                        self.record_quic_result(&url, true).await;
+                        Ok(response)
+                    }
+                    Err(err) => {
+                        self.record_quic_failure(&url, &err).await;
+                        Err(tcp_error)
+                    }
+                },
+            },
+        }
+    }
+
+    /// Sends the request over HTTP/3, retrying it over TCP if the QUIC connection couldn't be established.
+    async fn execute_h3_with_fallback(
+        &self,
+        clients: &Clients,
+        quic_client: Option<&QuicClient>,
+        endpoint: Option<&Authority>,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ImpitError> {
+        let url = prepared.url.clone();
+        match self
+            .execute_h3_request(clients, quic_client, endpoint, prepared, timeout)
+            .await
+        {
+            Ok(response) => {
+                self.record_quic_result(&url, true).await;
+                Ok(response)
+            }
+            Err(err) if err.is_connect_failure() && prepared.body.is_sendable() => {
+                debug!("HTTP/3 connection for {url} failed, retrying over TCP");
+                self.record_quic_failure(&url, &err).await;
+                self.execute_tcp_request(clients, prepared, timeout).await
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Sends the request over the QUIC connection to the origin if it's established within the head start,
+    /// or over TCP otherwise, so the request is sent only once.
+    ///
+    /// After the head start, the QUIC handshake goes on while the request is sent over TCP. The request is only sent
+    /// over HTTP/3 if the TCP connection fails, i.e. if the request wasn't sent yet.
+    async fn race_quic_connection(
+        &self,
+        clients: &Clients,
+        quic_client: &QuicClient,
+        endpoint: Option<&Authority>,
+        prepared: &mut PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ImpitError> {
+        let url = prepared.url.clone();
+        let connecting = quic_client.preconnect(&url, endpoint);
+        tokio::pin!(connecting);
 
+        let head_start = tokio::select! {
+            result = &mut connecting => Some(result),
+            _ = tokio::time::sleep(self.config.quic_head_start) => None,
+        };
+        let (handshake, tcp_error) = match head_start {
+            Some(handshake) => (handshake, None),
+            None => {
                 debug!(
-                    "Primary request to {url} failed with {primary_error}, retrying with vanilla client"
+                    "The QUIC connection to {url} is still pending after {:?}, sending the request over TCP",
+                    self.config.quic_head_start
                 );
-                match self
-                    .execute_request(vanilla_client, &mut prepared, timeout, false)
-                    .await
-                {
-                    Ok(resp) => resp,
-                    Err(_) => return Err(primary_error),
+                let mut handshake = None;
+                let tcp_result = {
+                    let tcp_attempt = self.execute_tcp_request(clients, prepared, timeout);
+                    tokio::pin!(tcp_attempt);
+                    loop {
+                        tokio::select! {
+                            result = &mut tcp_attempt => break result,
+                            result = &mut connecting, if handshake.is_none() => handshake = Some(result),
+                        }
+                    }
+                };
+
+                match tcp_result {
+                    // The request wasn't sent, so it can still go over HTTP/3.
+                    Err(err) if err.is_connect_error() => {
+                        debug!("TCP connection to {url} failed, waiting for the QUIC connection");
+                        let handshake = match handshake {
+                            Some(handshake) => handshake,
+                            None => connecting.await,
+                        };
+                        (handshake, Some(err))
+                    }
+                    result => {
+                        match handshake {
+                            Some(Ok(())) => self.record_quic_result(&url, true).await,
+                            Some(Err(err)) => self.record_quic_failure(&url, &err).await,
+                            None => {}
+                        }
+                        return result;
+                    }
                 }
             }
         };
 
-        if !h3 {
-            let engine_guard = self.h3_engine.read().await;
-            if let Some(h3_engine) = engine_guard.as_ref() {
-                h3_engine.set_h3_support(&host, false).await;
-
-                if let Some(alt_svc) = response.headers().get("Alt-Svc") {
-                    if let Ok(alt_svc_str) = alt_svc.to_str() {
-                        if alt_svc_str.contains("h3") {
-                            debug!(
-                                "{host} supports HTTP/3 (alt-svc header), adding to Alt-Svc cache"
-                            );
-                            h3_engine.set_h3_support(&host, true).await;
-                        }
+        match handshake {
+            Ok(()) => {
+                self.execute_h3_with_fallback(
+                    clients,
+                    Some(quic_client),
+                    endpoint,
+                    prepared,
+                    timeout,
+                )
+                .await
+            }
+            Err(err) => {
+                self.record_quic_failure(&url, &err).await;
+                match tcp_error {
+                    Some(tcp_error) => Err(tcp_error),
+                    None => {
+                        debug!("QUIC connection to {url} failed, sending the request over TCP");
+                        self.execute_tcp_request(clients, prepared, timeout).await
                     }
                 }
             }
         }
+    }
 
+    /// Sends the request through the middleware chain.
+    #[allow(clippy::too_many_arguments)]
+    async fn send(
+        &self,
+        request: ImpitRequest,
+        referrer: Option<Referrer>,
+        destination: RequestDestination,
+        timeout: Option<Duration>,
+        http3_prior_knowledge: Option<bool>,
+        rules: &RouteRules,
+        retry: &RetryPolicy,
+        redirect: &RedirectBehavior,
+    ) -> Result<Response, ImpitError> {
+        let url = request.url.clone();
+        let middleware = &self.config.middleware;
+
+        let result = async {
+            let response = self
+                .follow_redirects(
+                    request,
+                    referrer,
+                    destination,
+                    timeout,
+                    http3_prior_knowledge,
+                    rules,
+                    retry,
+                    redirect,
+                )
+                .await?;
+            middleware.on_response(&response).await?;
+            Ok::<_, ImpitError>(response)
+        }
+        .await;
+
+        if let Err(err) = &result {
+            middleware.on_error(&url, err).await;
+        }
+        result
+    }
+
+    /// Sends the request, following the redirects according to `redirect`.
+    #[allow(clippy::too_many_arguments)]
+    async fn follow_redirects(
+        &self,
+        mut request: ImpitRequest,
+        referrer: Option<Referrer>,
+        destination: RequestDestination,
+        timeout: Option<Duration>,
+        http3_prior_knowledge: Option<bool>,
+        rules: &RouteRules,
+        retry: &RetryPolicy,
+        redirect: &RedirectBehavior,
+    ) -> Result<Response, ImpitError> {
+        let mut history = Vec::new();
+        let mut chain = RedirectChain::new(&request, referrer, referer_position(&self.config));
+        let mut response = loop {
+            let hop = ImpitRequest {
+                url: request.url.clone(),
+                method: request.method.clone(),
+                headers: request.headers.clone(),
+                body: request.body.try_clone().unwrap_or(ImpitBody::Consumed),
+            };
+            // HTTP/3 can only be used for the `https` hops.
+            let prior_knowledge = http3_prior_knowledge.map(|pk| pk && hop.url.scheme() == "https");
+            let started = Instant::now();
+            let response = self
+                .dispatch_with_client_hints(
+                    std::mem::replace(&mut request, hop),
+                    destination,
+                    timeout,
+                    prior_knowledge,
+                    rules,
+                    retry,
+                    redirect,
+                )
+                .await?;
+            let elapsed = started.elapsed();
+
+            if matches!(redirect, RedirectBehavior::ManualRedirect) {
+                break response;
+            }
+            let Some(target) = redirect::redirect_target(&request, &response) else {
+                break response;
+            };
+            let target = match redirect {
+                RedirectBehavior::Custom(policy) => {
+                    let action = policy.decide(&RedirectAttempt {
+                        request: &request,
+                        response: &response,
+                        target: &target,
+                        previous: &history,
+                    });
+                    match action {
+                        RedirectAction::Follow => target,
+                        RedirectAction::FollowTo(url)
+                            if matches!(url.scheme(), "http" | "https") =>
+                        {
+                            url
+                        }
+                        RedirectAction::FollowTo(url) => {
+                            return Err(ImpitError::UrlProtocolError(url.scheme().to_string()))
+                        }
+                        RedirectAction::Stop => break response,
+                        RedirectAction::Error(reason) => {
+                            return Err(ImpitError::RedirectRejected(reason))
+                        }
+                    }
+                }
+                _ => target,
+            };
+
+            let method = request.method.clone();
+            let Some(next) = chain.next_request(request, &response, target) else {
+                break response;
+            };
+            let max = redirect.max_redirects();
+            if history.len() >= max {
+                return Err(ImpitError::TooManyRedirects(Some(max)));
+            }
+
+            debug!("Following the redirect to {}", next.url);
+            history.push(RedirectHop::new(method, &response, elapsed));
+            // Dropping the response releases its concurrency slots before the next hop is sent.
+            drop(response);
+            request = next;
+        };
+
+        response.extensions_mut().insert(RedirectHistory(history));
         Ok(response)
     }
 
+    /// Sends the request with the client hints its origin asked for.
+    ///
+    /// Like Chrome, a navigation is sent once more if the response lists a hint in its `Critical-CH` header
+    /// that the request lacked, unless its body is streamed.
+    #[allow(clippy::too_many_arguments)]
+    async fn dispatch_with_client_hints(
+        &self,
+        mut request: ImpitRequest,
+        destination: RequestDestination,
+        timeout: Option<Duration>,
+        http3_prior_knowledge: Option<bool>,
+        rules: &RouteRules,
+        retry: &RetryPolicy,
+        redirect: &RedirectBehavior,
+    ) -> Result<Response, ImpitError> {
+        self.client_hints.apply(&mut request);
+        let (url, method, headers) = (
+            request.url.clone(),
+            request.method.clone(),
+            request.headers.clone(),
+        );
+        let body = request.body.try_clone();
+
+        let response = self
+            .dispatch(
+                request,
+                timeout,
+                http3_prior_knowledge,
+                rules,
+                retry,
+                redirect,
+            )
+            .await?;
+        if !self.client_hints.record(destination, &headers, &response) {
+            return Ok(response);
+        }
+        let Some(body) = body else {
+            return Ok(response);
+        };
+
+        debug!("Sending the request to {url} again with the critical client hints");
+        drop(response);
+        let mut request = ImpitRequest {
+            url,
+            method,
+            headers,
+            body,
+        };
+        self.client_hints.apply(&mut request);
+        self.dispatch(
+            request,
+            timeout,
+            http3_prior_knowledge,
+            rules,
+            retry,
+            redirect,
+        )
+        .await
+    }
+
+    /// Sends the request, retrying it according to `retry`.
+    ///
+    /// Every attempt is prepared by the middleware first, and sent with the route picked for the prepared URL.
+    async fn dispatch(
+        &self,
+        mut request: ImpitRequest,
+        timeout: Option<Duration>,
+        http3_prior_knowledge: Option<bool>,
+        rules: &RouteRules,
+        retry: &RetryPolicy,
+        redirect: &RedirectBehavior,
+    ) -> Result<Response, ImpitError> {
+        let http3_prior_knowledge = http3_prior_knowledge.unwrap_or(false);
+        if http3_prior_knowledge && self.config.max_http_version < Version::HTTP_3 {
+            return Err(ImpitError::Http3Disabled);
+        }
+
+        let mut attempt = 1;
+        let (mut response, slots, url, local_address) = loop {
+            let body = match request.body.try_clone() {
+                Some(body) => body,
+                // A streamed body can only be sent once.
+                None => std::mem::replace(&mut request.body, ImpitBody::Consumed),
+            };
+            let mut attempt_request = ImpitRequest {
+                url: request.url.clone(),
+                method: request.method.clone(),
+                headers: request.headers.clone(),
+                body,
+            };
+            self.config
+                .middleware
+                .on_request(&mut attempt_request)
+                .await?;
+
+            let url = attempt_request.url.to_string();
+            let host = attempt_request
+                .url
+                .host_str()
+                .unwrap_or_default()
+                .to_string();
+            let route = self.route(&attempt_request.url, rules);
+
+            // An open circuit fails the request right away, without waiting in the queue first.
+            let admission = self.circuits.admit(&host)?;
+            // The slots are taken for every attempt, so that no slot is held during the backoff.
+            let slots = self
+                .limiter
+                .acquire(&attempt_request.url, &self.config.proxy_url)
+                .await?;
+
+            let (clients, local_address) = self.clients_for(&host, &route).await?;
+
+            let quic_client = clients
+                .quic
+                .as_ref()
+                .filter(|quic_client| !quic_client.is_relay_unavailable());
+            if http3_prior_knowledge && clients.h3.is_none() && quic_client.is_none() {
+                return Err(ImpitError::Http3ProxyUnsupported(
+                    self.config.proxy_url.clone(),
+                ));
+            }
+
+            let port = attempt_request.url.port_or_known_default().unwrap_or(443);
+            let h3_endpoint = if http3_prior_knowledge {
+                None
+            } else {
+                self.h3_endpoint(&clients, &attempt_request.url).await
+            };
+            let mut h3 = http3_prior_knowledge || h3_endpoint.is_some();
+
+            // Alternative endpoints on a different host or port can only be reached with our own QUIC client.
+            // The explicit connect-to rules take precedence over them.
+            let alternative = h3_endpoint.filter(|endpoint| {
+                route.connect_to.is_none() && (endpoint.host != host || endpoint.port != port)
+            });
+            let use_quic_client = clients.h3.is_none() || alternative.is_some();
+
+            let header_names = attempt_request
+                .headers
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect();
+            // Only the headers repeated before the middleware ran were appended on purpose.
+            let mut header_map =
+                to_header_map(attempt_request.headers, &repeated_names(&request.headers))?;
+            // Plain `http` requests always go over HTTP/1.1, where the browsers send `Host` first.
+            // `hyper` would append it after the other headers otherwise.
+            if attempt_request.url.scheme() == "http" && !h3 {
+                header_map = with_host_header(header_map, &attempt_request.url);
+            }
+
+            // `TRACE` is only sent by `Impit::trace`, a middleware can't switch to it.
+            let method = if attempt_request.method == Method::TRACE.as_str()
+                && request.method == attempt_request.method
+            {
+                Method::TRACE
+            } else {
+                parse_method(&attempt_request.method)?
+            };
+
+            let mut prepared = PreparedRequest {
+                method,
+                url: attempt_request.url,
+                headers: header_map,
+                header_names,
+                body: attempt_request.body,
+                max_redirects: Some(redirect.max_redirects()),
+            };
+
+            let quic_client = quic_client.filter(|_| use_quic_client);
+            // The requests that need our QUIC client go over TCP if it's unavailable, as it can't stream the request bodies.
+            if h3
+                && use_quic_client
+                && !http3_prior_knowledge
+                && (quic_client.is_none() || prepared.body.to_bytes().is_none())
+            {
+                debug!("{url} can't be sent over HTTP/3, falling back to TCP");
+                h3 = false;
+            }
+
+            let result = if !h3 {
+                debug!("{url} doesn't seem to have HTTP3 support");
+                self.execute_tcp_request(&clients, &mut prepared, timeout)
+                    .await
+            } else if http3_prior_knowledge {
+                debug!("Using QUIC for request to {url}");
+                self.execute_h3_request(
+                    &clients,
+                    quic_client,
+                    alternative.as_ref(),
+                    &mut prepared,
+                    timeout,
+                )
+                .await
+                .map_err(ImpitError::from)
+            } else {
+                self.race_h3(
+                    &clients,
+                    quic_client,
+                    alternative.as_ref(),
+                    &mut prepared,
+                    timeout,
+                )
+                .await
+            };
+            admission.record(&result);
+
+            // Streamed bodies that have already been sent can't be replayed.
+            let delay = retry
+                .retry_delay(attempt, &prepared.method, &result)
+                .filter(|_| request.body.is_sendable());
+            let Some(delay) = delay else {
+                break (result?, slots, prepared.url, local_address);
+            };
+
+            debug!("Attempt {attempt} of the request to {url} failed, retrying in {delay:?}");
+            drop(slots);
+            attempt += 1;
+            tokio::time::sleep(delay).await;
+        };
+
+        self.record_alt_svc(&url, &response).await;
+
+        // The concurrency slots are released once the response (and its body) is dropped.
+        if let Some(slots) = slots {
+            response.extensions_mut().insert(slots);
+        }
+
+        Ok(with_source_address(response, local_address))
+    }
+
+    /// Picks the route for a request to `url` from the connect-to rules and the server name in `rules`.
+    fn route(&self, url: &Url, rules: &RouteRules) -> Route {
+        let mut route = Route::for_url(url, &rules.connect_to, rules.server_name.clone());
+        // `reqwest` resolves the hosts without the port, so the clients need to know it.
+        let (target_host, target_port) = route.target(
+            &normalize_host(url.host_str().unwrap_or_default()),
+            url.port_or_known_default().unwrap_or(443),
+        );
+        if self.resolver.has_port_overrides(&target_host) {
+            route.resolve_port = Some((target_host.clone(), target_port));
+        }
+        if matches!(route.server_name, ServerName::Custom(_)) {
+            route.server_name_target = Some((
+                normalize_host(url.host_str().unwrap_or_default()),
+                target_host,
+                target_port,
+            ));
+        }
+        route
+    }
+
     async fn make_request(
         &self,
         method: Method,
@@ -563,8 +1890,22 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         let url = self.parse_url(url)?;
         let request_options = options.unwrap_or_default();
 
+        // The request-specific connect-to rules are checked first.
+        let rules = RouteRules {
+            connect_to: request_options
+                .connect_to
+                .into_iter()
+                .chain(self.config.connect_to.iter().cloned())
+                .collect(),
+            server_name: request_options
+                .server_name
+                .unwrap_or_else(|| self.config.server_name.clone()),
+        };
+
         let headers = request_options.headers;
-        let request = self.build_request(method, url, body, headers);
+        let destination = request_options.destination;
+        let mut request = self.build_request(method, url, body, headers, destination);
+        let referrer = self.navigation.apply(&mut request, destination);
 
         let timeout = match request_options.timeout {
             None => None,
@@ -574,8 +1915,50 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
             Some(Some(d)) => Some(d),
         };
         let http3_prior_knowledge = request_options.http3_prior_knowledge;
-        self.send(request, timeout, Some(http3_prior_knowledge))
-            .await
+        let retry = request_options.retry.as_ref().unwrap_or(&self.config.retry);
+        let redirect = request_options
+            .redirect
+            .as_ref()
+            .unwrap_or(&self.config.redirect);
+        let response = self
+            .send(
+                request,
+                referrer,
+                destination,
+                timeout,
+                Some(http3_prior_knowledge),
+                &rules,
+                retry,
+                redirect,
+            )
+            .await?;
+        // Only the top-level navigations replace the current document.
+        if destination == RequestDestination::Document {
+            self.navigation.visit(&response);
+        }
+        Ok(response)
+    }
+
+    /// Makes a request with an arbitrary method, e.g. `PROPFIND`, `REPORT`, `PURGE` or `QUERY`.
+    ///
+    /// The `method` has to be a valid HTTP token. The standard methods (`GET`, `POST`, ...) are matched
+    /// case-insensitively, the other methods are sent exactly as they are passed.
+    /// Returns [`ImpitError::InvalidMethod`] for invalid methods, and for `CONNECT`, `TRACE` and `TRACK`,
+    /// which the browsers don't send either. Use [`Impit::trace`] for `TRACE` requests.
+    ///
+    /// The `url` parameter should be a valid URL.
+    /// Additional options like `headers`, `timeout` or HTTP/3 usage can be passed via the `RequestOptions` struct.
+    ///
+    /// If the request is successful, the `reqwest::Response` struct is returned.
+    pub async fn request(
+        &self,
+        method: &str,
+        url: String,
+        body: Option<ImpitBody>,
+        options: Option<RequestOptions>,
+    ) -> Result<Response, ImpitError> {
+        let method = parse_method(method)?;
+        self.make_request(method, url, body, options).await
     }
 
     /// Makes a `GET` request to the specified URL.
@@ -705,3 +2088,195 @@ impl<CookieStoreImpl: CookieStore + 'static> Impit<CookieStoreImpl> {
         }
     }
 }
+
+/// Where the fingerprint sends the automatic `Referer` header.
+fn referer_position<CookieStoreImpl: CookieStore + 'static>(
+    config: &ImpitBuilder<CookieStoreImpl>,
+) -> HeaderPosition {
+    config
+        .fingerprint
+        .as_ref()
+        .map(|fp| fp.referer_position.clone())
+        .unwrap_or_default()
+}
+
+fn with_source_address(mut response: Response, local_address: Option<IpAddr>) -> Response {
+    if let Some(local_address) = local_address {
+        response
+            .extensions_mut()
+            .insert(SourceAddress(local_address));
+    }
+
+    response
+}
+
+/// Replaces the URL of the response, e.g. the one rewritten for a custom server name (see [`Route::tcp_url`]).
+fn with_url(response: Response, url: &Url) -> Response {
+    let (parts, body) = http::Response::<reqwest::Body>::from(response).into_parts();
+
+    let mut builder = http::Response::builder()
+        .status(parts.status)
+        .version(parts.version);
+    if let Some(headers) = builder.headers_mut() {
+        *headers = parts.headers;
+    }
+    if let Some(extensions) = builder.extensions_mut() {
+        *extensions = parts.extensions;
+    }
+
+    Response::from(
+        builder
+            .url(url.clone())
+            .body(body)
+            .expect("the parts come from a valid response"),
+    )
+}
+
+/// Puts the `Host` header for `url` in front of the other headers, unless it is set already.
+fn with_host_header(headers: HeaderMap, url: &Url) -> HeaderMap {
+    if headers.contains_key(HOST) {
+        return headers;
+    }
+    let Some(host) = url.host_str() else {
+        return headers;
+    };
+    let host = match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    let Ok(host) = HeaderValue::from_str(&host) else {
+        return headers;
+    };
+
+    let mut ordered = HeaderMap::with_capacity(headers.len() + 1);
+    ordered.insert(HOST, host);
+    // `HeaderMap` keeps the insertion order of the distinct header names.
+    ordered.extend(headers);
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use reqwest::cookie::Jar;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Answers every request with `response` and returns the server URL with the number of requests received.
+    async fn serve(response: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    while !head.ends_with(b"\r\n\r\n") {
+                        let Ok(byte) = stream.read_u8().await else {
+                            return;
+                        };
+                        head.push(byte);
+                    }
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn client(policy: RedirectPolicy) -> Impit<Jar> {
+        Impit::<Jar>::builder()
+            .with_redirect(RedirectBehavior::Custom(policy))
+            .build()
+            .unwrap()
+    }
+
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    const REDIRECT_LOOP: &str =
+        "HTTP/1.1 302 Found\r\nLocation: /again\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    #[tokio::test]
+    async fn test_custom_redirect_policy_is_capped() {
+        let (url, requests) = serve(REDIRECT_LOOP).await;
+
+        let result = client(RedirectPolicy::new(|_| RedirectAction::Follow))
+            .get(url, None, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ImpitError::TooManyRedirects(Some(MAX_POLICY_REDIRECTS)))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), MAX_POLICY_REDIRECTS + 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_redirect_to_unsupported_scheme_fails() {
+        let (url, requests) = serve(REDIRECT_LOOP).await;
+
+        let result = client(RedirectPolicy::new(|_| {
+            RedirectAction::FollowTo(Url::parse("ftp://example.com/file").unwrap())
+        }))
+        .get(url, None, None)
+        .await;
+        assert!(matches!(
+            result,
+            Err(ImpitError::UrlProtocolError(scheme)) if scheme == "ftp"
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_is_sent_once_while_quic_connects() {
+        let (url, requests) = serve(OK).await;
+        let origin = Url::parse(&url).unwrap();
+        // The QUIC endpoint never answers, so the handshake doesn't finish before the head start is over.
+        let quic_endpoint = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let expires = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600;
+        let cache = H3Cache::new();
+        cache
+            .import(
+                format!(
+                    "impit-h3-cache 1\n127.0.0.1:{} {} {expires}\n",
+                    origin.port().unwrap(),
+                    quic_endpoint.local_addr().unwrap()
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        let impit = Impit::<Jar>::builder()
+            .with_http3()
+            .with_h3_cache(cache)
+            .with_quic_head_start(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            impit.post(url, Some("data".into()), None),
+        )
+        .await
+        .expect("the request waited for the QUIC handshake")
+        .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -57,18 +57,49 @@
 //! This is because `impit` uses unstable features of `reqwest` (namely `http3` support), which are not available in the stable version of the library.
 
 #![deny(unused_crate_dependencies)]
+mod client_hints;
+mod http1;
 mod http_headers;
+mod quic;
 mod response_parsing;
 mod tls;
 
-pub(crate) mod http3;
-
 /// Main module that contains the `Impit` struct and its methods.
 pub mod impit;
 
 /// Customizing request options.
 pub mod request;
 
+/// Following the redirects and recording the redirect chain.
+pub mod redirect;
+
+/// Browser-like `Referer` and `Sec-Fetch-Site` headers.
+pub mod navigation;
+
+/// Binding the outgoing connections to local addresses.
+pub mod local_address;
+
+/// Configuring the DNS resolution.
+pub mod dns;
+
+/// Routing the connections to other hosts and overriding the TLS server name.
+pub mod routing;
+
+/// Discovering and caching the HTTP/3 support of the origins.
+pub mod http3;
+
+/// Retrying the failed requests.
+pub mod retry;
+
+/// Limiting the request rate and concurrency.
+pub mod limits;
+
+/// Failing fast for the hosts that keep failing.
+pub mod circuit_breaker;
+
+/// Hooking into the requests and responses.
+pub mod middleware;
+
 /// Errors and error handling.
 pub mod errors;
 
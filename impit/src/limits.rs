@@ -0,0 +1,542 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+use url::Url;
+
+use crate::errors::ImpitError;
+
+/// Maximum number of keys (e.g. hosts) a limit keeps track of, before the idle ones are forgotten.
+const MAX_TRACKED_KEYS: usize = 1024;
+
+/// What the requests are grouped by when counting them towards a [`RateLimit`] or a [`ConcurrencyLimit`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LimitKey {
+    /// Every hostname has its own limit.
+    #[default]
+    Host,
+    /// The hosts under the same registrable domain (e.g. `www.example.co.uk` and `api.example.co.uk`) share a limit.
+    ///
+    /// The registrable domains are looked up in the Public Suffix List.
+    RegistrableDomain,
+    /// All the requests sent through the same proxy (or without a proxy) share a limit.
+    Proxy,
+}
+
+impl LimitKey {
+    fn key(&self, url: &Url, proxy_url: &str) -> String {
+        let host = url.host_str().unwrap_or_default();
+
+        match self {
+            LimitKey::Host => host.to_string(),
+            LimitKey::RegistrableDomain => registrable_domain(host).to_string(),
+            LimitKey::Proxy => proxy_url.to_string(),
+        }
+    }
+}
+
+/// Returns the registrable domain of `host`, or the host itself for IP addresses and public suffixes.
+pub(crate) fn registrable_domain(host: &str) -> &str {
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return host;
+    }
+
+    psl::domain_str(host).unwrap_or(host)
+}
+
+/// A token bucket limit on how many requests are started per `period`.
+///
+/// Up to `max_requests` requests can be sent right away, then the requests are spread evenly over the `period`,
+/// in the order they arrived. Retried requests count as new requests.
+///
+/// ### Example
+/// ```rust
+/// use std::time::Duration;
+/// use impit::limits::{LimitKey, RateLimit};
+///
+/// // At most 10 requests per second to every registrable domain
+/// let limit = RateLimit {
+///     key: LimitKey::RegistrableDomain,
+///     max_requests: 10,
+///     period: Duration::from_secs(1),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    pub key: LimitKey,
+    pub max_requests: u32,
+    pub period: Duration,
+}
+
+/// A limit on how many requests can be in flight at the same time. The `max_concurrent` has to be at least 1.
+///
+/// A request occupies its slot until its [`Response`](reqwest::Response) is dropped or its body is read
+/// (e.g. with `text()`), so that the slow downloads count towards the limit too. Turning the response into
+/// a byte stream releases the slot right away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcurrencyLimit {
+    pub key: LimitKey,
+    pub max_concurrent: usize,
+}
+
+impl RateLimit {
+    /// Creates a limit of `requests_per_second` requests, which can also be a fraction
+    /// (e.g. `0.5` for one request every two seconds).
+    pub fn per_second(key: LimitKey, requests_per_second: f64) -> Self {
+        let max_requests = requests_per_second.floor().max(1.0);
+
+        RateLimit {
+            key,
+            max_requests: max_requests as u32,
+            period: Duration::try_from_secs_f64(max_requests / requests_per_second)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+
+    fn capacity(&self) -> f64 {
+        f64::from(self.max_requests.max(1))
+    }
+
+    /// Returns how many requests are allowed per second.
+    fn refill_rate(&self) -> f64 {
+        self.capacity() / self.period.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// The tokens can go negative, as every request takes its token (and its place in the queue) right away
+/// and then waits until the token would have been refilled.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let refilled = now.duration_since(self.updated).as_secs_f64() * limit.refill_rate();
+        self.tokens = (self.tokens + refilled).min(limit.capacity());
+        self.updated = now;
+    }
+
+    /// Returns how long the next request has to wait for its token.
+    fn wait(&self, limit: &RateLimit) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_secs_f64((1.0 - self.tokens) / limit.refill_rate())
+    }
+}
+
+/// The concurrency slots held by a request, released when dropped.
+#[derive(Debug)]
+pub(crate) struct Slots {
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+/// Enforces the rate and concurrency limits of an [`Impit`](crate::impit::Impit) instance.
+pub(crate) struct Limiter {
+    rate_limits: Vec<RateLimit>,
+    concurrency_limits: Vec<ConcurrencyLimit>,
+    queue_timeout: Option<Duration>,
+    /// The token buckets by the index of the rate limit and the key.
+    buckets: Mutex<HashMap<(usize, String), TokenBucket>>,
+    /// The semaphores by the index of the concurrency limit and the key.
+    semaphores: Mutex<HashMap<(usize, String), Arc<Semaphore>>>,
+}
+
+impl Limiter {
+    /// Creates the limiter, failing if a concurrency limit allows no requests at all.
+    pub(crate) fn new(
+        rate_limits: Vec<RateLimit>,
+        concurrency_limits: Vec<ConcurrencyLimit>,
+        queue_timeout: Option<Duration>,
+    ) -> Result<Self, ImpitError> {
+        if concurrency_limits
+            .iter()
+            .any(|limit| limit.max_concurrent == 0)
+        {
+            return Err(ImpitError::InvalidLimits(
+                "a concurrency limit has to allow at least one request".to_string(),
+            ));
+        }
+
+        Ok(Limiter {
+            rate_limits,
+            concurrency_limits,
+            queue_timeout,
+            buckets: Mutex::new(HashMap::new()),
+            semaphores: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.queue_timeout
+            .and_then(|timeout| Instant::now().checked_add(timeout))
+    }
+
+    fn queue_timeout_error(&self) -> ImpitError {
+        ImpitError::QueueTimeout(self.queue_timeout.unwrap_or_default().as_millis())
+    }
+
+    /// Waits until a request to `url` can be started without exceeding any of the limits, and takes its
+    /// concurrency slots.
+    ///
+    /// The rate limits are waited for first, so that a request doesn't hold any slot while it waits for its tokens.
+    /// The queue timeout applies to the whole wait. Returns `None` for the slots if there are no concurrency limits.
+    pub(crate) async fn acquire(
+        &self,
+        url: &Url,
+        proxy_url: &str,
+    ) -> Result<Option<Arc<Slots>>, ImpitError> {
+        let deadline = self.deadline();
+        self.acquire_tokens(url, proxy_url, deadline).await?;
+        self.acquire_slots(url, proxy_url, deadline).await
+    }
+
+    /// Waits for a concurrency slot for a request to `url` in every matching limit, until the `deadline`.
+    ///
+    /// Returns `None` if there are no concurrency limits.
+    async fn acquire_slots(
+        &self,
+        url: &Url,
+        proxy_url: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Arc<Slots>>, ImpitError> {
+        if self.concurrency_limits.is_empty() {
+            return Ok(None);
+        }
+
+        let mut permits = Vec::with_capacity(self.concurrency_limits.len());
+
+        // The semaphores are always acquired in the same order, so that two requests never wait for each other.
+        for (index, limit) in self.concurrency_limits.iter().enumerate() {
+            let semaphore = {
+                let mut semaphores = self.semaphores.lock().unwrap();
+                if semaphores.len() >= MAX_TRACKED_KEYS {
+                    semaphores.retain(|(index, _), semaphore| {
+                        semaphore.available_permits()
+                            < self.concurrency_limits[*index].max_concurrent
+                    });
+                }
+
+                semaphores
+                    .entry((index, limit.key.key(url, proxy_url)))
+                    .or_insert_with(|| Arc::new(Semaphore::new(limit.max_concurrent)))
+                    .clone()
+            };
+
+            let permit = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, semaphore.acquire_owned())
+                    .await
+                    .map_err(|_| self.queue_timeout_error())?,
+                None => semaphore.acquire_owned().await,
+            };
+            permits.push(permit.expect("the limit semaphores are never closed"));
+        }
+
+        Ok(Some(Arc::new(Slots { _permits: permits })))
+    }
+
+    /// Waits until a request to `url` can be started without exceeding any of the rate limits.
+    ///
+    /// The tokens of all the matching limits are taken at once, so the requests are started in the order
+    /// they called this. A request that would wait past the `deadline` fails without taking any tokens.
+    async fn acquire_tokens(
+        &self,
+        url: &Url,
+        proxy_url: &str,
+        deadline: Option<Instant>,
+    ) -> Result<(), ImpitError> {
+        if self.rate_limits.is_empty() {
+            return Ok(());
+        }
+
+        let wake_up = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let now = Instant::now();
+            if buckets.len() >= MAX_TRACKED_KEYS {
+                // The full buckets behave just like the new ones.
+                buckets.retain(|(index, _), bucket| {
+                    let limit = &self.rate_limits[*index];
+                    bucket.refill(limit, now);
+                    bucket.tokens < limit.capacity()
+                });
+            }
+
+            let keys: Vec<(usize, String)> = self
+                .rate_limits
+                .iter()
+                .enumerate()
+                .map(|(index, limit)| (index, limit.key.key(url, proxy_url)))
+                .collect();
+
+            let mut wait = Duration::ZERO;
+            for key in &keys {
+                let limit = &self.rate_limits[key.0];
+                let bucket = buckets.entry(key.clone()).or_insert(TokenBucket {
+                    tokens: limit.capacity(),
+                    updated: now,
+                });
+                bucket.refill(limit, now);
+                wait = wait.max(bucket.wait(limit));
+            }
+
+            let wake_up = now + wait;
+            if deadline.is_some_and(|deadline| wake_up > deadline) {
+                return Err(self.queue_timeout_error());
+            }
+
+            for key in &keys {
+                if let Some(bucket) = buckets.get_mut(key) {
+                    bucket.tokens -= 1.0;
+                }
+            }
+            wake_up
+        };
+
+        tokio::time::sleep_until(wake_up).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_keys() {
+        let url = Url::parse("https://api.example.co.uk/path").unwrap();
+
+        assert_eq!(LimitKey::Host.key(&url, ""), "api.example.co.uk");
+        assert_eq!(LimitKey::RegistrableDomain.key(&url, ""), "example.co.uk");
+        assert_eq!(
+            LimitKey::Proxy.key(&url, "http://proxy:8080"),
+            "http://proxy:8080"
+        );
+        assert_eq!(registrable_domain("127.0.0.1"), "127.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spreads_requests() {
+        let limit = RateLimit {
+            key: LimitKey::Host,
+            max_requests: 1,
+            period: Duration::from_millis(100),
+        };
+        let url = Url::parse("https://example.com/").unwrap();
+
+        let limiter = Limiter::new(vec![limit.clone()], vec![], None).unwrap();
+        let start = Instant::now();
+        limiter
+            .acquire_tokens(&url, "", limiter.deadline())
+            .await
+            .unwrap();
+        limiter
+            .acquire_tokens(&url, "", limiter.deadline())
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        let limiter = Limiter::new(vec![limit], vec![], Some(Duration::from_millis(10))).unwrap();
+        limiter
+            .acquire_tokens(&url, "", limiter.deadline())
+            .await
+            .unwrap();
+        assert!(matches!(
+            limiter.acquire_tokens(&url, "", limiter.deadline()).await,
+            Err(ImpitError::QueueTimeout(10))
+        ));
+        limiter
+            .acquire_tokens(
+                &Url::parse("https://example.org/").unwrap(),
+                "",
+                limiter.deadline(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_times_out() {
+        let limiter = Limiter::new(
+            vec![],
+            vec![ConcurrencyLimit {
+                key: LimitKey::RegistrableDomain,
+                max_concurrent: 1,
+            }],
+            Some(Duration::from_millis(10)),
+        )
+        .unwrap();
+
+        let first = Url::parse("https://a.example.com/").unwrap();
+        let second = Url::parse("https://b.example.com/").unwrap();
+
+        let slots = limiter.acquire(&first, "").await.unwrap();
+        assert!(matches!(
+            limiter.acquire(&second, "").await,
+            Err(ImpitError::QueueTimeout(10))
+        ));
+
+        drop(slots);
+        assert!(limiter.acquire(&second, "").await.is_ok());
+    }
+
+    #[test]
+    fn test_zero_concurrency_is_rejected() {
+        let limit = ConcurrencyLimit {
+            key: LimitKey::Host,
+            max_concurrent: 0,
+        };
+        assert!(matches!(
+            Limiter::new(vec![], vec![limit], None),
+            Err(ImpitError::InvalidLimits(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_hold_no_slots() {
+        let limiter = Arc::new(
+            Limiter::new(
+                vec![RateLimit {
+                    key: LimitKey::Host,
+                    max_requests: 1,
+                    period: Duration::from_millis(200),
+                }],
+                vec![ConcurrencyLimit {
+                    key: LimitKey::Proxy,
+                    max_concurrent: 1,
+                }],
+                Some(Duration::from_millis(300)),
+            )
+            .unwrap(),
+        );
+        let limited = Url::parse("https://example.com/").unwrap();
+        drop(limiter.acquire(&limited, "").await.unwrap());
+
+        // The second request to the same host waits for its token, without taking the shared slot.
+        let waiting = {
+            let (limiter, limited) = (limiter.clone(), limited.clone());
+            tokio::spawn(async move { limiter.acquire(&limited, "").await })
+        };
+        tokio::task::yield_now().await;
+        let start = Instant::now();
+        let other = limiter
+            .acquire(&Url::parse("https://example.org/").unwrap(), "")
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+        drop(other);
+
+        assert!(waiting.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout_covers_the_whole_wait() {
+        let limiter = Limiter::new(
+            vec![RateLimit {
+                key: LimitKey::Host,
+                max_requests: 1,
+                period: Duration::from_millis(60),
+            }],
+            vec![ConcurrencyLimit {
+                key: LimitKey::Host,
+                max_concurrent: 1,
+            }],
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap();
+        let url = Url::parse("https://example.com/").unwrap();
+        let slots = limiter.acquire(&url, "").await.unwrap();
+
+        // The request waits 60 ms for its token, so only the rest of the timeout is left for the slot.
+        let start = Instant::now();
+        assert!(matches!(
+            limiter.acquire(&url, "").await,
+            Err(ImpitError::QueueTimeout(100))
+        ));
+        assert!(start.elapsed() < Duration::from_millis(150));
+        drop(slots);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_fifo() {
+        let limiter = Arc::new(
+            Limiter::new(
+                vec![RateLimit {
+                    key: LimitKey::Host,
+                    max_requests: 1,
+                    period: Duration::from_millis(10),
+                }],
+                vec![],
+                None,
+            )
+            .unwrap(),
+        );
+        let url = Url::parse("https://example.com/").unwrap();
+        limiter
+            .acquire_tokens(&url, "", limiter.deadline())
+            .await
+            .unwrap();
+
+        let order = Arc::new(Mutex::new(vec![]));
+        let mut tasks = vec![];
+        for request in 0..3 {
+            let (limiter, url, order) = (limiter.clone(), url.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                limiter
+                    .acquire_tokens(&url, "", limiter.deadline())
+                    .await
+                    .unwrap();
+                order.lock().unwrap().push(request);
+            }));
+            // Let the task take its place in the queue before spawning the next one.
+            tokio::task::yield_now().await;
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_requests_keep_the_tokens() {
+        let fast = RateLimit {
+            key: LimitKey::Host,
+            max_requests: 1,
+            period: Duration::from_millis(1),
+        };
+        let slow = RateLimit {
+            key: LimitKey::Proxy,
+            max_requests: 1,
+            period: Duration::from_secs(60),
+        };
+        let limiter =
+            Limiter::new(vec![fast, slow], vec![], Some(Duration::from_millis(10))).unwrap();
+
+        let url = Url::parse("https://example.com/").unwrap();
+        limiter
+            .acquire_tokens(&url, "proxy-a", limiter.deadline())
+            .await
+            .unwrap();
+        // The host token is available, but the proxy one isn't, so neither is taken.
+        assert!(matches!(
+            limiter
+                .acquire_tokens(&url, "proxy-a", limiter.deadline())
+                .await,
+            Err(ImpitError::QueueTimeout(10))
+        ));
+
+        let buckets = limiter.buckets.lock().unwrap();
+        let host_bucket = &buckets[&(0, "example.com".to_string())];
+        assert!(host_bucket.tokens > -0.5);
+    }
+}
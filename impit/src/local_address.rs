@@ -0,0 +1,317 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use rand::Rng;
+
+use crate::errors::ImpitError;
+
+/// A set of local addresses the outgoing connections can be bound to.
+///
+/// The pool consists of IP prefixes in the CIDR notation. Plain addresses are treated as prefixes
+/// with the full length (`/32` for IPv4, `/128` for IPv6).
+/// Every time an address is needed, the pool takes the next prefix (round-robin) and picks a random address inside it.
+///
+/// Note that the addresses have to be assigned to (or routed to) the machine, otherwise the connections will fail.
+/// For IPv6 prefixes on Linux, this usually means a local route for the prefix and the `net.ipv6.ip_nonlocal_bind` sysctl.
+///
+/// ### Example
+/// ```rust
+/// use impit::local_address::LocalAddressPool;
+///
+/// // Random addresses from a routed IPv6 /48 prefix
+/// let ipv6_pool = LocalAddressPool::new(["2001:db8:1234::/48"]).unwrap();
+///
+/// // Round-robin across a set of IPv4 addresses
+/// let ipv4_pool = LocalAddressPool::new(["192.0.2.10", "192.0.2.11", "192.0.2.12"]).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalAddressPool {
+    prefixes: Vec<IpPrefix>,
+}
+
+impl LocalAddressPool {
+    /// Creates a new pool from a list of IP addresses and CIDR prefixes (e.g. `"192.0.2.10"` or `"2001:db8::/48"`).
+    ///
+    /// Returns an error if any of the entries is invalid, or if the list is empty.
+    pub fn new<I, S>(entries: I) -> Result<Self, ImpitError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let prefixes = entries
+            .into_iter()
+            .map(|entry| {
+                IpPrefix::parse(entry.as_ref()).ok_or_else(|| {
+                    ImpitError::ReqwestError(format!("Invalid local address: {}", entry.as_ref()))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if prefixes.is_empty() {
+            return Err(ImpitError::ReqwestError(
+                "The local address pool cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(LocalAddressPool { prefixes })
+    }
+}
+
+impl From<Vec<IpAddr>> for LocalAddressPool {
+    fn from(addresses: Vec<IpAddr>) -> Self {
+        LocalAddressPool {
+            prefixes: addresses.into_iter().map(IpPrefix::from).collect(),
+        }
+    }
+}
+
+/// Decides how often a new address is picked from the [`LocalAddressPool`].
+///
+/// The clients are bound to their local address, so each new address means a new set of clients with new
+/// connection pools (up to 64 sets are kept for reuse). Picking a new address for every request from a large prefix
+/// therefore means a fresh TCP (and TLS) handshake for every request, and no connection reuse at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocalAddressSelection {
+    /// Every request is sent from a new address.
+    ///
+    /// This disables the connection reuse, unless the pool only has a few addresses.
+    PerRequest,
+    /// Every host gets its own address, which is then used for all the requests to this host.
+    ///
+    /// The addresses of up to 1024 hosts are remembered, the hosts get new addresses once this is exceeded.
+    PerHost,
+    /// One address is picked when the [`Impit`](crate::impit::Impit) instance is built and used for all its requests.
+    #[default]
+    PerSession,
+}
+
+/// The local address the request was sent from.
+///
+/// Available in the [`reqwest::Response::extensions`] of responses made by an [`Impit`](crate::impit::Impit) instance
+/// with a local address (or a pool of local addresses) set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceAddress(pub IpAddr);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IpPrefix {
+    network: IpAddr,
+    length: u8,
+}
+
+impl From<IpAddr> for IpPrefix {
+    fn from(network: IpAddr) -> Self {
+        IpPrefix {
+            network,
+            length: if network.is_ipv4() { 32 } else { 128 },
+        }
+    }
+}
+
+impl IpPrefix {
+    fn parse(value: &str) -> Option<Self> {
+        let (address, length) = match value.split_once('/') {
+            Some((address, length)) => (address, Some(length)),
+            None => (value, None),
+        };
+
+        let prefix = IpPrefix::from(address.trim().parse::<IpAddr>().ok()?);
+
+        match length {
+            None => Some(prefix),
+            Some(length) => {
+                let length = length.trim().parse::<u8>().ok()?;
+                (length <= prefix.length).then_some(IpPrefix { length, ..prefix })
+            }
+        }
+    }
+
+    fn random_address(&self) -> IpAddr {
+        let mut rng = rand::rng();
+
+        match self.network {
+            IpAddr::V4(network) => {
+                let host_mask = u32::MAX.checked_shr(self.length as u32).unwrap_or(0);
+                let mut host = rng.random::<u32>() & host_mask;
+                // Skip the network and broadcast addresses of the prefix.
+                while host_mask >= 0b11 && (host == 0 || host == host_mask) {
+                    host = rng.random::<u32>() & host_mask;
+                }
+
+                IpAddr::V4(Ipv4Addr::from((u32::from(network) & !host_mask) | host))
+            }
+            IpAddr::V6(network) => {
+                let host_mask = u128::MAX.checked_shr(self.length as u32).unwrap_or(0);
+                let host = rng.random::<u128>() & host_mask;
+
+                IpAddr::V6(Ipv6Addr::from((u128::from(network) & !host_mask) | host))
+            }
+        }
+    }
+}
+
+/// Maximum number of hosts whose addresses are remembered with [`LocalAddressSelection::PerHost`].
+const MAX_HOST_ADDRESSES: usize = 1024;
+
+/// Picks the local addresses for requests according to the [`LocalAddressSelection`].
+#[derive(Debug)]
+pub(crate) struct LocalAddressSelector {
+    pool: LocalAddressPool,
+    selection: LocalAddressSelection,
+    next_prefix: AtomicUsize,
+    host_addresses: Mutex<HashMap<String, IpAddr>>,
+}
+
+impl LocalAddressSelector {
+    pub(crate) fn new(pool: LocalAddressPool, selection: LocalAddressSelection) -> Self {
+        // Start at a random prefix, so that sessions don't all start from the first entry.
+        let start = rand::rng().random_range(0..pool.prefixes.len().max(1));
+
+        LocalAddressSelector {
+            pool,
+            selection,
+            next_prefix: AtomicUsize::new(start),
+            host_addresses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn selection(&self) -> LocalAddressSelection {
+        self.selection
+    }
+
+    /// Returns the local address for a request to `host`, which resolved to `target` (if known).
+    ///
+    /// Only the prefixes of the same IP family as `target` are used, unless the pool has none of them.
+    /// With [`LocalAddressSelection::PerSession`], the caller is expected to call this only once and keep the result.
+    pub(crate) fn select(&self, host: &str, target: Option<IpAddr>) -> Option<IpAddr> {
+        match self.selection {
+            LocalAddressSelection::PerHost => {
+                let mut host_addresses = self.host_addresses.lock().unwrap();
+                if let Some(address) = host_addresses.get(host) {
+                    return Some(*address);
+                }
+
+                let address = self.next(target)?;
+                // Like the derived clients, the map is simply reset once it's full.
+                if host_addresses.len() >= MAX_HOST_ADDRESSES {
+                    host_addresses.clear();
+                }
+                host_addresses.insert(host.to_string(), address);
+                Some(address)
+            }
+            LocalAddressSelection::PerRequest | LocalAddressSelection::PerSession => {
+                self.next(target)
+            }
+        }
+    }
+
+    fn next(&self, target: Option<IpAddr>) -> Option<IpAddr> {
+        let matching: Vec<&IpPrefix> = self
+            .pool
+            .prefixes
+            .iter()
+            .filter(|prefix| {
+                target.is_none_or(|target| prefix.network.is_ipv4() == target.is_ipv4())
+            })
+            .collect();
+        let prefixes = if matching.is_empty() {
+            self.pool.prefixes.iter().collect()
+        } else {
+            matching
+        };
+        if prefixes.is_empty() {
+            return None;
+        }
+
+        let index = self.next_prefix.fetch_add(1, Ordering::Relaxed) % prefixes.len();
+        Some(prefixes[index].random_address())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_parsing() {
+        assert!(LocalAddressPool::new(["192.0.2.1", "2001:db8::/48", "10.0.0.0/8"]).is_ok());
+        assert!(LocalAddressPool::new(["192.0.2.1/33"]).is_err());
+        assert!(LocalAddressPool::new(["not-an-address"]).is_err());
+        assert!(LocalAddressPool::new(Vec::<String>::new()).is_err());
+    }
+
+    #[test]
+    fn test_random_addresses_stay_in_prefix() {
+        let prefix = IpPrefix::parse("2001:db8:1234::/48").unwrap();
+        for _ in 0..100 {
+            let IpAddr::V6(address) = prefix.random_address() else {
+                panic!("expected an IPv6 address");
+            };
+            assert_eq!(address.segments()[..3], [0x2001, 0x0db8, 0x1234]);
+        }
+
+        let prefix = IpPrefix::parse("192.0.2.0/30").unwrap();
+        for _ in 0..100 {
+            let address = prefix.random_address();
+            assert!(["192.0.2.1", "192.0.2.2"].contains(&address.to_string().as_str()));
+        }
+    }
+
+    #[test]
+    fn test_round_robin_and_per_host_selection() {
+        let pool = LocalAddressPool::new(["192.0.2.1", "192.0.2.2"]).unwrap();
+
+        let selector = LocalAddressSelector::new(pool.clone(), LocalAddressSelection::PerRequest);
+        let first = selector.select("example.com", None);
+        assert_ne!(first, selector.select("example.com", None));
+        assert_eq!(first, selector.select("example.com", None));
+
+        let selector = LocalAddressSelector::new(pool, LocalAddressSelection::PerHost);
+        let address = selector.select("example.com", None);
+        assert_eq!(address, selector.select("example.com", None));
+        assert_ne!(address, selector.select("example.org", None));
+    }
+
+    #[test]
+    fn test_selection_matches_target_family() {
+        let pool = LocalAddressPool::new(["192.0.2.1", "2001:db8::1"]).unwrap();
+        let selector = LocalAddressSelector::new(pool, LocalAddressSelection::PerRequest);
+
+        let ipv4_target = Some("198.51.100.1".parse().unwrap());
+        let ipv6_target = Some("2001:db8:ffff::1".parse().unwrap());
+        for _ in 0..4 {
+            assert_eq!(
+                selector.select("example.com", ipv4_target),
+                Some("192.0.2.1".parse().unwrap())
+            );
+            assert_eq!(
+                selector.select("example.com", ipv6_target),
+                Some("2001:db8::1".parse().unwrap())
+            );
+        }
+
+        // Without a matching prefix, any address is used.
+        let pool = LocalAddressPool::new(["192.0.2.1"]).unwrap();
+        let selector = LocalAddressSelector::new(pool, LocalAddressSelection::PerRequest);
+        assert_eq!(
+            selector.select("example.com", ipv6_target),
+            Some("192.0.2.1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_per_host_addresses_are_capped() {
+        let pool = LocalAddressPool::new(["2001:db8::/48"]).unwrap();
+        let selector = LocalAddressSelector::new(pool, LocalAddressSelection::PerHost);
+
+        for i in 0..MAX_HOST_ADDRESSES + 10 {
+            selector.select(&format!("host{i}.example.com"), None);
+        }
+        assert!(selector.host_addresses.lock().unwrap().len() <= MAX_HOST_ADDRESSES);
+    }
+}
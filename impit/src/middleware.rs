@@ -0,0 +1,164 @@
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use reqwest::Response;
+use url::Url;
+
+use crate::{errors::ImpitError, request::ImpitRequest};
+
+/// A hook into the requests sent by an [`Impit`](crate::impit::Impit) instance, e.g. for signing the requests,
+/// custom authentication, logging, response validation or metrics.
+///
+/// The middleware is registered with [`ImpitBuilder::with_middleware`](crate::impit::ImpitBuilder::with_middleware),
+/// and called in the order of registration. All the hooks have a no-op default implementation.
+///
+/// [`Middleware::on_request`] is called before every attempt sent over the wire - for each redirect hop, retry
+/// and the resend with the critical client hints, so e.g. the signatures are always made for the request
+/// that's actually sent. The other hooks are called once per request.
+///
+/// ### Example
+/// ```rust
+/// use async_trait::async_trait;
+/// use impit::{errors::ImpitError, middleware::Middleware, request::ImpitRequest};
+///
+/// #[derive(Debug)]
+/// struct BearerAuth(String);
+///
+/// #[async_trait]
+/// impl Middleware for BearerAuth {
+///     async fn on_request(&self, request: &mut ImpitRequest) -> Result<(), ImpitError> {
+///         request
+///             .headers
+///             .push(("Authorization".to_string(), format!("Bearer {}", self.0)));
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called with the request (including the impersonated headers) before every attempt to send it.
+    ///
+    /// The connection is picked for the URL of the request returned by the hook.
+    /// A header pushed to `request.headers` replaces the value that's set already, unless the header was repeated
+    /// (i.e. appended) before the hook ran.
+    /// Returning an error cancels the request, the error is returned to the caller.
+    async fn on_request(&self, _request: &mut ImpitRequest) -> Result<(), ImpitError> {
+        Ok(())
+    }
+
+    /// Called with the final response (after the redirects and retries), before it's returned to the caller.
+    ///
+    /// Returning an error (e.g. for an unexpected status code) fails the request with that error.
+    async fn on_response(&self, _response: &Response) -> Result<(), ImpitError> {
+        Ok(())
+    }
+
+    /// Called when the request to `url` fails, including the errors returned by the other hooks.
+    async fn on_error(&self, _url: &Url, _error: &ImpitError) {}
+}
+
+/// The middleware registered on an [`ImpitBuilder`](crate::impit::ImpitBuilder), in the order of registration.
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareChain(Vec<Arc<dyn Middleware>>);
+
+impl fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MiddlewareChain({} middleware)", self.0.len())
+    }
+}
+
+impl MiddlewareChain {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) async fn on_request(&self, request: &mut ImpitRequest) -> Result<(), ImpitError> {
+        for middleware in &self.0 {
+            middleware.on_request(request).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn on_response(&self, response: &Response) -> Result<(), ImpitError> {
+        for middleware in &self.0 {
+            middleware.on_response(response).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn on_error(&self, url: &Url, error: &ImpitError) {
+        for middleware in &self.0 {
+            middleware.on_error(url, error).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::request::ImpitBody;
+
+    #[derive(Default)]
+    struct Recorder {
+        name: &'static str,
+        errors: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Recorder {
+        async fn on_request(&self, request: &mut ImpitRequest) -> Result<(), ImpitError> {
+            request
+                .headers
+                .push(("X-Middleware".to_string(), self.name.to_string()));
+            Ok(())
+        }
+
+        async fn on_response(&self, response: &Response) -> Result<(), ImpitError> {
+            match response.status().as_u16() {
+                403 => Err(ImpitError::HTTPStatusError(403)),
+                _ => Ok(()),
+            }
+        }
+
+        async fn on_error(&self, url: &Url, error: &ImpitError) {
+            self.errors.lock().unwrap().push(format!("{url}: {error}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain_runs_in_order() {
+        let first = Arc::new(Recorder {
+            name: "first",
+            ..Recorder::default()
+        });
+        let mut chain = MiddlewareChain::default();
+        chain.push(first.clone());
+        chain.push(Arc::new(Recorder {
+            name: "second",
+            ..Recorder::default()
+        }));
+
+        let url = Url::parse("https://example.com/").unwrap();
+        let mut request = ImpitRequest {
+            url: url.clone(),
+            body: ImpitBody::Empty,
+            headers: vec![],
+            method: "GET".to_string(),
+        };
+        chain.on_request(&mut request).await.unwrap();
+        assert_eq!(
+            request.headers,
+            vec![
+                ("X-Middleware".to_string(), "first".to_string()),
+                ("X-Middleware".to_string(), "second".to_string()),
+            ]
+        );
+
+        let response = Response::from(http::Response::builder().status(403).body("").unwrap());
+        let error = chain.on_response(&response).await.unwrap_err();
+        chain.on_error(&url, &error).await;
+        assert_eq!(first.errors.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use tokio::net::{TcpSocket, UdpSocket};
+
+/// Where the sockets opened by the [`QuicClient`](super::QuicClient) are bound to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SocketBinding {
+    pub(crate) local_address: Option<IpAddr>,
+    pub(crate) interface: Option<String>,
+}
+
+impl SocketBinding {
+    /// Returns the local address to bind a socket talking to `peer` to.
+    ///
+    /// The configured local address is only used if it's from the same address family as `peer`.
+    fn bind_address(&self, peer: SocketAddr) -> SocketAddr {
+        let ip = match self.local_address {
+            Some(local_address) if local_address.is_ipv4() == peer.is_ipv4() => local_address,
+            _ if peer.is_ipv4() => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            _ => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+
+        SocketAddr::new(ip, 0)
+    }
+
+    /// Creates a TCP socket for connecting to `peer`.
+    pub(crate) fn tcp_socket(&self, peer: SocketAddr) -> io::Result<TcpSocket> {
+        let socket = if peer.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+
+        if let Some(interface) = &self.interface {
+            bind_tcp_device(&socket, interface)?;
+        }
+        if self.local_address.is_some() {
+            socket.bind(self.bind_address(peer))?;
+        }
+
+        Ok(socket)
+    }
+
+    /// Creates a UDP socket for sending datagrams to `peer`.
+    pub(crate) async fn udp_socket(&self, peer: SocketAddr) -> io::Result<UdpSocket> {
+        let socket = UdpSocket::bind(self.bind_address(peer)).await?;
+
+        if let Some(interface) = &self.interface {
+            bind_udp_device(&socket, interface)?;
+        }
+
+        Ok(socket)
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+fn bind_tcp_device(socket: &TcpSocket, interface: &str) -> io::Result<()> {
+    socket.bind_device(Some(interface.as_bytes()))
+}
+
+#[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+fn bind_udp_device(socket: &UdpSocket, interface: &str) -> io::Result<()> {
+    socket.bind_device(Some(interface.as_bytes()))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+fn bind_tcp_device(_: &TcpSocket, interface: &str) -> io::Result<()> {
+    Err(unsupported_interface(interface))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+fn bind_udp_device(_: &UdpSocket, interface: &str) -> io::Result<()> {
+    Err(unsupported_interface(interface))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+fn unsupported_interface(interface: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("binding to the network interface `{interface}` isn't supported on this platform"),
+    )
+}
@@ -0,0 +1,537 @@
+//! HTTP/3 client with control over the QUIC sockets.
+//!
+//! `reqwest` binds its QUIC sockets directly, so HTTP/3 requests made with it would bypass any configured proxy
+//! and ignore the network interface the client is bound to.
+//! The [`QuicClient`] opens the UDP socket for every connection itself, either bound to the configured interface,
+//! or relaying the datagrams through a SOCKS5 `UDP ASSOCIATE` session. It's also used for the requests with a custom
+//! [`Route`], as `reqwest` always connects to the port from the URL and sends the URL hostname as the server name.
+
+mod binding;
+mod socks5;
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZlibDecoder, ZstdDecoder};
+use bytes::{Buf, Bytes};
+use h3::client::{RequestStream, SendRequest};
+use log::debug;
+use quinn::{crypto::rustls::QuicClientConfig, Endpoint, EndpointConfig, TokioRuntime};
+use reqwest::{
+    header::{HeaderMap, CONNECTION, CONTENT_ENCODING, CONTENT_LENGTH, HOST, TRANSFER_ENCODING},
+    Method, Response, ResponseBuilderExt, Version,
+};
+use tokio::{
+    sync::{Mutex, OnceCell},
+    task::JoinSet,
+};
+use tokio_util::io::{ReaderStream, StreamReader};
+use url::{Host, Url};
+
+use crate::{dns::Resolver, errors::ImpitError, http3::Authority, routing::Route};
+
+pub(crate) use binding::SocketBinding;
+use socks5::Socks5Error;
+pub(crate) use socks5::{Socks5Proxy, TargetAddr};
+
+/// How long to wait for the QUIC handshake before giving up on HTTP/3.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors returned by [`QuicClient::send`], split by how the caller should recover from them.
+#[derive(Debug)]
+pub(crate) enum QuicError {
+    /// The proxy can't relay UDP traffic at all. HTTP/3 should be disabled for this proxy.
+    RelayUnavailable(String),
+    /// The QUIC connection to the target host couldn't be established.
+    ConnectFailed(String),
+    /// The connection was established, but the request itself failed.
+    Request(ImpitError),
+}
+
+impl QuicError {
+    /// Whether the request failed before it was sent, so it can be safely retried over TCP.
+    pub(crate) fn is_connect_failure(&self) -> bool {
+        match self {
+            QuicError::RelayUnavailable(_) | QuicError::ConnectFailed(_) => true,
+            QuicError::Request(err) => {
+                matches!(
+                    err,
+                    ImpitError::ConnectError(_) | ImpitError::ConnectTimeout
+                )
+            }
+        }
+    }
+
+    /// Whether QUIC itself failed for the target host, i.e. the connection or the handshake couldn't be completed.
+    ///
+    /// Unlike the failed requests on working connections (or an unusable proxy relay), these mean
+    /// that HTTP/3 should not be used for the host for a while.
+    pub(crate) fn is_handshake_failure(&self) -> bool {
+        match self {
+            QuicError::RelayUnavailable(_) => false,
+            QuicError::ConnectFailed(_) => true,
+            QuicError::Request(err) => {
+                matches!(
+                    err,
+                    ImpitError::ConnectError(_) | ImpitError::ConnectTimeout
+                )
+            }
+        }
+    }
+}
+
+impl From<QuicError> for ImpitError {
+    fn from(err: QuicError) -> Self {
+        match err {
+            QuicError::RelayUnavailable(reason) | QuicError::ConnectFailed(reason) => {
+                ImpitError::ConnectError(reason)
+            }
+            QuicError::Request(err) => err,
+        }
+    }
+}
+
+struct PooledConnection {
+    send_request: SendRequest<h3_quinn::OpenStreams, Bytes>,
+    closed: Arc<AtomicBool>,
+    _endpoint: Endpoint,
+}
+
+/// Makes HTTP/3 requests over QUIC sockets it opens itself, optionally relayed through a SOCKS5 proxy.
+pub(crate) struct QuicClient {
+    client_config: quinn::ClientConfig,
+    proxy: Option<Socks5Proxy>,
+    binding: SocketBinding,
+    resolver: Arc<Resolver>,
+    route: Route,
+    /// The connections per origin. Concurrent requests to a new origin wait for the same connection attempt.
+    connections: Mutex<HashMap<String, Arc<OnceCell<PooledConnection>>>>,
+    relay_unavailable: AtomicBool,
+}
+
+impl QuicClient {
+    pub(crate) fn new(
+        tls_config: rustls::ClientConfig,
+        proxy: Option<Socks5Proxy>,
+        binding: SocketBinding,
+        resolver: Arc<Resolver>,
+        route: Route,
+    ) -> Result<Self, ImpitError> {
+        let quic_config = QuicClientConfig::try_from(tls_config).map_err(|e| {
+            ImpitError::ReqwestError(format!("Invalid TLS configuration for QUIC: {e}"))
+        })?;
+
+        Ok(QuicClient {
+            client_config: quinn::ClientConfig::new(Arc::new(quic_config)),
+            proxy,
+            binding,
+            resolver,
+            route,
+            connections: Mutex::new(HashMap::new()),
+            relay_unavailable: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether the proxy has already refused to relay UDP traffic.
+    pub(crate) fn is_relay_unavailable(&self) -> bool {
+        self.relay_unavailable.load(Ordering::Relaxed)
+    }
+
+    /// Sends the request to the origin of `url`, connecting to its alternative `endpoint` (from `Alt-Svc`) if set.
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        endpoint: Option<&Authority>,
+        headers: HeaderMap,
+        body: Bytes,
+        timeout: Duration,
+    ) -> Result<Response, QuicError> {
+        let mut send_request = self.connection(url, endpoint).await?;
+
+        let mut request = http::Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .version(Version::HTTP_3)
+            .body(())
+            .map_err(|e| QuicError::Request(ImpitError::ReqwestError(e.to_string())))?;
+
+        // Connection-specific headers are forbidden in HTTP/3, the authority is sent in the `:authority` pseudo-header.
+        // `HeaderMap::remove` moves the last header into the freed slot, so the map is rebuilt to keep the order.
+        let mut name = None;
+        for (next, value) in headers {
+            name = next.or(name);
+            match &name {
+                Some(name) if ![HOST, CONNECTION, TRANSFER_ENCODING].contains(name) => {
+                    request.headers_mut().append(name.clone(), value);
+                }
+                _ => {}
+            }
+        }
+        if !body.is_empty() && !request.headers().contains_key(CONTENT_LENGTH) {
+            request
+                .headers_mut()
+                .insert(CONTENT_LENGTH, body.len().into());
+        }
+
+        let exchange = async {
+            let mut stream = send_request.send_request(request).await?;
+            if !body.is_empty() {
+                stream.send_data(body).await?;
+            }
+            stream.finish().await?;
+
+            let response = stream.recv_response().await?;
+            Ok::<_, h3::error::StreamError>((response, stream))
+        };
+
+        let (response, stream) = match tokio::time::timeout(timeout, exchange).await {
+            Ok(Ok(exchange)) => exchange,
+            Ok(Err(err)) => {
+                return Err(QuicError::Request(ImpitError::ReqwestError(format!(
+                    "HTTP/3 request failed: {err}"
+                ))))
+            }
+            Err(_) => {
+                return Err(QuicError::Request(ImpitError::TimeoutException(Some(
+                    timeout.as_millis(),
+                ))))
+            }
+        };
+
+        let (parts, ()) = response.into_parts();
+        let mut headers = parts.headers;
+        let body = decode_body(
+            &mut headers,
+            ResponseBody {
+                stream: Box::new(stream),
+                done: false,
+            },
+        );
+
+        let mut response = http::Response::builder()
+            .status(parts.status)
+            .version(Version::HTTP_3)
+            .url(url.clone())
+            .body(body)
+            .map_err(|e| QuicError::Request(ImpitError::ReqwestError(e.to_string())))?;
+        *response.headers_mut() = headers;
+
+        Ok(Response::from(response))
+    }
+
+    /// Connects to the origin of `url` (or its alternative `endpoint`) without sending a request,
+    /// so that the next request to it is sent on the pooled connection right away.
+    pub(crate) async fn preconnect(
+        &self,
+        url: &Url,
+        endpoint: Option<&Authority>,
+    ) -> Result<(), QuicError> {
+        self.connection(url, endpoint).await.map(|_| ())
+    }
+
+    /// Returns a request handle for a live HTTP/3 connection to the origin of `url`, connecting if needed.
+    async fn connection(
+        &self,
+        url: &Url,
+        endpoint: Option<&Authority>,
+    ) -> Result<SendRequest<h3_quinn::OpenStreams, Bytes>, QuicError> {
+        let port = url.port_or_known_default().unwrap_or(443);
+        let mut key = format!("{}:{port}", url.host_str().unwrap_or_default());
+        if let Some(endpoint) = endpoint {
+            key = format!("{key} via {}:{}", endpoint.host, endpoint.port);
+        }
+
+        let connection = {
+            let mut connections = self.connections.lock().await;
+            let connection = connections.entry(key).or_default();
+            if connection
+                .get()
+                .is_some_and(|pooled| pooled.closed.load(Ordering::Relaxed))
+            {
+                *connection = Arc::default();
+            }
+            connection.clone()
+        };
+
+        // If the attempt fails, the cell stays empty and the next waiter connects again.
+        let pooled = connection
+            .get_or_try_init(|| self.connect(url, port, endpoint))
+            .await?;
+        Ok(pooled.send_request.clone())
+    }
+
+    async fn connect(
+        &self,
+        url: &Url,
+        port: u16,
+        endpoint: Option<&Authority>,
+    ) -> Result<PooledConnection, QuicError> {
+        let host = match url.host() {
+            Some(Host::Domain(domain)) => domain.to_string(),
+            Some(Host::Ipv4(ip)) => ip.to_string(),
+            Some(Host::Ipv6(ip)) => ip.to_string(),
+            None => {
+                return Err(QuicError::Request(ImpitError::UrlMissingHostnameError(
+                    url.to_string(),
+                )))
+            }
+        };
+        // The alternative services are authenticated with the origin's name, only the connection target changes.
+        let server_name = self.route.server_name(&host);
+        let (target_host, target_port) = match endpoint {
+            Some(endpoint) => (endpoint.host.clone(), endpoint.port),
+            None => self.route.target(&host, port),
+        };
+
+        let connecting = async {
+            match &self.proxy {
+                Some(proxy) => {
+                    self.connect_proxied(proxy, &target_host, target_port, server_name)
+                        .await
+                }
+                None => {
+                    let peers = self.resolve(&target_host, target_port).await?;
+                    self.connect_direct(peers, server_name).await
+                }
+            }
+        };
+        let (endpoint, connection) = match tokio::time::timeout(HANDSHAKE_TIMEOUT, connecting).await
+        {
+            Ok(result) => result?,
+            Err(_) => {
+                return Err(QuicError::ConnectFailed(
+                    "QUIC handshake timed out".to_string(),
+                ))
+            }
+        };
+
+        let (mut driver, send_request) = h3::client::new(h3_quinn::Connection::new(connection))
+            .await
+            .map_err(|e| QuicError::ConnectFailed(e.to_string()))?;
+
+        let closed = Arc::new(AtomicBool::new(false));
+        let closed_flag = closed.clone();
+        tokio::spawn(async move {
+            let reason = driver.wait_idle().await;
+            debug!("HTTP/3 connection closed: {reason}");
+            closed_flag.store(true, Ordering::Relaxed);
+        });
+
+        Ok(PooledConnection {
+            send_request,
+            closed,
+            _endpoint: endpoint,
+        })
+    }
+
+    /// Connects to `host:port` through the SOCKS5 relay.
+    async fn connect_proxied(
+        &self,
+        proxy: &Socks5Proxy,
+        host: &str,
+        port: u16,
+        server_name: &str,
+    ) -> Result<(Endpoint, quinn::Connection), QuicError> {
+        let target = if proxy.resolves_remotely() && host.parse::<IpAddr>().is_err() {
+            TargetAddr::Domain(host.to_string(), port)
+        } else {
+            TargetAddr::Ip(self.resolve(host, port).await?[0])
+        };
+
+        let peer = target.peer_addr();
+        let socket = proxy
+            .associate(target, &self.binding, &self.resolver)
+            .await
+            .map_err(|err| {
+                if matches!(
+                    err,
+                    Socks5Error::UdpAssociateRefused(_) | Socks5Error::InvalidResponse
+                ) {
+                    self.relay_unavailable.store(true, Ordering::Relaxed);
+                }
+                QuicError::RelayUnavailable(err.to_string())
+            })?;
+
+        let mut endpoint = Endpoint::new_with_abstract_socket(
+            EndpointConfig::default(),
+            None,
+            Arc::new(socket),
+            Arc::new(TokioRuntime),
+        )
+        .map_err(|e| QuicError::ConnectFailed(e.to_string()))?;
+        endpoint.set_default_client_config(self.client_config.clone());
+
+        let connection = endpoint
+            .connect(peer, server_name)
+            .map_err(|e| QuicError::ConnectFailed(e.to_string()))?
+            .await
+            .map_err(|e| QuicError::ConnectFailed(e.to_string()))?;
+
+        Ok((endpoint, connection))
+    }
+
+    /// Races the connection attempts to `peers` (in order), starting a new one every
+    /// connection attempt delay or as soon as the previous one fails, as per Happy Eyeballs v2.
+    async fn connect_direct(
+        &self,
+        peers: Vec<SocketAddr>,
+        server_name: &str,
+    ) -> Result<(Endpoint, quinn::Connection), QuicError> {
+        let attempt_delay = self.resolver.happy_eyeballs().connection_attempt_delay;
+        let mut peers = peers.into_iter().peekable();
+        let mut attempts = JoinSet::new();
+        let mut last_error = None;
+
+        loop {
+            if let Some(peer) = peers.next() {
+                attempts.spawn(connect_to(
+                    self.binding.clone(),
+                    self.client_config.clone(),
+                    peer,
+                    server_name.to_string(),
+                ));
+            }
+
+            if attempts.is_empty() {
+                return Err(QuicError::ConnectFailed(
+                    last_error.unwrap_or_else(|| "no addresses to connect to".to_string()),
+                ));
+            }
+
+            tokio::select! {
+                Some(result) = attempts.join_next() => match result {
+                    Ok(Ok(connected)) => return Ok(connected),
+                    Ok(Err(err)) => last_error = Some(err),
+                    Err(err) => last_error = Some(err.to_string()),
+                },
+                _ = tokio::time::sleep(attempt_delay), if peers.peek().is_some() => {}
+            }
+        }
+    }
+
+    /// Resolves `host` locally, with the addresses sorted for Happy Eyeballs.
+    ///
+    /// With a local address set, only the addresses from its family are returned.
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, QuicError> {
+        let ips = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => self
+                .resolver
+                .lookup_ip(host, Some(port))
+                .await
+                .unwrap_or_default(),
+        };
+
+        let peers: Vec<SocketAddr> = ips
+            .into_iter()
+            .filter(|ip| {
+                self.binding
+                    .local_address
+                    .is_none_or(|local_address| local_address.is_ipv4() == ip.is_ipv4())
+            })
+            .map(|ip| SocketAddr::new(ip, port))
+            .collect();
+
+        if peers.is_empty() {
+            return Err(QuicError::Request(ImpitError::ConnectError(format!(
+                "Failed to resolve {host}"
+            ))));
+        }
+
+        Ok(peers)
+    }
+}
+
+/// Opens a new QUIC connection to `peer` on its own socket.
+async fn connect_to(
+    binding: SocketBinding,
+    client_config: quinn::ClientConfig,
+    peer: SocketAddr,
+    server_name: String,
+) -> Result<(Endpoint, quinn::Connection), String> {
+    let socket = binding
+        .udp_socket(peer)
+        .await
+        .and_then(|socket| socket.into_std())
+        .map_err(|e| e.to_string())?;
+
+    let mut endpoint = Endpoint::new(
+        EndpointConfig::default(),
+        None,
+        socket,
+        Arc::new(TokioRuntime),
+    )
+    .map_err(|e| e.to_string())?;
+    endpoint.set_default_client_config(client_config);
+
+    let connection = endpoint
+        .connect(peer, &server_name)
+        .map_err(|e| e.to_string())?
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok((endpoint, connection))
+}
+
+/// Streams the response body from an HTTP/3 request stream.
+struct ResponseBody {
+    stream: Box<RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>>,
+    done: bool,
+}
+
+impl futures_core::Stream for ResponseBody {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(self.stream.poll_recv_data(cx)) {
+            Ok(Some(mut chunk)) => Poll::Ready(Some(Ok(chunk.copy_to_bytes(chunk.remaining())))),
+            Ok(None) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Err(err) => {
+                self.done = true;
+                Poll::Ready(Some(Err(io::Error::other(err))))
+            }
+        }
+    }
+}
+
+/// Wraps the response body in a decoder for its `Content-Encoding`, the same way `reqwest` does for its own responses.
+pub(crate) fn decode_body<S>(headers: &mut HeaderMap, body: S) -> reqwest::Body
+where
+    S: futures_core::Stream<Item = io::Result<Bytes>> + Send + 'static,
+{
+    let encoding = headers
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_ascii_lowercase());
+
+    let reader = StreamReader::new(body);
+    let body = match encoding.as_deref() {
+        Some("gzip") => reqwest::Body::wrap_stream(ReaderStream::new(GzipDecoder::new(reader))),
+        Some("br") => reqwest::Body::wrap_stream(ReaderStream::new(BrotliDecoder::new(reader))),
+        Some("zstd") => reqwest::Body::wrap_stream(ReaderStream::new(ZstdDecoder::new(reader))),
+        Some("deflate") => reqwest::Body::wrap_stream(ReaderStream::new(ZlibDecoder::new(reader))),
+        _ => return reqwest::Body::wrap_stream(reader.into_inner()),
+    };
+
+    headers.remove(CONTENT_ENCODING);
+    headers.remove(CONTENT_LENGTH);
+    body
+}
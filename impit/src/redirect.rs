@@ -0,0 +1,537 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use reqwest::{
+    header::{HeaderMap, LOCATION, REFERRER_POLICY},
+    Response, StatusCode, Version,
+};
+use url::Url;
+
+use crate::{
+    fingerprint::HeaderPosition,
+    http_headers::insert_header,
+    navigation::{FetchSite, Referrer, ReferrerPolicy},
+    request::{ImpitBody, ImpitRequest},
+};
+
+/// The most redirects a [`RedirectPolicy`] can follow, like in the browsers.
+pub const MAX_POLICY_REDIRECTS: usize = 20;
+
+/// The headers describing the request body, dropped when a redirect changes the method to `GET`.
+const BODY_HEADERS: [&str; 5] = [
+    "content-type",
+    "content-length",
+    "content-encoding",
+    "content-language",
+    "content-location",
+];
+
+/// The headers carrying credentials, dropped on the redirects to a different origin.
+const CREDENTIAL_HEADERS: [&str; 4] = [
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "www-authenticate",
+];
+
+/// A redirect response followed by an [`Impit`](crate::impit::Impit) instance.
+#[derive(Debug, Clone)]
+pub struct RedirectHop {
+    /// The method of the request that was redirected.
+    pub method: String,
+    /// The URL of the request that was redirected.
+    pub url: Url,
+    /// The status code of the redirect response.
+    pub status: StatusCode,
+    /// The HTTP version of the redirect response.
+    pub version: Version,
+    /// The headers of the redirect response, including the `Location` and `Set-Cookie` headers.
+    pub headers: HeaderMap,
+    /// The time between sending the request and receiving the redirect response headers (including the retries).
+    pub elapsed: Duration,
+}
+
+impl RedirectHop {
+    pub(crate) fn new(method: String, response: &Response, elapsed: Duration) -> Self {
+        RedirectHop {
+            method,
+            url: response.url().clone(),
+            status: response.status(),
+            version: response.version(),
+            headers: response.headers().clone(),
+            elapsed,
+        }
+    }
+
+    /// The `Location` header of the redirect response.
+    pub fn location(&self) -> Option<&str> {
+        self.headers.get(LOCATION)?.to_str().ok()
+    }
+}
+
+/// The redirects followed before the final response, in order.
+///
+/// Available in the [`reqwest::Response::extensions`] of all responses made by an [`Impit`](crate::impit::Impit)
+/// instance. The history is empty if the response wasn't redirected.
+///
+/// ### Example
+/// ```rust,no_run
+/// # async fn run() -> Result<(), impit::errors::ImpitError> {
+/// use impit::{impit::Impit, redirect::RedirectHistory};
+/// use reqwest::cookie::Jar;
+///
+/// let impit = Impit::<Jar>::default();
+/// let response = impit.get("https://example.com".to_string(), None, None).await?;
+///
+/// if let Some(history) = response.extensions().get::<RedirectHistory>() {
+///     for hop in history.iter() {
+///         println!("{} {} -> {:?}", hop.status, hop.url, hop.location());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RedirectHistory(pub Vec<RedirectHop>);
+
+impl std::ops::Deref for RedirectHistory {
+    type Target = [RedirectHop];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A custom redirect policy, used with [`RedirectBehavior::Custom`](crate::impit::RedirectBehavior::Custom).
+///
+/// The callback is called for every redirect response (with a `Location` header) and decides whether
+/// (and where) the redirect is followed. Like in the browsers, at most [`MAX_POLICY_REDIRECTS`] redirects are
+/// followed, then the request fails with [`ImpitError::TooManyRedirects`](crate::errors::ImpitError::TooManyRedirects).
+/// [`RedirectAttempt::previous`] can be used to enforce a lower limit.
+///
+/// ### Example
+/// ```rust
+/// use impit::{
+///     impit::{Impit, RedirectBehavior},
+///     redirect::{RedirectAction, RedirectPolicy},
+/// };
+/// use reqwest::cookie::Jar;
+///
+/// let policy = RedirectPolicy::new(|attempt| {
+///     if attempt.previous.len() >= 5 {
+///         RedirectAction::Error("too many redirects".to_string())
+///     } else if attempt.target.path().starts_with("/login") {
+///         RedirectAction::Stop
+///     } else if attempt.target.host_str() != attempt.request.url.host_str() {
+///         RedirectAction::Error(format!("cross-host redirect to {}", attempt.target))
+///     } else {
+///         RedirectAction::Follow
+///     }
+/// });
+///
+/// let impit = Impit::<Jar>::builder()
+///     .with_redirect(RedirectBehavior::Custom(policy))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RedirectPolicy(Arc<dyn Fn(&RedirectAttempt<'_>) -> RedirectAction + Send + Sync>);
+
+impl RedirectPolicy {
+    /// Creates a new redirect policy from a callback.
+    pub fn new(
+        callback: impl Fn(&RedirectAttempt<'_>) -> RedirectAction + Send + Sync + 'static,
+    ) -> Self {
+        RedirectPolicy(Arc::new(callback))
+    }
+
+    pub(crate) fn decide(&self, attempt: &RedirectAttempt<'_>) -> RedirectAction {
+        (self.0)(attempt)
+    }
+}
+
+impl fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RedirectPolicy")
+    }
+}
+
+/// A redirect response passed to a [`RedirectPolicy`].
+pub struct RedirectAttempt<'a> {
+    /// The request that was redirected.
+    pub request: &'a ImpitRequest,
+    /// The redirect response.
+    pub response: &'a Response,
+    /// The URL the redirect points to, resolved against the request URL.
+    pub target: &'a Url,
+    /// The redirects followed before this one, oldest first.
+    pub previous: &'a [RedirectHop],
+}
+
+/// What to do with a redirect, returned by a [`RedirectPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedirectAction {
+    /// Follow the redirect.
+    Follow,
+    /// Follow the redirect, but to another URL.
+    ///
+    /// Fails the request with [`ImpitError::UrlProtocolError`](crate::errors::ImpitError::UrlProtocolError)
+    /// if the URL isn't `http` or `https`.
+    FollowTo(Url),
+    /// Don't follow the redirect, return the redirect response instead.
+    Stop,
+    /// Fail the request with [`ImpitError::RedirectRejected`](crate::errors::ImpitError::RedirectRejected)
+    /// and the passed reason.
+    Error(String),
+}
+
+/// Returns the URL `response` redirects to, or `None` if it isn't a redirect.
+pub(crate) fn redirect_target(request: &ImpitRequest, response: &Response) -> Option<Url> {
+    if !matches!(
+        response.status(),
+        StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return None;
+    }
+
+    let location = response.headers().get(LOCATION)?.to_str().ok()?;
+    request.url.join(location).ok()
+}
+
+/// Returns the request to send to `url` after a redirect with `status` from `request` (whose body is
+/// `ImpitBody::Consumed` if it can't be sent again), or `None` if the redirect can't be followed.
+fn next_request(request: ImpitRequest, status: StatusCode, url: Url) -> Option<ImpitRequest> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let ImpitRequest {
+        url: previous_url,
+        mut method,
+        mut headers,
+        mut body,
+    } = request;
+
+    let is_head = method.eq_ignore_ascii_case("HEAD");
+    let is_post = method.eq_ignore_ascii_case("POST");
+    if (status == StatusCode::SEE_OTHER && !is_head)
+        || (matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND) && is_post)
+    {
+        method = "GET".to_string();
+        body = ImpitBody::Empty;
+        headers.retain(|(name, _)| !BODY_HEADERS.contains(&name.to_ascii_lowercase().as_str()));
+    } else if !body.is_sendable() {
+        // Streamed bodies can't be replayed, so the redirect response is returned instead.
+        return None;
+    }
+
+    if url.origin() != previous_url.origin() {
+        headers
+            .retain(|(name, _)| !CREDENTIAL_HEADERS.contains(&name.to_ascii_lowercase().as_str()));
+    }
+
+    Some(ImpitRequest {
+        url,
+        body,
+        headers,
+        method,
+    })
+}
+
+/// Returns the value of the `name` header, matched case-insensitively.
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Replaces the value of the `name` header in place, or appends the header if it's missing.
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: String) {
+    match headers
+        .iter_mut()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+    {
+        Some((_, current)) => *current = value,
+        None => headers.push((name.to_string(), value)),
+    }
+}
+
+fn remove_header(headers: &mut Vec<(String, String)>, name: &str) {
+    headers.retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+}
+
+/// The state of a redirect chain, used to update the headers of the redirected requests like the browsers do.
+///
+/// Besides the method and body changes (see [`next_request`]), the `Referer` is recomputed for every hop under the
+/// referrer policy (updated by the `Referrer-Policy` headers of the redirect responses), `Sec-Fetch-Site` reflects
+/// the least trusted hop of the chain and `Origin` becomes `null` after a cross-origin hop. The `Referer` is computed
+/// from the full URL of the referring document, so a hop back to its origin can send more than the first request
+/// did. The other headers the first request doesn't have (e.g. because the fingerprint doesn't send them) are
+/// never added.
+pub(crate) struct RedirectChain {
+    /// The URLs of the requests sent so far.
+    urls: Vec<Url>,
+    /// The full URL of the document the first request was sent from.
+    referrer: Option<Url>,
+    policy: ReferrerPolicy,
+    site: Option<FetchSite>,
+    /// Where the `Referer` header goes if an earlier hop dropped it.
+    referer_position: HeaderPosition,
+}
+
+impl RedirectChain {
+    pub(crate) fn new(
+        request: &ImpitRequest,
+        referrer: Option<Referrer>,
+        referer_position: HeaderPosition,
+    ) -> Self {
+        let (referrer, policy) = match referrer {
+            Some(Referrer { url, policy }) => (Some(url), policy),
+            None => (None, ReferrerPolicy::default()),
+        };
+        RedirectChain {
+            urls: vec![request.url.clone()],
+            referrer,
+            policy,
+            site: header(&request.headers, "sec-fetch-site").and_then(FetchSite::from_header),
+            referer_position,
+        }
+    }
+
+    /// Returns the request to send to `url` after the redirect `response` to `request`,
+    /// or `None` if the redirect can't be followed.
+    pub(crate) fn next_request(
+        &mut self,
+        request: ImpitRequest,
+        response: &Response,
+        url: Url,
+    ) -> Option<ImpitRequest> {
+        let previous_url = request.url.clone();
+        let previous_method = request.method.clone();
+        let mut next = next_request(request, response.status(), url)?;
+
+        if let Some(policy) = response
+            .headers()
+            .get(REFERRER_POLICY)
+            .and_then(|value| value.to_str().ok())
+            .and_then(ReferrerPolicy::from_header)
+        {
+            self.policy = policy;
+        }
+        if let Some(referrer) = &self.referrer {
+            match self.policy.referrer(referrer, &next.url) {
+                Some(value) if header(&next.headers, "referer").is_some() => {
+                    set_header(&mut next.headers, "Referer", value)
+                }
+                Some(value) => {
+                    insert_header(&mut next.headers, "Referer", value, &self.referer_position)
+                }
+                None => remove_header(&mut next.headers, "referer"),
+            }
+        }
+
+        if next.method != previous_method {
+            // The navigations switched to `GET` don't send `Origin`.
+            remove_header(&mut next.headers, "origin");
+        } else if previous_url.origin() != next.url.origin()
+            && header(&next.headers, "origin").is_some()
+        {
+            set_header(&mut next.headers, "Origin", "null".to_string());
+        }
+
+        if let Some(site) = &mut self.site {
+            for url in &self.urls {
+                let relation = FetchSite::between(url, &next.url);
+                if relation != FetchSite::SameOrigin {
+                    *site = (*site).max(relation);
+                }
+            }
+            set_header(
+                &mut next.headers,
+                "Sec-Fetch-Site",
+                site.as_str().to_string(),
+            );
+        }
+
+        self.urls.push(next.url.clone());
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    fn request(method: &str, body: ImpitBody) -> ImpitRequest {
+        ImpitRequest {
+            url: Url::parse("https://example.com/form").unwrap(),
+            body,
+            headers: vec![
+                ("Content-Type".to_string(), "text/plain".to_string()),
+                ("Authorization".to_string(), "Bearer token".to_string()),
+            ],
+            method: method.to_string(),
+        }
+    }
+
+    fn redirect(status: u16, location: &str) -> Response {
+        Response::from(
+            http::Response::builder()
+                .status(status)
+                .header(LOCATION, location)
+                .body("")
+                .unwrap(),
+        )
+    }
+
+    fn follow(request: ImpitRequest, response: &Response) -> Option<ImpitRequest> {
+        let url = redirect_target(&request, response)?;
+        next_request(request, response.status(), url)
+    }
+
+    #[test]
+    fn test_post_redirects_switch_to_get() {
+        let next = follow(request("POST", "data".into()), &redirect(302, "/done")).unwrap();
+
+        assert_eq!(next.method, "GET");
+        assert_eq!(next.url.as_str(), "https://example.com/done");
+        assert!(matches!(next.body, ImpitBody::Empty));
+        assert_eq!(
+            next.headers,
+            vec![("Authorization".to_string(), "Bearer token".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_temporary_redirects_keep_the_method() {
+        let next = follow(
+            request("PUT", "data".into()),
+            &redirect(307, "https://other.example.com/upload"),
+        )
+        .unwrap();
+
+        assert_eq!(next.method, "PUT");
+        assert!(matches!(next.body, ImpitBody::Bytes(_)));
+        assert_eq!(
+            next.headers,
+            vec![("Content-Type".to_string(), "text/plain".to_string())]
+        );
+
+        assert!(follow(request("PUT", ImpitBody::Consumed), &redirect(308, "/")).is_none());
+        assert!(follow(request("GET", ImpitBody::Empty), &redirect(200, "/")).is_none());
+        assert!(follow(
+            request("GET", ImpitBody::Empty),
+            &redirect(302, "ftp://example.com/")
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_redirect_chain_updates_navigation_headers() {
+        let mut request = request("POST", "data".into());
+        request.headers = vec![
+            ("Origin".to_string(), "https://example.com".to_string()),
+            (
+                "Referer".to_string(),
+                "https://example.com/form?step=2".to_string(),
+            ),
+            ("Sec-Fetch-Site".to_string(), "same-origin".to_string()),
+        ];
+        let referrer = Referrer {
+            url: Url::parse("https://example.com/form?step=2").unwrap(),
+            policy: ReferrerPolicy::default(),
+        };
+        let mut chain = RedirectChain::new(&request, Some(referrer), HeaderPosition::End);
+
+        let response = redirect(307, "https://login.example.com/");
+        let target = redirect_target(&request, &response).unwrap();
+        let next = chain.next_request(request, &response, target).unwrap();
+        assert_eq!(
+            next.headers,
+            vec![
+                ("Origin".to_string(), "null".to_string()),
+                ("Referer".to_string(), "https://example.com/".to_string()),
+                ("Sec-Fetch-Site".to_string(), "same-site".to_string()),
+            ]
+        );
+
+        let mut response = redirect(303, "https://other.org/done");
+        response
+            .headers_mut()
+            .insert(REFERRER_POLICY, "no-referrer".parse().unwrap());
+        let target = redirect_target(&next, &response).unwrap();
+        let next = chain.next_request(next, &response, target).unwrap();
+        assert_eq!(next.method, "GET");
+        assert_eq!(
+            next.headers,
+            vec![("Sec-Fetch-Site".to_string(), "cross-site".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_redirect_chain_recovers_the_full_referrer() {
+        let mut request = request("GET", ImpitBody::Empty);
+        request.url = Url::parse("https://other.org/track").unwrap();
+        request.headers = vec![("Referer".to_string(), "https://example.com/".to_string())];
+        let referrer = Referrer {
+            url: Url::parse("https://example.com/products?page=2").unwrap(),
+            policy: ReferrerPolicy::default(),
+        };
+        let mut chain = RedirectChain::new(&request, Some(referrer), HeaderPosition::End);
+
+        // Back on the document's origin, the full URL is sent again.
+        let response = redirect(302, "https://example.com/cart");
+        let target = redirect_target(&request, &response).unwrap();
+        let next = chain.next_request(request, &response, target).unwrap();
+        assert_eq!(
+            next.headers,
+            vec![(
+                "Referer".to_string(),
+                "https://example.com/products?page=2".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_custom_policy_sees_the_redirect() {
+        let policy = RedirectPolicy::new(|attempt| {
+            if attempt.target.path() == "/login" {
+                RedirectAction::Stop
+            } else {
+                RedirectAction::Follow
+            }
+        });
+
+        let request = request("GET", ImpitBody::Empty);
+        let response = redirect(302, "/login");
+        let target = redirect_target(&request, &response).unwrap();
+        let attempt = RedirectAttempt {
+            request: &request,
+            response: &response,
+            target: &target,
+            previous: &[],
+        };
+
+        assert_eq!(policy.decide(&attempt), RedirectAction::Stop);
+    }
+
+    #[test]
+    fn test_redirect_hop_keeps_the_response_metadata() {
+        let hop = RedirectHop::new(
+            "POST".to_string(),
+            &redirect(303, "/done"),
+            Duration::from_millis(20),
+        );
+
+        assert_eq!(hop.method, "POST");
+        assert_eq!(hop.status, StatusCode::SEE_OTHER);
+        assert_eq!(hop.location(), Some("/done"));
+        assert_eq!(hop.elapsed, Duration::from_millis(20));
+    }
+}
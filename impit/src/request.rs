@@ -2,8 +2,17 @@ use std::time::Duration;
 
 use bytes::Bytes;
 use futures_core::TryStream;
+use http::Method;
 use url::Url;
 
+use crate::{
+    errors::ImpitError,
+    fingerprint::RequestDestination,
+    impit::RedirectBehavior,
+    retry::RetryPolicy,
+    routing::{ConnectTo, ServerName},
+};
+
 /// A struct that holds the request options.
 ///
 /// Unlike the [`ImpitBuilder`](crate::impit::ImpitBuilder) struct, these options are specific to a single request.
@@ -11,9 +20,11 @@ use url::Url;
 /// Used by the [`Impit`](crate::impit::Impit) struct's methods.
 #[derive(Debug, Clone, Default)]
 pub struct RequestOptions {
-    /// A `Vec` of string pairs that represent custom HTTP request headers. These take precedence over the headers set in [`ImpitBuilder`](crate::impit::ImpitBuilder)
-    /// (both from the `with_headers` and the `with_browser` methods).
-    pub headers: Vec<(String, String)>,
+    /// The operations on the HTTP request headers, applied in order after the ones set in [`ImpitBuilder`](crate::impit::ImpitBuilder)
+    /// (both from the `with_headers` and the `with_browser` methods), so these take precedence.
+    ///
+    /// This used to be a list of `(name, value)` pairs, which now convert to [`HeaderOp::Set`] with `.into()`.
+    pub headers: Vec<HeaderOp>,
     /// The per-request timeout, with three possible states:
     ///
     /// - `None` — inherit the client-level default timeout set via [`ImpitBuilder::with_default_timeout`](crate::impit::ImpitBuilder::with_default_timeout).
@@ -24,6 +35,77 @@ pub struct RequestOptions {
     ///
     /// If [`ImpitBuilder::with_http3`](crate::impit::ImpitBuilder::with_http3) wasn't called, this option will cause [`ErrorType::Http3Disabled`](crate::impit::ErrorType::Http3Disabled) errors.
     pub http3_prior_knowledge: bool,
+    /// Rules to open the connection for this request to a different host or port, checked before the ones set with
+    /// [`ImpitBuilder::with_connect_to`](crate::impit::ImpitBuilder::with_connect_to).
+    pub connect_to: Vec<ConnectTo>,
+    /// Overrides the TLS server name set with [`ImpitBuilder::with_server_name`](crate::impit::ImpitBuilder::with_server_name).
+    ///
+    /// Use [`ServerName::Disabled`] to send the request without the SNI extension.
+    pub server_name: Option<ServerName>,
+    /// Overrides the retry policy set with [`ImpitBuilder::with_retry`](crate::impit::ImpitBuilder::with_retry).
+    pub retry: Option<RetryPolicy>,
+    /// Overrides the redirect behavior set with [`ImpitBuilder::with_redirect`](crate::impit::ImpitBuilder::with_redirect).
+    pub redirect: Option<RedirectBehavior>,
+    /// What the request is fetching. The fingerprint's `Accept`, `Sec-Fetch-*`, `Upgrade-Insecure-Requests` and `Priority`
+    /// headers are adjusted accordingly, see [`BrowserFingerprint::headers_for`](crate::fingerprint::BrowserFingerprint::headers_for).
+    ///
+    /// Defaults to [`RequestDestination::Document`], i.e. a top-level navigation.
+    pub destination: RequestDestination,
+}
+
+/// An operation on the request headers.
+///
+/// The operations are applied in order on top of the impersonated browser's headers, first the ones set with
+/// [`ImpitBuilder::with_headers`](crate::impit::ImpitBuilder::with_headers), then the ones in [`RequestOptions::headers`].
+/// Header names are matched case-insensitively.
+///
+/// A `(name, value)` pair converts to [`HeaderOp::Set`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderOp {
+    /// Replaces all the values of the header with this one, keeping the position of the header if it's set already.
+    Set(String, String),
+    /// Adds another value of the header after the existing ones, e.g. for repeated `Accept` or `X-Forwarded-For` headers.
+    Append(String, String),
+    /// Removes all the values of the header, including the impersonated one.
+    Remove(String),
+    /// Sends the header with an empty value.
+    SetEmpty(String),
+}
+
+impl HeaderOp {
+    /// The name of the header the operation works on.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Set(name, _) | Self::Append(name, _) => name,
+            Self::Remove(name) | Self::SetEmpty(name) => name,
+        }
+    }
+
+    /// Converts a list of headers with optional values, as passed in the bindings, to header operations.
+    ///
+    /// A `None` value removes the header, an empty value sends it empty and a repeated name adds another value of
+    /// the header.
+    ///
+    /// Note that an empty value used to remove the header, pass `None` for that instead.
+    pub fn from_entries<I: IntoIterator<Item = (String, Option<String>)>>(entries: I) -> Vec<Self> {
+        let mut ops: Vec<Self> = vec![];
+        for (name, value) in entries {
+            let repeated = ops.iter().any(|op| op.name().eq_ignore_ascii_case(&name));
+            ops.push(match value {
+                None => Self::Remove(name),
+                Some(value) if repeated => Self::Append(name, value),
+                Some(value) if value.is_empty() => Self::SetEmpty(name),
+                Some(value) => Self::Set(name, value),
+            });
+        }
+        ops
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> From<(N, V)> for HeaderOp {
+    fn from((name, value): (N, V)) -> Self {
+        Self::Set(name.into(), value.into())
+    }
 }
 
 /// The body of a request.
@@ -74,6 +156,24 @@ impl ImpitBody {
         }
     }
 
+    /// Returns a copy of the body, or `None` if it's streamed and can only be sent once.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        match self {
+            Self::Empty => Some(Self::Empty),
+            Self::Bytes(bytes) => Some(Self::Bytes(bytes.clone())),
+            Self::Stream(_) | Self::Consumed => None,
+        }
+    }
+
+    /// Returns a copy of a fully buffered body, or `None` for streamed bodies.
+    pub(crate) fn to_bytes(&self) -> Option<Bytes> {
+        match self {
+            Self::Empty => Some(Bytes::new()),
+            Self::Bytes(bytes) => Some(bytes.clone()),
+            Self::Stream(_) | Self::Consumed => None,
+        }
+    }
+
     /// Whether a body is still available to send - `false` only for a streamed body that has
     /// already been consumed by a previous attempt.
     pub(crate) fn is_sendable(&self) -> bool {
@@ -94,10 +194,64 @@ pub struct ImpitRequest {
     pub method: String,
 }
 
+/// The methods that can't be used for a request, like the forbidden methods of the Fetch standard.
+/// `TRACE` requests can still be sent with [`Impit::trace`](crate::impit::Impit::trace).
+const FORBIDDEN_METHODS: [&str; 3] = ["CONNECT", "TRACE", "TRACK"];
+
+/// Parses a request method, which can be any valid HTTP token (e.g. `PROPFIND`, `PURGE` or `QUERY`).
+///
+/// The standard methods are matched case-insensitively and sent in uppercase, the other methods are sent as they are.
+/// The forbidden methods (`CONNECT`, `TRACE` and `TRACK`) are rejected with [`ImpitError::InvalidMethod`].
+pub(crate) fn parse_method(method: &str) -> Result<Method, ImpitError> {
+    if FORBIDDEN_METHODS
+        .iter()
+        .any(|forbidden| forbidden.eq_ignore_ascii_case(method))
+    {
+        return Err(ImpitError::InvalidMethod(method.to_string()));
+    }
+
+    let standard = [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::DELETE,
+        Method::PATCH,
+        Method::HEAD,
+        Method::OPTIONS,
+    ];
+    if let Some(standard) = standard
+        .into_iter()
+        .find(|standard| standard.as_str().eq_ignore_ascii_case(method))
+    {
+        return Ok(standard);
+    }
+
+    Method::from_bytes(method.as_bytes()).map_err(|_| ImpitError::InvalidMethod(method.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn methods_are_parsed_as_tokens() {
+        assert_eq!(parse_method("patch").unwrap(), Method::PATCH);
+        assert_eq!(parse_method("PROPFIND").unwrap().as_str(), "PROPFIND");
+        assert_eq!(parse_method("Query").unwrap().as_str(), "Query");
+        assert!(parse_method("").is_err());
+        assert!(parse_method("GET /").is_err());
+    }
+
+    #[test]
+    fn forbidden_methods_are_rejected() {
+        for method in ["CONNECT", "connect", "TRACE", "Trace", "TRACK"] {
+            assert!(matches!(
+                parse_method(method),
+                Err(ImpitError::InvalidMethod(_))
+            ));
+        }
+    }
+
     #[test]
     fn buffered_bodies_can_be_sent_repeatedly() {
         let mut body = ImpitBody::from("hello");
@@ -0,0 +1,277 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::Method;
+use rand::Rng;
+use reqwest::{header::RETRY_AFTER, Response};
+
+use crate::errors::ImpitError;
+
+/// Which failed requests are retried, how many times, and how long to wait between the attempts.
+///
+/// The requests failing before they reach the server (connection errors, and the HTTP/2 streams refused with
+/// `REFUSED_STREAM`) are retried regardless of the method. The requests dropped by a `GOAWAY` frame are only
+/// retried for the idempotent methods, and the responses with one of the `statuses` only for the `methods`
+/// (by default the idempotent ones), as the server might have already processed the request.
+///
+/// Requests with a streamed body that has already been sent are never retried.
+///
+/// ### Example
+/// ```rust
+/// use std::time::Duration;
+/// use impit::retry::RetryPolicy;
+///
+/// let retry = RetryPolicy {
+///     max_attempts: 5,
+///     statuses: vec![429, 502, 503, 504],
+///     max_backoff: Duration::from_secs(10),
+///     ..RetryPolicy::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one. `1` disables the retries.
+    pub max_attempts: usize,
+    /// Whether to retry the requests that failed to connect to the server.
+    pub retry_connect_errors: bool,
+    /// Whether to retry the HTTP/2 requests the server refused to process (`REFUSED_STREAM` or `GOAWAY`).
+    pub retry_refused_streams: bool,
+    /// The response status codes to retry.
+    pub statuses: Vec<u16>,
+    /// The methods whose requests are retried after a response with one of the `statuses`.
+    pub methods: Vec<Method>,
+    /// The delay before the first retry, doubled for every further retry.
+    pub initial_backoff: Duration,
+    /// The longest delay between two attempts.
+    pub max_backoff: Duration,
+    /// Whether to randomize the delays, so that the clients failing at the same time don't retry at the same time.
+    pub jitter: bool,
+    /// Whether to wait as long as the `Retry-After` response header asks for, instead of the backoff.
+    ///
+    /// Responses asking for a longer delay than `max_backoff` are returned without retrying.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            retry_connect_errors: true,
+            retry_refused_streams: true,
+            statuses: vec![429, 503],
+            methods: vec![
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::TRACE,
+                Method::PUT,
+                Method::DELETE,
+            ],
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries the requests.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Returns how long to wait before retrying a `method` request whose `attempt`-th attempt ended with `result`,
+    /// or `None` if the request shouldn't be retried.
+    pub(crate) fn retry_delay(
+        &self,
+        attempt: usize,
+        method: &Method,
+        result: &Result<Response, ImpitError>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match result {
+            Err(ImpitError::ConnectError(_) | ImpitError::ConnectTimeout)
+                if self.retry_connect_errors =>
+            {
+                Some(self.backoff(attempt))
+            }
+            Err(ImpitError::RefusedStream(_)) if self.retry_refused_streams => {
+                Some(self.backoff(attempt))
+            }
+            Err(ImpitError::GoAway(_)) if self.retry_refused_streams && method.is_idempotent() => {
+                Some(self.backoff(attempt))
+            }
+            Err(_) => None,
+            Ok(response) => {
+                if !self.statuses.contains(&response.status().as_u16())
+                    || !self.methods.contains(method)
+                {
+                    return None;
+                }
+
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after)
+                    .filter(|_| self.respect_retry_after);
+
+                match retry_after {
+                    Some(delay) if delay > self.max_backoff => None,
+                    Some(delay) => Some(delay),
+                    None => Some(self.backoff(attempt)),
+                }
+            }
+        }
+    }
+
+    /// Returns the exponential backoff after the `attempt`-th attempt.
+    fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31) as u32;
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return backoff;
+        }
+
+        // "Equal jitter" keeps at least half of the backoff, so that the retries are never sent right away.
+        let half = backoff / 2;
+        half + half.mul_f64(rand::rng().random_range(0.0..=1.0))
+    }
+}
+
+/// Parses the `Retry-After` header value, either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = parse_http_date(value)?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parses an HTTP date in the preferred IMF-fixdate format, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let [day, month, year, time, "GMT"] = date.split(' ').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|name| *name == month)? as i64
+        + 1;
+    let day: i64 = day.parse().ok()?;
+    let year: i64 = year.parse().ok()?;
+
+    let [hours, minutes, seconds] = time.split(':').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let seconds_of_day = hours.parse::<u64>().ok()? * 3600
+        + minutes.parse::<u64>().ok()? * 60
+        + seconds.parse::<u64>().ok()?;
+
+    // Days since the Unix epoch for the proleptic Gregorian calendar (Howard Hinnant's `days_from_civil`).
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146097 + day_of_era - 719468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86400 + seconds_of_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_parsing() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784111777))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let retry = RetryPolicy {
+            jitter: false,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(retry.backoff(1), Duration::from_secs(1));
+        assert_eq!(retry.backoff(3), Duration::from_secs(4));
+        assert_eq!(retry.backoff(10), Duration::from_secs(5));
+
+        let retry = RetryPolicy {
+            jitter: true,
+            ..retry
+        };
+        let backoff = retry.backoff(2);
+        assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_connect_errors_are_retried_for_any_method() {
+        let retry = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let error = Err(ImpitError::ConnectError("refused".to_string()));
+
+        assert!(retry.retry_delay(1, &Method::POST, &error).is_some());
+        assert!(retry.retry_delay(3, &Method::POST, &error).is_none());
+        assert!(retry
+            .retry_delay(1, &Method::GET, &Err(ImpitError::ReadTimeout))
+            .is_none());
+        assert!(RetryPolicy::none()
+            .retry_delay(1, &Method::GET, &error)
+            .is_none());
+    }
+
+    #[test]
+    fn test_go_away_is_retried_for_idempotent_methods() {
+        let retry = RetryPolicy::default();
+        let refused = Err(ImpitError::RefusedStream("REFUSED_STREAM".to_string()));
+        let go_away = Err(ImpitError::GoAway("NO_ERROR".to_string()));
+
+        assert!(retry.retry_delay(1, &Method::POST, &refused).is_some());
+        assert!(retry.retry_delay(1, &Method::GET, &go_away).is_some());
+        assert!(retry.retry_delay(1, &Method::POST, &go_away).is_none());
+        assert!(RetryPolicy {
+            retry_refused_streams: false,
+            ..RetryPolicy::default()
+        }
+        .retry_delay(1, &Method::GET, &go_away)
+        .is_none());
+    }
+}
@@ -0,0 +1,295 @@
+use std::str::FromStr;
+
+use url::Url;
+
+use crate::errors::ImpitError;
+
+/// A rule to open the connections for a host to a different host or port, like curl's `--connect-to`.
+///
+/// Unlike rewriting the request URL, the requests keep the original `Host` header (`:authority`), the TLS server name
+/// and the cookies - only the address the connection is made to changes.
+///
+/// The rules are parsed from strings in the `host:port:connect_host:connect_port` format. Empty fields match any host
+/// (or port) on the left side, and keep the original host (or port) on the right side.
+/// IPv6 addresses have to be enclosed in square brackets.
+///
+/// ### Example
+/// ```rust
+/// use impit::routing::ConnectTo;
+///
+/// // Reach the origin server behind a CDN directly
+/// let origin: ConnectTo = "example.com:443:origin.example.net:8443".parse().unwrap();
+///
+/// // Send all the requests for any host to a load-balancer backend
+/// let backend: ConnectTo = "::[2001:db8::10]:".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConnectTo {
+    host: Option<String>,
+    port: Option<u16>,
+    connect_host: Option<String>,
+    connect_port: Option<u16>,
+}
+
+impl ConnectTo {
+    /// Creates a new rule. `None` in `host` or `port` matches any host or port, `None` in `connect_host`
+    /// or `connect_port` keeps the host or port from the request URL.
+    pub fn new(
+        host: Option<String>,
+        port: Option<u16>,
+        connect_host: Option<String>,
+        connect_port: Option<u16>,
+    ) -> Self {
+        ConnectTo {
+            host: host.map(|host| normalize_host(&host)),
+            port,
+            connect_host: connect_host.map(|host| normalize_host(&host)),
+            connect_port,
+        }
+    }
+
+    fn matches(&self, host: &str, port: u16) -> bool {
+        self.host
+            .as_deref()
+            .is_none_or(|rule_host| rule_host == host)
+            && self.port.is_none_or(|rule_port| rule_port == port)
+    }
+}
+
+impl FromStr for ConnectTo {
+    type Err = ImpitError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ImpitError::ReqwestError(format!("Invalid connect-to rule: {value}"));
+
+        let fields = split_fields(value).ok_or_else(invalid)?;
+        let [host, port, connect_host, connect_port] = fields.as_slice() else {
+            return Err(invalid());
+        };
+
+        let parse_port = |port: &str| match port {
+            "" => Ok(None),
+            port => port.parse::<u16>().map(Some).map_err(|_| invalid()),
+        };
+        let parse_host = |host: &str| (!host.is_empty()).then(|| host.to_string());
+
+        Ok(ConnectTo::new(
+            parse_host(host),
+            parse_port(port)?,
+            parse_host(connect_host),
+            parse_port(connect_port)?,
+        ))
+    }
+}
+
+/// Splits the `:`-separated fields of a [`ConnectTo`] rule, keeping the bracketed IPv6 addresses intact.
+fn split_fields(value: &str) -> Option<Vec<&str>> {
+    let mut fields = vec![];
+    let mut rest = value;
+
+    loop {
+        let end = if rest.starts_with('[') {
+            rest.find(']')? + 1
+        } else {
+            rest.find(':').unwrap_or(rest.len())
+        };
+
+        fields.push(&rest[..end]);
+        match rest[end..].strip_prefix(':') {
+            Some(next) => rest = next,
+            None if end == rest.len() => return Some(fields),
+            None => return None,
+        }
+    }
+}
+
+pub(crate) fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase()
+}
+
+/// The server name sent in the TLS handshake (SNI).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ServerName {
+    /// Send the hostname from the request URL, like the browsers do.
+    #[default]
+    Host,
+    /// Send a different server name than the hostname from the request URL.
+    ///
+    /// The server certificate is verified against this name. It's only sent to the host from the request URL,
+    /// other hosts (e.g. after a redirect) get their own hostname.
+    ///
+    /// Over TCP, these requests are sent over HTTP/1.1 only, as `reqwest` takes the server name from the URL.
+    /// The request is made for the server name, and the `Host` header carries the hostname from the URL instead.
+    Custom(String),
+    /// Don't send the SNI extension at all.
+    Disabled,
+}
+
+/// Where the connection for a request is opened to, and which server name is sent in the TLS handshake.
+///
+/// Requests with a non-default route are sent with clients built for that route, as `reqwest` only allows
+/// setting these per client.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Route {
+    /// The URL host, with the host and port to connect to instead.
+    pub(crate) connect_to: Option<(String, Option<String>, Option<u16>)>,
+    pub(crate) server_name: ServerName,
+    /// The connection target host and port, if the DNS overrides of the host depend on the port.
+    pub(crate) resolve_port: Option<(String, u16)>,
+    /// With a custom server name, the URL host, with the host and port the connections actually go to.
+    pub(crate) server_name_target: Option<(String, String, u16)>,
+}
+
+impl Route {
+    /// Picks the route for a request to `url`, using the first matching [`ConnectTo`] rule.
+    pub(crate) fn for_url<'a>(
+        url: &Url,
+        rules: impl IntoIterator<Item = &'a ConnectTo>,
+        server_name: ServerName,
+    ) -> Self {
+        let host = normalize_host(url.host_str().unwrap_or_default());
+        let port = url.port_or_known_default().unwrap_or(443);
+
+        let connect_to = rules
+            .into_iter()
+            .find(|rule| rule.matches(&host, port))
+            .filter(|rule| rule.connect_host.is_some() || rule.connect_port.is_some())
+            .map(|rule| (host, rule.connect_host.clone(), rule.connect_port));
+
+        Route {
+            connect_to,
+            server_name,
+            resolve_port: None,
+            server_name_target: None,
+        }
+    }
+
+    /// Whether the request can be sent with the default clients.
+    pub(crate) fn is_default(&self) -> bool {
+        self.connect_to.is_none()
+            && self.server_name == ServerName::Host
+            && self.resolve_port.is_none()
+    }
+
+    /// Returns the host and port to open the connection for a request to `host:port` to.
+    ///
+    /// Other hosts (e.g. after a redirect) are connected to directly.
+    pub(crate) fn target(&self, host: &str, port: u16) -> (String, u16) {
+        match &self.connect_to {
+            Some((route_host, connect_host, connect_port)) if route_host == host => (
+                connect_host.clone().unwrap_or_else(|| host.to_string()),
+                connect_port.unwrap_or(port),
+            ),
+            _ => (host.to_string(), port),
+        }
+    }
+
+    /// Returns the URL `reqwest` has to request to send the custom server name in the TLS handshake,
+    /// or `None` if `url` can be requested as it is.
+    ///
+    /// The clients for this route resolve the server name to the addresses of the actual target.
+    pub(crate) fn tcp_url(&self, url: &Url) -> Option<Url> {
+        let ServerName::Custom(server_name) = &self.server_name else {
+            return None;
+        };
+        let (host, _, _) = self.server_name_target.as_ref()?;
+        if url.scheme() != "https" || normalize_host(url.host_str()?) != *host {
+            return None;
+        }
+
+        let mut tcp_url = url.clone();
+        tcp_url.set_host(Some(server_name)).ok()?;
+        Some(tcp_url)
+    }
+
+    /// Returns the server name for the TLS handshake with `host`.
+    pub(crate) fn server_name<'a>(&'a self, host: &'a str) -> &'a str {
+        match &self.server_name {
+            ServerName::Custom(server_name) => server_name,
+            ServerName::Host | ServerName::Disabled => host,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_to_parsing() {
+        let rule: ConnectTo = "Example.com:443:[2001:db8::1]:8443".parse().unwrap();
+        assert_eq!(
+            rule,
+            ConnectTo::new(
+                Some("example.com".to_string()),
+                Some(443),
+                Some("2001:db8::1".to_string()),
+                Some(8443)
+            )
+        );
+
+        assert_eq!(
+            "::backend.internal:".parse::<ConnectTo>().unwrap(),
+            ConnectTo::new(None, None, Some("backend.internal".to_string()), None)
+        );
+
+        assert!("example.com:443:backend".parse::<ConnectTo>().is_err());
+        assert!("example.com:https:backend:443"
+            .parse::<ConnectTo>()
+            .is_err());
+        assert!("example.com:443:[2001:db8::1:443"
+            .parse::<ConnectTo>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_route_uses_first_matching_rule() {
+        let rules: Vec<ConnectTo> = ["example.com:80::8080", "example.com::origin.internal:"]
+            .iter()
+            .map(|rule| rule.parse().unwrap())
+            .collect();
+
+        let url = Url::parse("https://example.com/").unwrap();
+        let route = Route::for_url(&url, &rules, ServerName::Host);
+        assert_eq!(
+            route.target("example.com", 443),
+            ("origin.internal".to_string(), 443)
+        );
+
+        let url = Url::parse("http://example.com/").unwrap();
+        let route = Route::for_url(&url, &rules, ServerName::Host);
+        assert_eq!(
+            route.target("example.com", 80),
+            ("example.com".to_string(), 8080)
+        );
+
+        let url = Url::parse("https://example.org/").unwrap();
+        assert!(Route::for_url(&url, &rules, ServerName::Host).is_default());
+    }
+
+    #[test]
+    fn test_custom_server_name_url() {
+        let mut route = Route::for_url(
+            &Url::parse("https://example.com/").unwrap(),
+            &[],
+            ServerName::Custom("front.example.net".to_string()),
+        );
+        route.server_name_target =
+            Some(("example.com".to_string(), "example.com".to_string(), 443));
+
+        let url = Url::parse("https://example.com:8443/path?query").unwrap();
+        assert_eq!(
+            route.tcp_url(&url).unwrap().as_str(),
+            "https://front.example.net:8443/path?query"
+        );
+        // Plain HTTP has no TLS handshake, and other hosts get their own hostname.
+        assert!(route
+            .tcp_url(&Url::parse("http://example.com/").unwrap())
+            .is_none());
+        assert!(route
+            .tcp_url(&Url::parse("https://example.org/").unwrap())
+            .is_none());
+    }
+}
@@ -87,6 +87,7 @@ pub struct TlsConfigBuilder {
     tls_fingerprint: Option<TlsFingerprint>,
     max_http_version: Version,
     ignore_tls_errors: bool,
+    sni: bool,
 }
 
 impl Default for TlsConfigBuilder {
@@ -95,6 +96,7 @@ impl Default for TlsConfigBuilder {
             tls_fingerprint: None,
             max_http_version: Version::HTTP_2,
             ignore_tls_errors: false,
+            sni: true,
         }
     }
 }
@@ -120,6 +122,11 @@ impl TlsConfigBuilder {
         self
     }
 
+    pub fn with_sni(&mut self, sni: bool) -> &mut Self {
+        self.sni = sni;
+        self
+    }
+
     pub fn build(self) -> rustls::ClientConfig {
         let ignore_tls_errors = self.ignore_tls_errors;
         let max_http_version = self.max_http_version;
@@ -224,6 +231,8 @@ impl TlsConfigBuilder {
             config.alpn_protocols = vec![b"h3".to_vec()];
         };
 
+        config.enable_sni = self.sni;
+
         config
     }
 }